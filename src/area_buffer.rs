@@ -1,6 +1,7 @@
 use crossterm::event::{
     KeyCode,
     KeyEvent,
+    KeyModifiers,
 };
 use unicode_width::UnicodeWidthChar;
 
@@ -40,11 +41,18 @@ impl AreaBuffer {
         self.calc_content_width();
     }
 
+    /// returns true if the key is handled
     pub fn process_key_event(
         &mut self,
-        KeyEvent { code, modifiers: _ }: KeyEvent,
-    ) {
+        KeyEvent { code, modifiers }: KeyEvent,
+    ) -> bool {
         match code {
+            KeyCode::Char(_)
+                if modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                return false;
+            }
             KeyCode::Char(c) => {
                 self.add_char(c);
             }
@@ -106,8 +114,9 @@ impl AreaBuffer {
                     self.calc_content_width();
                 }
             }
-            _ => (),
+            _ => return false,
         }
+        true
     }
 
     pub fn set_cursor_loc(&mut self, cursor_x: usize, cursor_y: usize) {
@@ -0,0 +1,227 @@
+//! Backends provide the renderer with the size of the screen, the events to be processed
+//! and the setup/teardown of the terminal.
+//!
+//! The default backend is the terminal via crossterm. The headless backend
+//! allows running the render loop without a TTY, which is useful for testing.
+use crate::{
+    command,
+    renderer::Viewport,
+};
+use crossterm::{
+    cursor,
+    event::{
+        self,
+        Event,
+    },
+    terminal,
+    Result,
+};
+#[cfg(feature = "async")]
+use futures::stream::{
+    LocalBoxStream,
+    StreamExt,
+};
+use std::{
+    collections::VecDeque,
+    io::Write,
+    time::Duration,
+};
+
+/// Provides the terminal size, the event source and the terminal setup
+/// used by the Renderer
+pub trait Backend {
+    /// returns the size of the screen in (columns, rows)
+    fn size(&self) -> Result<(u16, u16)>;
+
+    /// wait for the next event.
+    /// Returns None if there are no more events to be processed,
+    /// which will end the render loop.
+    fn read_event(&mut self) -> Result<Option<Event>>;
+
+    /// wait up to timeout for an event to be available,
+    /// returns true if the next call to `read_event` will not block.
+    ///
+    /// Backends which can not wait for events with a timeout always return true,
+    /// the timers of the renderer are then only checked in between events.
+    fn poll(&mut self, _timeout: Duration) -> Result<bool> {
+        Ok(true)
+    }
+
+    /// returns the stream of events used by `Renderer::run_async`,
+    /// the render loop ends when the stream ends.
+    ///
+    /// Backends without an event stream return None, `Renderer::run_async` then
+    /// checks `poll` without waiting in between its tasks and reads the events with `read_event`.
+    #[cfg(feature = "async")]
+    fn event_stream(
+        &mut self,
+    ) -> Option<LocalBoxStream<'static, Result<Event>>> {
+        None
+    }
+
+    /// returns the location of the cursor in (column, row),
+    /// which is where an inline viewport starts.
+    fn cursor_position(&self) -> Result<(u16, u16)> {
+        Ok((0, 0))
+    }
+
+    /// prepare the screen for the viewport before the render loop starts
+    fn init(&mut self, w: &mut dyn Write, viewport: Viewport) -> Result<()>;
+
+    /// restore the screen after the render loop ends
+    fn finalize(&mut self, w: &mut dyn Write, viewport: Viewport)
+        -> Result<()>;
+}
+
+/// The terminal backend using crossterm
+#[derive(Default, Debug, Clone, Copy)]
+pub struct CrosstermBackend;
+
+impl Backend for CrosstermBackend {
+    fn size(&self) -> Result<(u16, u16)> {
+        terminal::size()
+    }
+
+    fn read_event(&mut self) -> Result<Option<Event>> {
+        event::read().map(Some)
+    }
+
+    fn poll(&mut self, timeout: Duration) -> Result<bool> {
+        event::poll(timeout)
+    }
+
+    #[cfg(feature = "async")]
+    fn event_stream(
+        &mut self,
+    ) -> Option<LocalBoxStream<'static, Result<Event>>> {
+        Some(event::EventStream::new().boxed_local())
+    }
+
+    fn cursor_position(&self) -> Result<(u16, u16)> {
+        cursor::position()
+    }
+
+    fn init(&mut self, w: &mut dyn Write, viewport: Viewport) -> Result<()> {
+        match viewport {
+            Viewport::Fullscreen => command::init(w),
+            Viewport::Inline(_) => command::init_inline(),
+        }
+    }
+
+    fn finalize(
+        &mut self,
+        w: &mut dyn Write,
+        viewport: Viewport,
+    ) -> Result<()> {
+        match viewport {
+            Viewport::Fullscreen => command::finalize(w),
+            Viewport::Inline(_) => command::finalize_inline(w),
+        }
+    }
+}
+
+/// A backend that doesn't need a terminal.
+/// It has a fixed size and process a scripted list of events,
+/// the render loop ends when all of the events are processed.
+#[derive(Debug, Clone)]
+pub struct HeadlessBackend {
+    size: (u16, u16),
+    events: VecDeque<Event>,
+}
+
+impl HeadlessBackend {
+    /// create a headless backend with a fixed size and the events to be processed
+    pub fn new(width: u16, height: u16, events: Vec<Event>) -> Self {
+        HeadlessBackend {
+            size: (width, height),
+            events: events.into(),
+        }
+    }
+
+    /// add an event to the end of the scripted events
+    pub fn push_event(&mut self, event: Event) {
+        self.events.push_back(event);
+    }
+}
+
+impl Backend for HeadlessBackend {
+    fn size(&self) -> Result<(u16, u16)> {
+        Ok(self.size)
+    }
+
+    fn read_event(&mut self) -> Result<Option<Event>> {
+        Ok(self.events.pop_front())
+    }
+
+    fn init(&mut self, _w: &mut dyn Write, _viewport: Viewport) -> Result<()> {
+        Ok(())
+    }
+
+    fn finalize(
+        &mut self,
+        _w: &mut dyn Write,
+        _viewport: Viewport,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        Buffer,
+        FlexBox,
+        Renderer,
+        TextInput,
+        Widget,
+    };
+    use crossterm::event::{
+        KeyCode,
+        KeyModifiers,
+        MouseButton,
+        MouseEvent,
+    };
+
+    fn row_text(buf: &Buffer, y: usize, x: usize, len: usize) -> String {
+        (x..x + len)
+            .filter_map(|i| buf.get_cell(i, y))
+            .map(|cell| cell.symbol.clone())
+            .collect()
+    }
+
+    #[test]
+    fn headless_text_input() {
+        let mut root = FlexBox::<()>::new();
+        root.vertical();
+        root.add_child(Box::new(TextInput::new("Hello")));
+
+        let backend = HeadlessBackend::new(
+            20,
+            5,
+            vec![
+                Event::Mouse(MouseEvent::Down(
+                    MouseButton::Left,
+                    3,
+                    1,
+                    KeyModifiers::empty(),
+                )),
+                Event::Key(KeyCode::Char('X').into()),
+            ],
+        );
+        let mut out = vec![];
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            None,
+            &mut root,
+            Box::new(backend),
+        );
+        renderer.run().expect("must run");
+
+        let buf = renderer.buffer();
+        // the text input is focused, so it is drawn with a thick border
+        assert_eq!(row_text(buf, 0, 0, 3), "┏━━");
+        assert_eq!(row_text(buf, 1, 0, 8), "┃HelXlo ");
+        assert_eq!(row_text(buf, 2, 0, 3), "┗━━");
+    }
+}
@@ -13,13 +13,17 @@ use crossterm::{
         SetForegroundColor,
     },
 };
-use ito_canvas::unicode_canvas::Canvas;
 use std::{
     fmt,
     io::Write,
 };
 use unicode_width::UnicodeWidthStr;
 
+pub(crate) use snapshot::parse_color;
+pub use snapshot::SnapshotError;
+
+mod snapshot;
+
 /// Cell contains the attributes of the char used in the buffer.
 /// This information is needed when rendering each cell to the terminal
 #[derive(Clone, Default, PartialEq, Debug)]
@@ -34,6 +38,10 @@ pub struct Cell {
     pub attributes: Attributes,
 }
 
+/// The colors and attributes of a cell, used when rendering to determine
+/// whether the terminal style needs to be changed between cells
+type CellStyle = (Option<Color>, Option<Color>, Attributes);
+
 /// Contains a vec of cells.
 /// Buffer contains the information needed to render into the screen
 #[derive(PartialEq, Debug)]
@@ -99,6 +107,14 @@ impl Cell {
     pub fn background(&mut self, color: Color) {
         self.background_color = Some(color);
     }
+
+    fn style(&self) -> CellStyle {
+        (
+            self.foreground_color,
+            self.background_color,
+            self.attributes,
+        )
+    }
 }
 
 impl Buffer {
@@ -118,6 +134,14 @@ impl Buffer {
         })
     }
 
+    /// set the background color of all the cells
+    pub(crate) fn set_background(&mut self, color: Color) {
+        self.cells
+            .iter_mut()
+            .flatten()
+            .for_each(|cell| cell.background(color))
+    }
+
     /// set the character of this location with symbol
     pub fn set_symbol<S: ToString>(&mut self, x: usize, y: usize, symbol: S) {
         self.set_cell(x, y, Cell::new(symbol));
@@ -140,20 +164,18 @@ impl Buffer {
         }
     }
 
-    /// get the characters from the drawing canvas and
-    /// insert them into this buffer
-    pub(crate) fn write_canvas(&mut self, canvas: Canvas) {
-        canvas
-            .get_cells()
-            .for_each(|(x, y, ch)| self.set_symbol(x, y, ch))
-    }
-
-    /// set the cell at this location
+    /// set the cell at this location,
+    /// the cell keeps the background color if the new cell doesn't have one
     pub fn set_cell(&mut self, x: usize, y: usize, new_cell: Cell) {
         if let Some(line) = self.cells.get_mut(y) {
             if let Some(cell) = line.get_mut(x) {
                 let unicode_width = new_cell.unicode_width();
+                // the background shows through the cells which doesn't have their own
+                let background = cell.background_color;
                 *cell = new_cell;
+                if cell.background_color.is_none() {
+                    cell.background_color = background;
+                }
                 if unicode_width > 1 {
                     for i in 1..unicode_width {
                         self.set_symbol(x + i, y, '\0');
@@ -163,6 +185,11 @@ impl Buffer {
         }
     }
 
+    /// get a reference to the cell at this location
+    pub fn get_cell(&self, x: usize, y: usize) -> Option<&Cell> {
+        self.cells.get(y).and_then(|line| line.get(x))
+    }
+
     /// get the diff of 2 buffers
     pub fn diff<'a>(&self, new: &'a Self) -> Vec<(usize, usize, &'a Cell)> {
         let mut patches = vec![];
@@ -192,6 +219,78 @@ impl Buffer {
         }
         Ok(())
     }
+
+    /// writes only the cells that have changed compared to the previous buffer.
+    ///
+    /// Adjacent changed cells in the same row are coalesced into a single cursor move and
+    /// print, while the colors and attributes are only emitted when they differ from
+    /// the previously printed cell.
+    pub fn render_diff(
+        &self,
+        prev: &Self,
+        w: &mut dyn Write,
+    ) -> crossterm::Result<()> {
+        self.render_diff_at(prev, 0, w)
+    }
+
+    /// write the cells that are changed from the previous buffer,
+    /// where the top of the buffer is at this row of the terminal.
+    pub fn render_diff_at(
+        &self,
+        prev: &Self,
+        top: u16,
+        w: &mut dyn Write,
+    ) -> crossterm::Result<()> {
+        crossterm::queue!(w, cursor::Hide)?;
+        let default_style: CellStyle = (None, None, Attributes::default());
+        let mut current_style = default_style;
+        let mut cursor_loc: Option<(usize, usize)> = None;
+        let mut run = String::new();
+        for (x, y, cell) in prev.diff(self) {
+            // filler is \0 null character, it is covered by the wide character before it
+            if cell.is_filler() {
+                continue;
+            }
+            if cursor_loc != Some((x, y)) {
+                Self::flush_run(&mut run, w)?;
+                crossterm::queue!(w, cursor::MoveTo(x as u16, top + y as u16))?;
+            }
+            let cell_style = cell.style();
+            if cell_style != current_style {
+                Self::flush_run(&mut run, w)?;
+                if current_style != default_style {
+                    crossterm::queue!(w, ResetColor)?;
+                }
+                let (fg, bg, attributes) = cell_style;
+                if let Some(bg) = bg {
+                    crossterm::queue!(w, SetBackgroundColor(bg))?;
+                }
+                if let Some(fg) = fg {
+                    crossterm::queue!(w, SetForegroundColor(fg))?;
+                }
+                if !attributes.is_empty() {
+                    crossterm::queue!(w, SetAttributes(attributes))?;
+                }
+                current_style = cell_style;
+            }
+            run.push_str(&cell.symbol);
+            cursor_loc = Some((x + std::cmp::max(1, cell.unicode_width()), y));
+        }
+        Self::flush_run(&mut run, w)?;
+        if current_style != default_style {
+            crossterm::queue!(w, ResetColor)?;
+        }
+        Ok(())
+    }
+
+    /// print the accumulated characters of a run and clear it
+    fn flush_run(run: &mut String, w: &mut dyn Write) -> crossterm::Result<()> {
+        if !run.is_empty() {
+            crossterm::queue!(w, Print(&run))?;
+            run.clear();
+        }
+        Ok(())
+    }
 }
 
 impl fmt::Display for Cell {
@@ -287,4 +386,44 @@ mod test {
         assert_eq!(Cell::new(' '), buf2.cells[1][1]);
         assert_eq!(Cell::new(' '), buf2.cells[1][2]);
     }
+
+    #[test]
+    fn render_diff_coalesce() {
+        let prev = Buffer::new(10, 3);
+        let mut buf = Buffer::new(10, 3);
+        buf.write_str(1, 0, "Hi");
+        buf.write_bold_str(5, 2, "XY");
+
+        let mut out = vec![];
+        buf.render_diff(&prev, &mut out).unwrap();
+
+        let mut expected = String::new();
+        crossterm::queue!(
+            expected,
+            cursor::Hide,
+            cursor::MoveTo(1, 0),
+            Print("Hi"),
+            cursor::MoveTo(5, 2),
+            SetAttributes(Attribute::Bold.into()),
+            Print("XY"),
+            ResetColor
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn render_diff_unchanged() {
+        let mut prev = Buffer::new(10, 3);
+        prev.write_str(0, 0, "Hello");
+        let mut buf = Buffer::new(10, 3);
+        buf.write_str(0, 0, "Hello");
+
+        let mut out = vec![];
+        buf.render_diff(&prev, &mut out).unwrap();
+
+        let mut expected = String::new();
+        crossterm::queue!(expected, cursor::Hide).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
 }
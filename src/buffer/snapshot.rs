@@ -0,0 +1,400 @@
+//! Plain text and styled text representation of a Buffer,
+//! which can be checked in as golden files and compared in tests.
+//!
+//! The styled text is the plain text with the style of the cells encoded in tags:
+//! ```text
+//! Hello [fg=red bg=#00ff00 bold]world[/]!
+//! ```
+//! - A tag sets the style of the cells that follows it, until `[/]` or another tag.
+//! - The style is reset to the default at the start of each line.
+//! - A literal `[` is written as `[[`.
+//! - Colors are written as the color name (`red`, `dark_grey`, `reset`),
+//!   `#rrggbb` for rgb colors, or the ansi value number (`fg=208`).
+//! - Attributes are written in snake case (`bold`, `italic`, `crossed_out`).
+use super::{
+    Buffer,
+    Cell,
+};
+use crossterm::style::{
+    Attribute,
+    Color,
+};
+use std::{
+    error,
+    fmt,
+};
+
+/// Error encountered when parsing a styled text into a Buffer
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotError {
+    /// the line number (starting from 0) where the error occured
+    pub line: usize,
+    /// the description of the error
+    pub message: String,
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl error::Error for SnapshotError {}
+
+impl Buffer {
+    /// returns the characters of this buffer, one line per row.
+    /// The fillers of wide characters are not included.
+    pub fn to_plain_text(&self) -> String {
+        self.cells
+            .iter()
+            .map(|line| {
+                line.iter()
+                    .filter(|cell| !cell.is_filler())
+                    .map(|cell| cell.symbol.as_str())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// returns the characters of this buffer, one line per row,
+    /// with the colors and attributes of the cells encoded in tags.
+    /// The result can be parsed back with `Buffer::from_styled_text`
+    pub fn to_styled_text(&self) -> String {
+        self.cells
+            .iter()
+            .map(|line| {
+                let mut text = String::new();
+                let mut current_tag: Option<String> = None;
+                for cell in line.iter().filter(|cell| !cell.is_filler()) {
+                    let tag = style_tag(cell);
+                    if tag != current_tag {
+                        match &tag {
+                            Some(tag) => text += &format!("[{}]", tag),
+                            None => text += "[/]",
+                        }
+                        current_tag = tag;
+                    }
+                    text += &cell.symbol.replace('[', "[[");
+                }
+                if current_tag.is_some() {
+                    text += "[/]";
+                }
+                text
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// create a buffer from a styled text, see `Buffer::to_styled_text`.
+    ///
+    /// The width of the buffer is the width of the longest line,
+    /// shorter lines are padded with empty cells.
+    pub fn from_styled_text(text: &str) -> Result<Self, SnapshotError> {
+        let text = text.strip_suffix('\n').unwrap_or(text);
+        let mut lines = vec![];
+        for (j, line) in text.split('\n').enumerate() {
+            lines.push(
+                parse_line(line)
+                    .map_err(|message| SnapshotError { line: j, message })?,
+            );
+        }
+        let width = lines
+            .iter()
+            .map(|line| line.iter().map(|cell| cell.unicode_width()).sum())
+            .max()
+            .unwrap_or(0);
+        let mut buffer = Buffer::new(width, lines.len());
+        for (j, line) in lines.into_iter().enumerate() {
+            let mut x = 0;
+            for cell in line {
+                let cell_width = cell.unicode_width();
+                buffer.set_cell(x, j, cell);
+                x += cell_width;
+            }
+        }
+        Ok(buffer)
+    }
+
+    /// compare this buffer to the expected buffer.
+    /// Returns a readable report of the differences, listing which cells differ,
+    /// or None if the buffers are the same.
+    pub fn diff_report(&self, expected: &Self) -> Option<String> {
+        let mut report = vec![];
+        let size = |buf: &Buffer| {
+            (
+                buf.cells.first().map(|line| line.len()).unwrap_or(0),
+                buf.cells.len(),
+            )
+        };
+        let (width, height) = size(self);
+        let (expected_width, expected_height) = size(expected);
+        if (width, height) != (expected_width, expected_height) {
+            report.push(format!(
+                "size differs: expected {}x{}, got {}x{}",
+                expected_width, expected_height, width, height
+            ));
+        }
+        for j in 0..std::cmp::max(height, expected_height) {
+            for i in 0..std::cmp::max(width, expected_width) {
+                let cell = self.get_cell(i, j);
+                let expected_cell = expected.get_cell(i, j);
+                if cell != expected_cell {
+                    report.push(format!(
+                        "cell ({}, {}): expected {}, got {}",
+                        i,
+                        j,
+                        describe_cell(expected_cell),
+                        describe_cell(cell)
+                    ));
+                }
+            }
+        }
+        if report.is_empty() {
+            None
+        } else {
+            Some(format!(
+                "{}\n--- expected:\n{}\n--- got:\n{}",
+                report.join("\n"),
+                expected.to_styled_text(),
+                self.to_styled_text()
+            ))
+        }
+    }
+}
+
+fn describe_cell(cell: Option<&Cell>) -> String {
+    match cell {
+        Some(cell) => {
+            let symbol = if cell.is_filler() {
+                "\\0".to_string()
+            } else {
+                cell.symbol.clone()
+            };
+            match style_tag(cell) {
+                Some(tag) => format!("{:?} [{}]", symbol, tag),
+                None => format!("{:?}", symbol),
+            }
+        }
+        None => "no cell".to_string(),
+    }
+}
+
+/// the tag content of the style of the cell, None if it has the default style
+fn style_tag(cell: &Cell) -> Option<String> {
+    let mut parts = vec![];
+    if let Some(fg) = cell.foreground_color {
+        parts.push(format!("fg={}", color_name(fg)));
+    }
+    if let Some(bg) = cell.background_color {
+        parts.push(format!("bg={}", color_name(bg)));
+    }
+    for attr in all_attributes() {
+        if cell.attributes.has(attr) {
+            parts.push(attribute_name(attr));
+        }
+    }
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(" "))
+    }
+}
+
+/// parse the cells of a line of styled text
+fn parse_line(line: &str) -> Result<Vec<Cell>, String> {
+    let mut cells = vec![];
+    let mut style = Cell::default();
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == '[' {
+            if chars.peek() == Some(&'[') {
+                chars.next();
+            } else {
+                let mut tag = String::new();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(c) => tag.push(c),
+                        None => return Err(format!("unclosed tag `[{}`", tag)),
+                    }
+                }
+                style = parse_tag(&tag)?;
+                continue;
+            }
+        }
+        cells.push(Cell {
+            symbol: ch.to_string(),
+            ..style.clone()
+        });
+    }
+    Ok(cells)
+}
+
+/// parse the content of a tag into a cell which only has the style set
+fn parse_tag(tag: &str) -> Result<Cell, String> {
+    let mut cell = Cell::default();
+    if tag == "/" {
+        return Ok(cell);
+    }
+    for part in tag.split_whitespace() {
+        if let Some(fg) = part.strip_prefix("fg=") {
+            cell.foreground_color = Some(parse_color(fg)?);
+        } else if let Some(bg) = part.strip_prefix("bg=") {
+            cell.background_color = Some(parse_color(bg)?);
+        } else if let Some(attr) =
+            all_attributes().find(|attr| attribute_name(*attr) == part)
+        {
+            cell.attributes.set(attr);
+        } else {
+            return Err(format!("unknown style `{}`", part));
+        }
+    }
+    Ok(cell)
+}
+
+fn all_attributes() -> impl Iterator<Item = Attribute> {
+    Attribute::iterator().filter(|attr| *attr != Attribute::__Nonexhaustive)
+}
+
+/// the snake case name of the attribute, ie: CrossedOut -> crossed_out
+fn attribute_name(attr: Attribute) -> String {
+    to_snake_case(&format!("{:?}", attr))
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.extend(ch.to_lowercase());
+        } else {
+            snake.push(ch);
+        }
+    }
+    snake
+}
+
+fn color_name(color: Color) -> String {
+    match color {
+        Color::Rgb { r, g, b } => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        Color::AnsiValue(v) => v.to_string(),
+        _ => to_snake_case(&format!("{:?}", color)),
+    }
+}
+
+pub(crate) fn parse_color(s: &str) -> Result<Color, String> {
+    let color = match s {
+        "reset" => Color::Reset,
+        "black" => Color::Black,
+        "dark_grey" => Color::DarkGrey,
+        "red" => Color::Red,
+        "dark_red" => Color::DarkRed,
+        "green" => Color::Green,
+        "dark_green" => Color::DarkGreen,
+        "yellow" => Color::Yellow,
+        "dark_yellow" => Color::DarkYellow,
+        "blue" => Color::Blue,
+        "dark_blue" => Color::DarkBlue,
+        "magenta" => Color::Magenta,
+        "dark_magenta" => Color::DarkMagenta,
+        "cyan" => Color::Cyan,
+        "dark_cyan" => Color::DarkCyan,
+        "white" => Color::White,
+        "grey" => Color::Grey,
+        _ => {
+            if let Some(hex) = s.strip_prefix('#') {
+                let channel = |i: usize| {
+                    hex.get(i..i + 2)
+                        .and_then(|c| u8::from_str_radix(c, 16).ok())
+                };
+                match (hex.len(), channel(0), channel(2), channel(4)) {
+                    (6, Some(r), Some(g), Some(b)) => Color::Rgb { r, g, b },
+                    _ => return Err(format!("invalid rgb color `{}`", s)),
+                }
+            } else if let Ok(v) = s.parse::<u8>() {
+                Color::AnsiValue(v)
+            } else {
+                return Err(format!("unknown color `{}`", s));
+            }
+        }
+    };
+    Ok(color)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn plain_text() {
+        let mut buf = Buffer::new(6, 2);
+        buf.write_str(0, 0, "Hello");
+        buf.set_symbol(1, 1, '〇');
+        buf.set_symbol(3, 1, 'x');
+        assert_eq!(buf.to_plain_text(), "Hello \n 〇x  ");
+    }
+
+    #[test]
+    fn styled_text_round_trip() {
+        let mut buf = Buffer::new(12, 2);
+        buf.write_str(0, 0, "a[b]");
+        buf.write_bold_str(5, 0, "bold");
+        let mut cell = Cell::new('c');
+        cell.color(Color::Red);
+        cell.background(Color::Rgb {
+            r: 0,
+            g: 255,
+            b: 16,
+        });
+        cell.attributes(vec![Attribute::Italic, Attribute::CrossedOut]);
+        buf.set_cell(0, 1, cell);
+        let mut cell = Cell::new('⦿');
+        cell.color(Color::AnsiValue(208));
+        buf.set_cell(2, 1, cell);
+
+        let styled = buf.to_styled_text();
+        assert_eq!(
+            styled,
+            "a[[b] [bold]bold[/]   \n\
+             [fg=red bg=#00ff10 italic crossed_out]c[/] [fg=208]⦿[/]         "
+        );
+        let parsed = Buffer::from_styled_text(&styled).expect("must parse");
+        assert_eq!(parsed.diff_report(&buf), None);
+        assert_eq!(parsed, buf);
+    }
+
+    #[test]
+    fn styled_text_short_lines_are_padded() {
+        let buf = Buffer::from_styled_text("abc\nd\n").expect("must parse");
+        assert_eq!(buf.to_plain_text(), "abc\nd  ");
+    }
+
+    #[test]
+    fn styled_text_errors() {
+        assert_eq!(
+            Buffer::from_styled_text("ok\n[bold"),
+            Err(SnapshotError {
+                line: 1,
+                message: "unclosed tag `[bold`".to_string()
+            })
+        );
+        assert_eq!(
+            Buffer::from_styled_text("[fg=nope]x").map_err(|e| e.message),
+            Err("unknown color `nope`".to_string())
+        );
+    }
+
+    #[test]
+    fn diff_report() {
+        let expected = Buffer::from_styled_text("ab\ncd").unwrap();
+        let got = Buffer::from_styled_text("ab\n[bold]c[/]x").unwrap();
+        let report = got.diff_report(&expected).expect("must differ");
+        assert!(report.starts_with(
+            "cell (0, 1): expected \"c\", got \"c\" [bold]\n\
+             cell (1, 1): expected \"d\", got \"x\"\n"
+        ));
+    }
+}
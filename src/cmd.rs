@@ -1,23 +1,51 @@
+use crate::command::SetTitle;
 use crossterm::cursor;
 use std::io::Write;
 
 /// creates a Cmd representation which translate to actual tty commands
-#[derive(Debug, Clone, Copy)]
+/// or effects which are executed by the renderer.
+///
+/// Cmds are returned by the widgets when drawn and by the app when a msg is dispatched.
+#[derive(Debug, Clone, PartialEq)]
 pub enum Cmd {
     /// Move the cursor to x,y location
     MoveTo(usize, usize),
     /// show the cursor
     ShowCursor,
+    /// exit the event loop of the renderer
+    Quit,
+    /// move the focus to the next focusable widget
+    FocusNext,
+    /// move the focus to the previous focusable widget
+    FocusPrev,
+    /// move the focus to the widget with this id
+    Focus(String),
+    /// recompute the layout of the widget tree
+    Relayout,
+    /// set the title of the terminal window
+    SetTitle(String),
+    /// ring the terminal bell
+    Bell,
 }
 
 impl Cmd {
     /// execute the command to the supplied writable buffer (ie: stdout)
+    ///
+    /// Cmds which affects the renderer rather than the terminal, such as Quit and Focus,
+    /// are executed by the renderer and writes nothing here.
     pub fn execute(&self, w: &mut dyn Write) -> crossterm::Result<()> {
         match self {
             Cmd::MoveTo(x, y) => {
                 crossterm::queue!(w, cursor::MoveTo(*x as u16, *y as u16))
             }
             Cmd::ShowCursor => crossterm::queue!(w, cursor::Show),
+            Cmd::SetTitle(title) => crossterm::queue!(w, SetTitle(title)),
+            Cmd::Bell => Ok(write!(w, "\x07")?),
+            Cmd::Quit
+            | Cmd::FocusNext
+            | Cmd::FocusPrev
+            | Cmd::Focus(_)
+            | Cmd::Relayout => Ok(()),
         }
     }
 }
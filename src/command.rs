@@ -8,8 +8,76 @@ use crossterm::{
     style,
     terminal,
     terminal::ClearType,
+    Command,
 };
-use std::io::Write;
+use std::{
+    fmt,
+    io::{
+        self,
+        Write,
+    },
+    panic,
+    sync::{
+        atomic::{
+            AtomicU8,
+            Ordering,
+        },
+        Once,
+    },
+};
+
+/// A command that sets the title of the terminal window, with OSC 0 which also sets
+/// the icon name. This is the same as `terminal::SetTitle` in the later versions of crossterm.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetTitle<T: fmt::Display>(pub T);
+
+impl<T: fmt::Display> Command for SetTitle<T> {
+    type AnsiType = String;
+
+    fn ansi_code(&self) -> Self::AnsiType {
+        format!("\x1b]0;{}\x07", self.0)
+    }
+
+    /// the title is not set in the windows console which doesn't support the ansi codes
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> crossterm::Result<()> {
+        Ok(())
+    }
+}
+
+/// the terminal is not modified
+const RESTORED: u8 = 0;
+/// the terminal is in raw mode, alternate screen and mouse capture
+const FULLSCREEN: u8 = 1;
+/// the terminal is in raw mode only
+const INLINE: u8 = 2;
+
+/// the mode the terminal is setup into,
+/// used to restore the terminal when a panic happens
+static TERMINAL_STATE: AtomicU8 = AtomicU8::new(RESTORED);
+
+static PANIC_HOOK: Once = Once::new();
+
+/// install a panic hook which restores the terminal before the panic message is printed,
+/// otherwise the message is lost in the alternate screen and the terminal is left in raw mode.
+pub(crate) fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let prev_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let _ = restore(&mut io::stdout());
+            prev_hook(info);
+        }));
+    });
+}
+
+/// restore the terminal if it is still setup by init or init_inline
+pub(crate) fn restore(w: &mut dyn Write) -> crossterm::Result<()> {
+    match TERMINAL_STATE.load(Ordering::SeqCst) {
+        FULLSCREEN => finalize(w),
+        INLINE => finalize_inline(w),
+        _ => Ok(()),
+    }
+}
 
 pub(crate) fn reset_top(w: &mut dyn Write) -> crossterm::Result<()> {
     crossterm::queue!(
@@ -22,11 +90,35 @@ pub(crate) fn reset_top(w: &mut dyn Write) -> crossterm::Result<()> {
 }
 
 pub(crate) fn init(w: &mut dyn Write) -> crossterm::Result<()> {
+    TERMINAL_STATE.store(FULLSCREEN, Ordering::SeqCst);
     crossterm::execute!(w, terminal::EnterAlternateScreen, EnableMouseCapture)?;
     terminal::enable_raw_mode()
 }
 
+/// setup the terminal for rendering inline below the cursor,
+/// the alternate screen is not used and the mouse is not captured
+/// so the scrollback of the terminal still works.
+pub(crate) fn init_inline() -> crossterm::Result<()> {
+    TERMINAL_STATE.store(INLINE, Ordering::SeqCst);
+    terminal::enable_raw_mode()
+}
+
+/// restore the terminal setup by init_inline,
+/// this does nothing if the terminal is already restored
+pub(crate) fn finalize_inline(w: &mut dyn Write) -> crossterm::Result<()> {
+    if TERMINAL_STATE.swap(RESTORED, Ordering::SeqCst) == RESTORED {
+        return Ok(());
+    }
+    crossterm::execute!(w, style::ResetColor, cursor::Show)?;
+    terminal::disable_raw_mode()
+}
+
+/// restore the terminal setup by init,
+/// this does nothing if the terminal is already restored
 pub(crate) fn finalize(w: &mut dyn Write) -> crossterm::Result<()> {
+    if TERMINAL_STATE.swap(RESTORED, Ordering::SeqCst) == RESTORED {
+        return Ok(());
+    }
     crossterm::execute!(
         w,
         style::ResetColor,
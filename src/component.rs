@@ -0,0 +1,858 @@
+//! Build the UI declaratively from a view function, Elm style.
+//!
+//! A `Component` returns a `sauron_vdom` tree of the titik widgets from its `view`,
+//! and updates its state from the msgs emitted by the widgets.
+//! After each update, the new view is diffed against the previous one and only the
+//! differences are patched into the live widgets. The widgets are not recreated,
+//! so the focus, scroll location and cursor of the widgets are preserved.
+//!
+//! The widgets are identified by their tag, such as "button" and "flexbox",
+//! see `Widget::get_tag`. Text nodes have no widgets and are ignored.
+//!
+//! ```ignore
+//! impl Component<Msg> for Counter {
+//!     fn view(&self) -> Node<Msg> {
+//!         flexbox(
+//!             vec![],
+//!             vec![
+//!                 button(vec![label("+"), on_click(|_| Msg::Increment)]),
+//!                 text_area(vec![value(self.count.to_string())]),
+//!             ],
+//!         )
+//!     }
+//!
+//!     fn update(&mut self, msg: Msg) -> Vec<Cmd> {
+//!         match msg {
+//!             Msg::Increment => self.count += 1,
+//!         }
+//!         vec![]
+//!     }
+//! }
+//!
+//! let program = Program::new(Counter::default());
+//! let mut root = program.create_root();
+//! Renderer::new(&mut stdout, Some(&program), root.as_mut()).run()?;
+//! ```
+use crate::{
+    find_node::find_widget_by_widget_id_mut,
+    renderer::Dispatch,
+    widget_id::{
+        self,
+        WidgetId,
+    },
+    Button,
+    Checkbox,
+    Cmd,
+    ComboBox,
+    FlexBox,
+    GroupBox,
+    InlineStyle,
+    Layer,
+    ListBox,
+    Placement,
+    Radio,
+    Slider,
+    TabBox,
+    TextArea,
+    TextInput,
+    Widget,
+};
+pub use builder::*;
+use sauron_vdom::{
+    Callback,
+    Patch,
+    Value,
+};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fmt,
+};
+
+mod builder;
+
+/// A virtual node of a titik widget
+pub type Node<MSG> =
+    sauron_vdom::Node<&'static str, &'static str, sauron_vdom::Event, MSG>;
+
+/// An attribute or an event listener of a virtual node
+pub type Attribute<MSG> =
+    sauron_vdom::Attribute<&'static str, sauron_vdom::Event, MSG>;
+
+/// An Elm style component, the view is a function of its state
+pub trait Component<MSG> {
+    /// returns the view of the current state of this component
+    fn view(&self) -> Node<MSG>;
+
+    /// update the state of this component with the msg emitted by the widgets,
+    /// returns the commands to be executed by the renderer
+    fn update(&mut self, msg: MSG) -> Vec<Cmd>;
+}
+
+/// Runs a component in the renderer.
+///
+/// The program dispatches the msgs to the component, then patches the live widget tree
+/// with the differences of the new view to the previous view.
+pub struct Program<COMP, MSG>
+where
+    MSG: 'static,
+{
+    component: RefCell<COMP>,
+    current_view: RefCell<Node<MSG>>,
+}
+
+impl<COMP, MSG> fmt::Debug for Program<COMP, MSG>
+where
+    COMP: fmt::Debug,
+    MSG: 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Program")
+            .field("component", &self.component)
+            .finish()
+    }
+}
+
+impl<COMP, MSG> Program<COMP, MSG>
+where
+    COMP: Component<MSG>,
+    MSG: fmt::Debug + 'static,
+{
+    /// create a program of this component
+    pub fn new(component: COMP) -> Self {
+        let current_view = component.view();
+        Program {
+            component: RefCell::new(component),
+            current_view: RefCell::new(current_view),
+        }
+    }
+
+    /// create the widget tree from the current view of the component,
+    /// this is used as the root node of the renderer.
+    pub fn create_root(&self) -> Box<dyn Widget<MSG>> {
+        create_widget(&self.current_view.borrow())
+            .expect("the root of the view must be a widget")
+    }
+}
+
+impl<COMP, MSG> Dispatch<MSG> for Program<COMP, MSG>
+where
+    COMP: Component<MSG>,
+    MSG: fmt::Debug + 'static,
+{
+    fn dispatch(&self, msg: MSG, root_node: &mut dyn Widget<MSG>) -> Vec<Cmd> {
+        let cmds = self.component.borrow_mut().update(msg);
+        let new_view = self.component.borrow().view();
+        patch(root_node, &self.current_view.borrow(), &new_view);
+        *self.current_view.borrow_mut() = new_view;
+        cmds
+    }
+}
+
+/// create the widget of this virtual node together with its children,
+/// returns None if the node is a text node.
+/// An unknown tag is logged and created as an empty flexbox, which still holds its children.
+pub fn create_widget<MSG>(node: &Node<MSG>) -> Option<Box<dyn Widget<MSG>>>
+where
+    MSG: fmt::Debug + 'static,
+{
+    let element = node.as_element_ref()?;
+    let mut widget: Box<dyn Widget<MSG>> = match element.tag {
+        "button" => Box::new(Button::new("")),
+        "checkbox" => Box::new(Checkbox::new("")),
+        "combobox" => Box::new(ComboBox::new()),
+        "flexbox" => Box::new(FlexBox::new()),
+        "groupbox" => Box::new(GroupBox::new()),
+        "layer" => Box::new(Layer::new(Placement::Center)),
+        "listbox" => Box::new(ListBox::new()),
+        "radio" => Box::new(Radio::new("")),
+        "slider" => Box::new(Slider::new(0.0)),
+        "tabbox" => Box::new(TabBox::new()),
+        "textarea" => Box::new(TextArea::new("")),
+        "textinput" => Box::new(TextInput::new("")),
+        tag => {
+            eprintln!("there is no widget with the tag: {}", tag);
+            Box::new(FlexBox::new())
+        }
+    };
+    set_attributes(widget.as_mut(), &element.attrs);
+    set_event_listeners(widget.as_mut(), &element.attrs);
+    for child in element.children.iter() {
+        if let Some(child_widget) = create_widget(child) {
+            widget.add_child(child_widget);
+        }
+    }
+    Some(widget)
+}
+
+/// set the value attributes into the widget, the id and class are set for all widgets,
+/// the rest are set by the widget itself
+fn set_attributes<MSG>(widget: &mut dyn Widget<MSG>, attrs: &[Attribute<MSG>])
+where
+    MSG: 'static,
+{
+    for attr in attrs.iter() {
+        if let Some(value) = attr.get_value() {
+            match attr.name {
+                "id" => widget.set_id(&value.to_string()),
+                "class" => {
+                    for class in widget.get_classes().to_vec() {
+                        widget.remove_class(&class);
+                    }
+                    for class in value.to_string().split_whitespace() {
+                        widget.add_class(class);
+                    }
+                }
+                "style" => {
+                    match value.to_string().parse() {
+                        Ok(style) => widget.set_inline_style(style),
+                        Err(e) => eprintln!("{} in the view, ignored", e),
+                    }
+                }
+                name => widget.set_attribute(name, value),
+            }
+        }
+    }
+}
+
+/// replace the event listeners of the widget with the listeners in the attributes
+fn set_event_listeners<MSG>(
+    widget: &mut dyn Widget<MSG>,
+    attrs: &[Attribute<MSG>],
+) where
+    MSG: 'static,
+{
+    let mut events: BTreeMap<&str, Vec<Callback<sauron_vdom::Event, MSG>>> =
+        BTreeMap::new();
+    for attr in attrs.iter() {
+        if let Some(cb) = attr.get_callback() {
+            events.entry(attr.name).or_default().push(cb.clone());
+        }
+    }
+    for (event, listeners) in events {
+        widget.set_event_listeners(event, listeners);
+    }
+}
+
+/// The widget of a node in the previous view
+struct Mapped<'a, MSG>
+where
+    MSG: 'static,
+{
+    node: &'a Node<MSG>,
+    /// None for the text nodes
+    widget_id: Option<WidgetId>,
+    parent: Option<WidgetId>,
+    /// the index of the widget in the children of its parent
+    position: usize,
+}
+
+/// map the nodes of the view to the widgets, in the depth first order used by the patches
+fn map_nodes<'a, MSG>(
+    node: &'a Node<MSG>,
+    widget: Option<&dyn Widget<MSG>>,
+    parent: Option<WidgetId>,
+    position: usize,
+    mapped: &mut Vec<Mapped<'a, MSG>>,
+) where
+    MSG: 'static,
+{
+    let widget = widget.filter(|_| node.as_element_ref().is_some());
+    let widget_id = widget.and_then(|widget| widget.get_widget_id());
+    mapped.push(Mapped {
+        node,
+        widget_id,
+        parent,
+        position,
+    });
+    if let Some(children) = node.get_children() {
+        let child_widgets = widget.and_then(|widget| widget.children());
+        let mut widget_position = 0;
+        for child in children.iter() {
+            if child.as_element_ref().is_some() {
+                let child_widget = child_widgets
+                    .and_then(|widgets| widgets.get(widget_position))
+                    .map(|child_widget| child_widget.as_ref());
+                map_nodes(
+                    child,
+                    child_widget,
+                    widget_id,
+                    widget_position,
+                    mapped,
+                );
+                widget_position += 1;
+            } else {
+                map_nodes(child, None, widget_id, widget_position, mapped);
+            }
+        }
+    }
+}
+
+/// apply the differences of the new view to the old view into the live widgets
+/// which are created from the old view.
+///
+/// The root widget is owned by the caller and can not change its type,
+/// if the root of the new view has a different tag, the content of the root widget
+/// is rebuilt from the new view instead.
+pub fn patch<MSG>(
+    root_node: &mut dyn Widget<MSG>,
+    old_view: &Node<MSG>,
+    new_view: &Node<MSG>,
+) where
+    MSG: fmt::Debug + 'static,
+{
+    // sauron_vdom can not diff a text node which is replaced with an element
+    if replaces_text_with_element(old_view, new_view) {
+        rebuild_root(root_node, old_view, new_view);
+        refresh_event_listeners(root_node, new_view);
+        return;
+    }
+    widget_id::assign_widget_ids(root_node);
+    let mut mapped = vec![];
+    map_nodes(old_view, Some(&*root_node), None, 0, &mut mapped);
+    // the positions of the widgets removed from their parent,
+    // the siblings after them are shifted to the left
+    let mut removed: Vec<(WidgetId, usize)> = vec![];
+
+    for patch in sauron_vdom::diff(old_view, new_view) {
+        let target = match mapped.get(patch.node_idx()) {
+            Some(target) => target,
+            None => continue,
+        };
+        let widget_id = match target.widget_id {
+            Some(widget_id) => widget_id,
+            None => continue,
+        };
+        match patch {
+            Patch::Replace(_tag, _idx, new_node) => {
+                replace_widget(root_node, target, new_node, &mut removed)
+            }
+            _ => {
+                if let Some(widget) =
+                    find_widget_by_widget_id_mut(root_node, widget_id)
+                {
+                    patch_widget(widget, target.node, patch);
+                }
+            }
+        }
+    }
+    // the callbacks are created on each view, which are never equal to the previous,
+    // so the listeners are always replaced with the ones in the new view
+    refresh_event_listeners(root_node, new_view);
+}
+
+fn replace_widget<MSG>(
+    root_node: &mut dyn Widget<MSG>,
+    target: &Mapped<MSG>,
+    new_node: &Node<MSG>,
+    removed: &mut Vec<(WidgetId, usize)>,
+) where
+    MSG: fmt::Debug + 'static,
+{
+    match target.parent {
+        Some(parent) => {
+            let shift = removed
+                .iter()
+                .filter(|(id, position)| {
+                    *id == parent && *position < target.position
+                })
+                .count();
+            let position = target.position - shift;
+            let parent_widget =
+                match find_widget_by_widget_id_mut(root_node, parent) {
+                    Some(parent_widget) => parent_widget,
+                    None => return,
+                };
+            match create_widget(new_node) {
+                Some(new_widget) => {
+                    if let Some(child) = parent_widget
+                        .children_mut()
+                        .and_then(|children| children.get_mut(position))
+                    {
+                        *child = new_widget;
+                        widget_id::ensure_widget_id(child.as_mut());
+                    }
+                }
+                // the element is replaced with a text node, which has no widget
+                None => {
+                    if parent_widget.take_child(position).is_some() {
+                        removed.push((parent, target.position));
+                    }
+                }
+            }
+        }
+        None => rebuild_root(root_node, target.node, new_node),
+    }
+}
+
+/// the root can not be replaced, it is owned by the caller of the renderer,
+/// its content is recreated from the new view instead.
+fn rebuild_root<MSG>(
+    root_node: &mut dyn Widget<MSG>,
+    old_view: &Node<MSG>,
+    new_view: &Node<MSG>,
+) where
+    MSG: fmt::Debug + 'static,
+{
+    let element = match new_view.as_element_ref() {
+        Some(element) => element,
+        None => {
+            eprintln!("the root of the view can not be a text node, ignored");
+            return;
+        }
+    };
+    if old_view.tag() != new_view.tag() {
+        eprintln!(
+            "the root of the view can not change its tag to `{}`, only its content is rebuilt",
+            element.tag
+        );
+    }
+    while let Some(count) = root_node.children().map(|c| c.len()) {
+        if count == 0 {
+            break;
+        }
+        root_node.take_child(count - 1);
+    }
+    set_attributes(root_node, &element.attrs);
+    for child in element.children.iter() {
+        if let Some(child_widget) = create_widget(child) {
+            root_node.add_child(child_widget);
+        }
+    }
+}
+
+/// whether the new view replaces a text node of the old view with an element,
+/// following the pairing of the nodes done in `sauron_vdom::diff`
+fn replaces_text_with_element<MSG>(
+    old_node: &Node<MSG>,
+    new_node: &Node<MSG>,
+) -> bool {
+    match (old_node.as_element_ref(), new_node.as_element_ref()) {
+        (None, Some(_)) => true,
+        (Some(old_element), Some(new_element))
+            if old_element.tag == new_element.tag =>
+        {
+            old_element
+                .children
+                .iter()
+                .zip(new_element.children.iter())
+                .any(|(old_child, new_child)| {
+                    replaces_text_with_element(old_child, new_child)
+                })
+        }
+        _ => false,
+    }
+}
+
+fn patch_widget<MSG>(
+    widget: &mut dyn Widget<MSG>,
+    old_node: &Node<MSG>,
+    patch: Patch<&'static str, &'static str, sauron_vdom::Event, MSG>,
+) where
+    MSG: fmt::Debug + 'static,
+{
+    match patch {
+        Patch::AppendChildren(_tag, _idx, new_children) => {
+            for child in new_children {
+                if let Some(child_widget) = create_widget(child) {
+                    widget.add_child(child_widget);
+                }
+            }
+        }
+        Patch::TruncateChildren(_tag, _idx, len) => {
+            // only the element nodes have widgets
+            let keep = old_node
+                .get_children()
+                .map(|children| {
+                    children
+                        .iter()
+                        .take(len)
+                        .filter(|child| child.as_element_ref().is_some())
+                        .count()
+                })
+                .unwrap_or(0);
+            while let Some(count) = widget.children().map(|c| c.len()) {
+                if count <= keep {
+                    break;
+                }
+                widget.take_child(count - 1);
+            }
+        }
+        Patch::AddAttributes(_tag, _idx, attrs) => {
+            set_attributes(widget, &attrs)
+        }
+        Patch::RemoveAttributes(_tag, _idx, names) => {
+            for name in names {
+                match name {
+                    "class" => {
+                        for class in widget.get_classes().to_vec() {
+                            widget.remove_class(&class);
+                        }
+                    }
+                    "style" => widget.set_inline_style(InlineStyle::default()),
+                    name => widget.remove_attribute(name),
+                }
+            }
+        }
+        Patch::RemoveEventListener(_tag, _idx, events) => {
+            for event in events {
+                widget.set_event_listeners(event, vec![]);
+            }
+        }
+        // the added listeners are set in `refresh_event_listeners`
+        Patch::AddEventListener(..) => (),
+        Patch::Replace(..) | Patch::ChangeText(..) => (),
+    }
+}
+
+/// set the event listeners of the widgets to the ones in the view
+fn refresh_event_listeners<MSG>(
+    widget: &mut dyn Widget<MSG>,
+    view: &Node<MSG>,
+) {
+    if let Some(element) = view.as_element_ref() {
+        set_event_listeners(widget, &element.attrs);
+        if let Some(children) = widget.children_mut() {
+            let child_views = element
+                .children
+                .iter()
+                .filter(|child| child.as_element_ref().is_some());
+            for (child, child_view) in children.iter_mut().zip(child_views) {
+                refresh_event_listeners(child.as_mut(), child_view);
+            }
+        }
+    }
+}
+
+/// convert the numeric value into f32
+pub(crate) fn value_to_f32(value: &Value) -> Option<f32> {
+    match *value {
+        Value::U8(v) => Some(v as f32),
+        Value::U16(v) => Some(v as f32),
+        Value::U32(v) => Some(v as f32),
+        Value::U64(v) => Some(v as f32),
+        Value::Usize(v) => Some(v as f32),
+        Value::I8(v) => Some(v as f32),
+        Value::I16(v) => Some(v as f32),
+        Value::I32(v) => Some(v as f32),
+        Value::I64(v) => Some(v as f32),
+        Value::Isize(v) => Some(v as f32),
+        Value::F32(v) => Some(v),
+        Value::F64(v) => Some(v as f32),
+        _ => None,
+    }
+}
+
+/// convert the value into a list of strings, a single value is a list of one string
+pub(crate) fn value_to_strings(value: &Value) -> Vec<String> {
+    match value {
+        Value::Vec(values) => values.iter().map(|v| v.to_string()).collect(),
+        value => vec![value.to_string()],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        find_node::find_widget_by_id,
+        query::find_by_id_as,
+    };
+    use crossterm::event::{
+        Event,
+        KeyCode,
+        KeyModifiers,
+        MouseButton,
+        MouseEvent,
+    };
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Msg {
+        Increment,
+        Reset,
+    }
+
+    struct Counter {
+        count: usize,
+    }
+
+    impl Component<Msg> for Counter {
+        fn view(&self) -> Node<Msg> {
+            let mut children = vec![
+                button(vec![
+                    id("increment"),
+                    label("+"),
+                    on_click(|_| Msg::Increment),
+                ]),
+                text_input(vec![id("count"), value(self.count.to_string())]),
+            ];
+            if self.count > 1 {
+                children.push(checkbox(vec![id("many"), checked(true)]));
+            }
+            flexbox(vec![class("counter")], children)
+        }
+
+        fn update(&mut self, msg: Msg) -> Vec<Cmd> {
+            match msg {
+                Msg::Increment => self.count += 1,
+                Msg::Reset => self.count = 0,
+            }
+            vec![]
+        }
+    }
+
+    fn count<MSG: 'static>(root: &dyn Widget<MSG>) -> &str {
+        find_by_id_as::<TextInput<MSG>, _>(root, "count")
+            .unwrap()
+            .get_value()
+    }
+
+    #[test]
+    fn patch_live_widgets() {
+        let program = Program::new(Counter { count: 0 });
+        let mut root = program.create_root();
+        assert_eq!(root.get_classes(), &["counter".to_string()]);
+        assert_eq!(count(root.as_ref()), "0");
+
+        widget_id::assign_widget_ids(root.as_mut());
+        let input_id = find_widget_by_id(root.as_ref(), "count")
+            .unwrap()
+            .get_widget_id();
+        root.children_mut().unwrap()[1].set_focused(true);
+
+        program.dispatch(Msg::Increment, root.as_mut());
+        program.dispatch(Msg::Increment, root.as_mut());
+        assert_eq!(count(root.as_ref()), "2");
+        assert!(
+            find_by_id_as::<Checkbox<Msg>, _>(root.as_ref(), "many").is_some()
+        );
+        // the input is patched, not recreated
+        let input = find_widget_by_id(root.as_ref(), "count").unwrap();
+        assert_eq!(input.get_widget_id(), input_id);
+        assert!(input.is_focused());
+
+        program.dispatch(Msg::Reset, root.as_mut());
+        assert_eq!(count(root.as_ref()), "0");
+        assert_eq!(root.children().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn listeners_are_replaced() {
+        let program = Program::new(Counter { count: 0 });
+        let mut root = program.create_root();
+        for _ in 0..3 {
+            program.dispatch(Msg::Increment, root.as_mut());
+        }
+        let click = Event::Mouse(MouseEvent::Down(
+            MouseButton::Left,
+            1,
+            1,
+            KeyModifiers::empty(),
+        ));
+        let msgs = root.children_mut().unwrap()[0].process_event(click);
+        assert_eq!(msgs, vec![Msg::Increment]);
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum PanelMsg {
+        Counter(Msg),
+        Close,
+    }
+
+    /// embeds the counter, which keeps its own state and msgs
+    struct Panel {
+        counter: Counter,
+        closed: bool,
+    }
+
+    impl Component<PanelMsg> for Panel {
+        fn view(&self) -> Node<PanelMsg> {
+            flexbox(
+                vec![],
+                vec![
+                    map_msg(self.counter.view(), PanelMsg::Counter),
+                    button(vec![label("close"), on_click(|_| PanelMsg::Close)]),
+                ],
+            )
+        }
+
+        fn update(&mut self, msg: PanelMsg) -> Vec<Cmd> {
+            match msg {
+                PanelMsg::Counter(msg) => self.counter.update(msg),
+                PanelMsg::Close => {
+                    self.closed = true;
+                    vec![]
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn nested_components() {
+        let program = Program::new(Panel {
+            counter: Counter { count: 0 },
+            closed: false,
+        });
+        let mut root = program.create_root();
+        let click = Event::Mouse(MouseEvent::Down(
+            MouseButton::Left,
+            1,
+            1,
+            KeyModifiers::empty(),
+        ));
+        let increment = root.children_mut().unwrap()[0]
+            .child_mut(0)
+            .unwrap()
+            .process_event(click);
+        assert_eq!(increment, vec![PanelMsg::Counter(Msg::Increment)]);
+        for msg in increment {
+            program.dispatch(msg, root.as_mut());
+        }
+        assert_eq!(count(root.as_ref()), "1");
+
+        let close = root.children_mut().unwrap()[1].process_event(click);
+        assert_eq!(close, vec![PanelMsg::Close]);
+    }
+
+    #[test]
+    fn unknown_tag() {
+        let view: Node<Msg> = flexbox(
+            vec![],
+            vec![widget("marquee", vec![], vec![button(vec![id("inner")])])],
+        );
+        let root = create_widget(&view).unwrap();
+        let unknown = &root.children().unwrap()[0];
+        assert!(unknown.as_any().downcast_ref::<FlexBox<Msg>>().is_some());
+        assert!(find_widget_by_id(root.as_ref(), "inner").is_some());
+    }
+
+    #[test]
+    fn invalid_style() {
+        let view: Node<Msg> =
+            button(vec![id("styled"), style("flex-grow: lots")]);
+        let button = create_widget(&view).unwrap();
+        assert!(button.get_inline_style().is_none());
+    }
+
+    #[test]
+    fn root_changes_its_tag() {
+        let old_view: Node<Msg> =
+            flexbox(vec![class("old")], vec![button(vec![id("old")])]);
+        let new_view: Node<Msg> =
+            group_box(vec![class("new")], vec![checkbox(vec![id("new")])]);
+        let mut root = create_widget(&old_view).unwrap();
+        patch(root.as_mut(), &old_view, &new_view);
+        assert!(find_widget_by_id(root.as_ref(), "old").is_none());
+        assert!(find_widget_by_id(root.as_ref(), "new").is_some());
+        assert!(root.get_classes().contains(&"new".to_string()));
+    }
+
+    #[test]
+    fn replace_text_and_elements() {
+        let elements: Node<Msg> = flexbox(
+            vec![],
+            vec![
+                button(vec![id("first")]),
+                button(vec![id("middle")]),
+                checkbox(vec![id("last")]),
+            ],
+        );
+        let with_text: Node<Msg> = flexbox(
+            vec![],
+            vec![
+                sauron_vdom::builder::text("first"),
+                button(vec![id("middle")]),
+                button(vec![id("last")]),
+            ],
+        );
+        let ids = |root: &dyn Widget<Msg>| -> Vec<Option<String>> {
+            root.children()
+                .unwrap()
+                .iter()
+                .map(|child| child.get_id().clone())
+                .collect()
+        };
+        let mut root = create_widget(&elements).unwrap();
+        patch(root.as_mut(), &elements, &with_text);
+        assert_eq!(
+            ids(root.as_ref()),
+            vec![Some("middle".to_string()), Some("last".to_string())]
+        );
+        assert!(
+            find_by_id_as::<Button<Msg>, _>(root.as_ref(), "last").is_some()
+        );
+
+        patch(root.as_mut(), &with_text, &elements);
+        assert_eq!(
+            ids(root.as_ref()),
+            vec![
+                Some("first".to_string()),
+                Some("middle".to_string()),
+                Some("last".to_string())
+            ]
+        );
+        assert!(
+            find_by_id_as::<Checkbox<Msg>, _>(root.as_ref(), "last").is_some()
+        );
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum FormMsg {
+        Name(String),
+    }
+
+    struct Form {
+        name: String,
+    }
+
+    impl Component<FormMsg> for Form {
+        fn view(&self) -> Node<FormMsg> {
+            flexbox(
+                vec![],
+                vec![text_input(vec![
+                    id("name"),
+                    value(&self.name),
+                    on_input(|event| {
+                        match event {
+                            sauron_vdom::Event::InputEvent(input) => {
+                                FormMsg::Name(input.value.to_string())
+                            }
+                            _ => unreachable!(),
+                        }
+                    }),
+                ])],
+            )
+        }
+
+        fn update(&mut self, msg: FormMsg) -> Vec<Cmd> {
+            match msg {
+                FormMsg::Name(name) => self.name = name,
+            }
+            vec![]
+        }
+    }
+
+    #[test]
+    fn typing_into_text_input() {
+        let program = Program::new(Form {
+            name: "iv".to_string(),
+        });
+        let mut root = program.create_root();
+        let typed = root.children_mut().unwrap()[0]
+            .process_event(Event::Key(KeyCode::Char('a').into()));
+        assert_eq!(typed, vec![FormMsg::Name("iva".to_string())]);
+        for msg in typed {
+            program.dispatch(msg, root.as_mut());
+        }
+        assert_eq!(
+            find_by_id_as::<TextInput<FormMsg>, _>(root.as_ref(), "name")
+                .unwrap()
+                .get_value(),
+            "iva"
+        );
+        // moving the cursor does not change the value
+        let moved = root.children_mut().unwrap()[0]
+            .process_event(Event::Key(KeyCode::Left.into()));
+        assert!(moved.is_empty());
+    }
+}
@@ -0,0 +1,187 @@
+//! Functions to build the view of a component
+use super::{
+    Attribute,
+    Node,
+};
+use sauron_vdom::{
+    builder,
+    Callback,
+    Value,
+};
+
+/// create a node of the widget with this tag
+pub fn widget<MSG>(
+    tag: &'static str,
+    attrs: Vec<Attribute<MSG>>,
+    children: Vec<Node<MSG>>,
+) -> Node<MSG> {
+    builder::element(tag, attrs, children)
+}
+
+/// a flexbox containing the children
+pub fn flexbox<MSG>(
+    attrs: Vec<Attribute<MSG>>,
+    children: Vec<Node<MSG>>,
+) -> Node<MSG> {
+    widget("flexbox", attrs, children)
+}
+
+/// a groupbox containing the children
+pub fn group_box<MSG>(
+    attrs: Vec<Attribute<MSG>>,
+    children: Vec<Node<MSG>>,
+) -> Node<MSG> {
+    widget("groupbox", attrs, children)
+}
+
+/// a tabbox, each of the children is the content of a tab
+pub fn tab_box<MSG>(
+    attrs: Vec<Attribute<MSG>>,
+    children: Vec<Node<MSG>>,
+) -> Node<MSG> {
+    widget("tabbox", attrs, children)
+}
+
+/// an overlay layer centered on the screen containing the children
+pub fn layer<MSG>(
+    attrs: Vec<Attribute<MSG>>,
+    children: Vec<Node<MSG>>,
+) -> Node<MSG> {
+    widget("layer", attrs, children)
+}
+
+/// a button
+pub fn button<MSG>(attrs: Vec<Attribute<MSG>>) -> Node<MSG> {
+    widget("button", attrs, vec![])
+}
+
+/// a checkbox
+pub fn checkbox<MSG>(attrs: Vec<Attribute<MSG>>) -> Node<MSG> {
+    widget("checkbox", attrs, vec![])
+}
+
+/// a radio button
+pub fn radio<MSG>(attrs: Vec<Attribute<MSG>>) -> Node<MSG> {
+    widget("radio", attrs, vec![])
+}
+
+/// a single line text input
+pub fn text_input<MSG>(attrs: Vec<Attribute<MSG>>) -> Node<MSG> {
+    widget("textinput", attrs, vec![])
+}
+
+/// a multi line text area
+pub fn text_area<MSG>(attrs: Vec<Attribute<MSG>>) -> Node<MSG> {
+    widget("textarea", attrs, vec![])
+}
+
+/// a slider
+pub fn slider<MSG>(attrs: Vec<Attribute<MSG>>) -> Node<MSG> {
+    widget("slider", attrs, vec![])
+}
+
+/// a list of items
+pub fn list_box<MSG>(attrs: Vec<Attribute<MSG>>) -> Node<MSG> {
+    widget("listbox", attrs, vec![])
+}
+
+/// a drop down box to choose one of the items
+pub fn combo_box<MSG>(attrs: Vec<Attribute<MSG>>) -> Node<MSG> {
+    widget("combobox", attrs, vec![])
+}
+
+/// embed the view of a sub component which has its own msg type,
+/// the msgs it emits are converted into the parent msg with f
+pub fn map_msg<CMSG, MSG, F>(node: Node<CMSG>, f: F) -> Node<MSG>
+where
+    CMSG: 'static,
+    MSG: 'static,
+    F: Fn(CMSG) -> MSG + 'static,
+{
+    node.map_msg(f)
+}
+
+/// create an attribute with this name and value
+pub fn attr<MSG, V>(name: &'static str, value: V) -> Attribute<MSG>
+where
+    V: Into<Value>,
+{
+    builder::attr(name, value)
+}
+
+/// listen to the event with this name, such as "click" or "input"
+pub fn on<MSG, F>(event: &'static str, f: F) -> Attribute<MSG>
+where
+    F: Fn(sauron_vdom::Event) -> MSG + 'static,
+{
+    builder::on(event, Callback::from(f))
+}
+
+/// the id of the widget
+pub fn id<MSG>(id: &str) -> Attribute<MSG> {
+    attr("id", id.to_string())
+}
+
+/// the classes of the widget, separated by space
+pub fn class<MSG>(class: &str) -> Attribute<MSG> {
+    attr("class", class.to_string())
+}
+
+/// the css-like inline style of the widget, such as `flex-grow: 1; color: cyan`.
+/// The style is parsed when the widget is created or patched,
+/// an invalid style is logged and ignored.
+pub fn style<MSG>(style: &str) -> Attribute<MSG> {
+    attr("style", style.to_string())
+}
+
+/// the label of a button, checkbox, radio or groupbox
+pub fn label<MSG, S: ToString>(label: S) -> Attribute<MSG> {
+    attr("label", label.to_string())
+}
+
+/// the value of a text input, text area, slider or combobox
+pub fn value<MSG, V>(value: V) -> Attribute<MSG>
+where
+    V: Into<Value>,
+{
+    attr("value", value)
+}
+
+/// the checked state of a checkbox or a radio button
+pub fn checked<MSG>(checked: bool) -> Attribute<MSG> {
+    attr("checked", checked)
+}
+
+/// lay out the children of a box horizontally or vertically
+pub fn horizontal<MSG>(horizontal: bool) -> Attribute<MSG> {
+    attr("horizontal", horizontal)
+}
+
+/// the labels of the tabs in a tabbox, or the items in a listbox or a combobox
+pub fn labels<MSG, S: ToString>(labels: &[S]) -> Attribute<MSG> {
+    attr(
+        "labels",
+        Value::Vec(
+            labels
+                .iter()
+                .map(|label| Value::String(label.to_string()))
+                .collect(),
+        ),
+    )
+}
+
+/// called when a button is clicked
+pub fn on_click<MSG, F>(f: F) -> Attribute<MSG>
+where
+    F: Fn(sauron_vdom::Event) -> MSG + 'static,
+{
+    on("click", f)
+}
+
+/// called when the value of the widget is changed by the user
+pub fn on_input<MSG, F>(f: F) -> Attribute<MSG>
+where
+    F: Fn(sauron_vdom::Event) -> MSG + 'static,
+{
+    on("input", f)
+}
@@ -1,4 +1,7 @@
-use crate::Widget;
+use crate::{
+    widget_id::WidgetId,
+    Widget,
+};
 
 /// Traverse the node tree until the node_idx is found
 fn find_node<'a, MSG>(
@@ -6,13 +9,13 @@ fn find_node<'a, MSG>(
     node_idx: usize,
     cur_index: &mut usize,
 ) -> Option<&'a dyn Widget<MSG>> {
-    if let Some(children) = node.children() {
+    if node_idx == *cur_index {
+        return Some(node);
+    } else if let Some(children) = node.children() {
         children.iter().find_map(|child| {
             *cur_index += 1;
             find_node(child.as_ref(), node_idx, cur_index)
         })
-    } else if node_idx == *cur_index {
-        return Some(node);
     } else {
         None
     }
@@ -52,6 +55,74 @@ pub fn find_widget_mut<MSG>(
     find_node_mut(root_widget, node_idx, &mut 0)
 }
 
+fn find_widget_id_path<MSG>(
+    node: &dyn Widget<MSG>,
+    widget_id: WidgetId,
+    path: &mut Vec<WidgetId>,
+) -> bool {
+    let node_id = match node.get_widget_id() {
+        Some(node_id) => node_id,
+        None => return false,
+    };
+    path.push(node_id);
+    if node_id == widget_id {
+        return true;
+    }
+    if let Some(children) = node.children() {
+        for child in children.iter() {
+            if find_widget_id_path(child.as_ref(), widget_id, path) {
+                return true;
+            }
+        }
+    }
+    path.pop();
+    false
+}
+
+/// returns the widget ids from the root_widget down to the widget with this widget_id,
+/// the path is empty if the widget is not in the tree
+pub fn find_widget_path<MSG>(
+    root_widget: &dyn Widget<MSG>,
+    widget_id: WidgetId,
+) -> Vec<WidgetId> {
+    let mut path = vec![];
+    find_widget_id_path(root_widget, widget_id, &mut path);
+    path
+}
+
+/// returns a reference to the widget from the root widget tree matching the supplied widget_id
+pub fn find_widget_by_widget_id<MSG>(
+    root_widget: &dyn Widget<MSG>,
+    widget_id: WidgetId,
+) -> Option<&dyn Widget<MSG>> {
+    if root_widget.get_widget_id() == Some(widget_id) {
+        Some(root_widget)
+    } else if let Some(children) = root_widget.children() {
+        children.iter().find_map(|child| {
+            find_widget_by_widget_id(child.as_ref(), widget_id)
+        })
+    } else {
+        None
+    }
+}
+
+/// returns a mutable reference to the widget from the root widget tree matching the supplied
+/// widget_id
+pub fn find_widget_by_widget_id_mut<MSG>(
+    root_widget: &mut dyn Widget<MSG>,
+    widget_id: WidgetId,
+) -> Option<&mut dyn Widget<MSG>> {
+    if root_widget.get_widget_id() == Some(widget_id) {
+        Some(root_widget)
+    } else if let Some(children) = root_widget.children_mut() {
+        children.iter_mut().find_map(|child| {
+            find_widget_by_widget_id_mut(child.as_mut(), widget_id)
+        })
+    } else {
+        None
+    }
+}
+
 /// returns a reference to the widget from the root widget tree matching the supplied id
 pub fn find_widget_by_id<'a, MSG>(
     root_widget: &'a dyn Widget<MSG>,
@@ -141,4 +212,30 @@ mod test {
         assert_eq!(*got_btn2.get_id(), Some("btn2".to_string()));
         assert_eq!(got_btn2, &btn2_clone);
     }
+
+    #[test]
+    fn find_by_widget_id() {
+        let mut root = FlexBox::<()>::new();
+        root.add_child(Box::new(Button::new("btn1")));
+        let mut row = FlexBox::new();
+        row.add_child(Box::new(Button::new("btn2")));
+        root.add_child(Box::new(row));
+        let btn2_id = root.children().unwrap()[1].children().unwrap()[0]
+            .get_widget_id()
+            .expect("must be assigned on insertion");
+
+        // the id stays with the widget when the widgets before it are removed
+        root.take_child(0);
+        let btn2 = find_widget_by_widget_id(&root, btn2_id)
+            .expect("must return a widget")
+            .as_any()
+            .downcast_ref::<Button<()>>()
+            .expect("must be button");
+        assert_eq!(btn2, &Button::new("btn2"));
+        // the root has no id until the tree is laid out
+        assert!(find_widget_path(&root, btn2_id).is_empty());
+        crate::widget_id::assign_widget_ids(&mut root);
+        assert_eq!(find_widget_path(&root, btn2_id).len(), 3);
+        assert!(find_widget_by_widget_id_mut(&mut root, btn2_id).is_some());
+    }
 }
@@ -0,0 +1,271 @@
+//! Keyboard focus traversal of the widget tree.
+//!
+//! Widgets are identified by their widget id, so the focus stays on the same widget
+//! even when the widgets before it are added or removed.
+use crate::{
+    widget_id::WidgetId,
+    LayoutTree,
+    Widget,
+};
+
+/// The direction used in moving the focus spatially
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FocusDirection {
+    /// move the focus to the nearest widget above the focused widget
+    Up,
+    /// move the focus to the nearest widget below the focused widget
+    Down,
+    /// move the focus to the nearest widget on the left of the focused widget
+    Left,
+    /// move the focus to the nearest widget on the right of the focused widget
+    Right,
+}
+
+/// call f on each of the widget in the tree in depth first order
+fn walk<'a, MSG>(
+    node: &'a dyn Widget<MSG>,
+    f: &mut dyn FnMut(&'a dyn Widget<MSG>),
+) {
+    f(node);
+    if let Some(children) = node.children() {
+        for child in children.iter() {
+            walk(child.as_ref(), f);
+        }
+    }
+}
+
+/// returns the widget id of all the focusable widgets in document order,
+/// disabled widgets are left out
+pub fn focusable_widgets<MSG>(root: &dyn Widget<MSG>) -> Vec<WidgetId> {
+    let mut widgets = vec![];
+    walk(root, &mut |widget| {
+        if widget.is_focusable() && !widget.is_disabled() {
+            widgets.extend(widget.get_widget_id());
+        }
+    });
+    widgets
+}
+
+/// returns the widget id of the widget which is currently focused
+pub fn focused_widget<MSG>(root: &dyn Widget<MSG>) -> Option<WidgetId> {
+    let mut focused = None;
+    walk(root, &mut |widget| {
+        if focused.is_none() && widget.is_focused() {
+            focused = widget.get_widget_id();
+        }
+    });
+    focused
+}
+
+/// returns the widget id of the widget with this id
+pub fn widget_with_id<MSG>(
+    root: &dyn Widget<MSG>,
+    id: &str,
+) -> Option<WidgetId> {
+    let mut found = None;
+    walk(root, &mut |widget| {
+        if found.is_none() && widget.get_id().as_deref() == Some(id) {
+            found = widget.get_widget_id();
+        }
+    });
+    found
+}
+
+/// returns the next focusable widget after the current one,
+/// wrapping around to the first one when the end of the tree is reached
+pub fn next_focus<MSG>(
+    root: &dyn Widget<MSG>,
+    current: Option<WidgetId>,
+) -> Option<WidgetId> {
+    let widgets = focusable_widgets(root);
+    current
+        .and_then(|current| widgets.iter().position(|id| *id == current))
+        .and_then(|pos| widgets.get(pos + 1))
+        .or_else(|| widgets.first())
+        .copied()
+}
+
+/// returns the previous focusable widget before the current one,
+/// wrapping around to the last one when the start of the tree is reached
+pub fn prev_focus<MSG>(
+    root: &dyn Widget<MSG>,
+    current: Option<WidgetId>,
+) -> Option<WidgetId> {
+    let widgets = focusable_widgets(root);
+    current
+        .and_then(|current| widgets.iter().position(|id| *id == current))
+        .and_then(|pos| pos.checked_sub(1))
+        .and_then(|pos| widgets.get(pos))
+        .or_else(|| widgets.last())
+        .copied()
+}
+
+/// returns the focusable widget nearest to the current one in the supplied direction.
+///
+/// Widgets which are more aligned to the current widget are preferred over the widgets which
+/// are nearer but are farther off the axis of the direction.
+pub fn spatial_focus<MSG>(
+    root: &dyn Widget<MSG>,
+    layout_tree: &LayoutTree,
+    current: Option<WidgetId>,
+    direction: FocusDirection,
+) -> Option<WidgetId> {
+    let current = match current {
+        Some(current) => current,
+        None => return next_focus(root, None),
+    };
+    let layouts = layout_tree.widget_layouts();
+    let rect = |widget_id| {
+        layouts.get(&widget_id).map(|layout| {
+            (
+                layout.location.x,
+                layout.location.y,
+                layout.size.width,
+                layout.size.height,
+            )
+        })
+    };
+    let (cur_x, cur_y, cur_width, cur_height) = rect(current)?;
+    focusable_widgets(root)
+        .into_iter()
+        .filter(|widget_id| *widget_id != current)
+        .filter_map(|widget_id| {
+            let (x, y, width, height) = rect(widget_id)?;
+            // the gap between the edges along the direction,
+            // and the offset of the centers across the direction
+            let (gap, offset) = match direction {
+                FocusDirection::Up => {
+                    (
+                        cur_y - (y + height),
+                        (x + width / 2.0) - (cur_x + cur_width / 2.0),
+                    )
+                }
+                FocusDirection::Down => {
+                    (
+                        y - (cur_y + cur_height),
+                        (x + width / 2.0) - (cur_x + cur_width / 2.0),
+                    )
+                }
+                FocusDirection::Left => {
+                    (
+                        cur_x - (x + width),
+                        (y + height / 2.0) - (cur_y + cur_height / 2.0),
+                    )
+                }
+                FocusDirection::Right => {
+                    (
+                        x - (cur_x + cur_width),
+                        (y + height / 2.0) - (cur_y + cur_height / 2.0),
+                    )
+                }
+            };
+            if gap >= 0.0 {
+                Some((widget_id, gap + offset.abs() * 2.0))
+            } else {
+                None
+            }
+        })
+        .min_by(|(_, a), (_, b)| {
+            a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .map(|(widget_id, _)| widget_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::*;
+    use stretch::{
+        geometry::Size,
+        number::Number,
+    };
+
+    /// ```ignore
+    /// 0 FlexBox
+    /// 1   Button
+    /// 2   FlexBox
+    /// 3     TextInput
+    /// 4     Checkbox
+    /// 5   Button
+    /// ```
+    fn build() -> FlexBox<()> {
+        let mut root = FlexBox::new();
+        root.vertical();
+        root.add_child(Box::new(Button::new("btn1")));
+        let mut row = FlexBox::new();
+        row.horizontal();
+        row.add_child(Box::new(TextInput::new("input")));
+        row.add_child(Box::new(Checkbox::new("cb")));
+        root.add_child(Box::new(row));
+        root.add_child(Box::new(Button::new("btn2")));
+        crate::widget_id::assign_widget_ids(&mut root);
+        root
+    }
+
+    /// the widget ids in depth first order
+    fn widget_ids(root: &dyn Widget<()>) -> Vec<WidgetId> {
+        let mut ids = vec![];
+        walk(root, &mut |widget| ids.extend(widget.get_widget_id()));
+        ids
+    }
+
+    #[test]
+    fn tab_order() {
+        let root = build();
+        let ids = widget_ids(&root);
+        assert_eq!(
+            focusable_widgets(&root),
+            vec![ids[1], ids[3], ids[4], ids[5]]
+        );
+        assert_eq!(next_focus(&root, None), Some(ids[1]));
+        assert_eq!(next_focus(&root, Some(ids[1])), Some(ids[3]));
+        assert_eq!(next_focus(&root, Some(ids[5])), Some(ids[1]));
+        assert_eq!(prev_focus(&root, None), Some(ids[5]));
+        assert_eq!(prev_focus(&root, Some(ids[3])), Some(ids[1]));
+        assert_eq!(prev_focus(&root, Some(ids[1])), Some(ids[5]));
+    }
+
+    #[test]
+    fn disabled_are_not_focusable() {
+        let mut root = build();
+        let ids = widget_ids(&root);
+        root.child_mut(2).unwrap().set_disabled(true);
+        assert_eq!(focusable_widgets(&root), vec![ids[1], ids[3], ids[4]]);
+        assert_eq!(next_focus(&root, Some(ids[4])), Some(ids[1]));
+    }
+
+    #[test]
+    fn focused_widget() {
+        let mut root = build();
+        let ids = widget_ids(&root);
+        assert_eq!(super::focused_widget(&root), None);
+        layout::set_focused_widget(&mut root, Some(ids[4]));
+        assert_eq!(super::focused_widget(&root), Some(ids[4]));
+        // removing a widget before the focused one keeps the id of the focused widget
+        root.child_mut(1).unwrap().take_child(0);
+        assert_eq!(super::focused_widget(&root), Some(ids[4]));
+        assert_eq!(widget_ids(&root).len(), 5);
+    }
+
+    #[test]
+    fn spatial() {
+        let mut root = build();
+        let ids = widget_ids(&root);
+        let layout_tree = layout::compute_layout(
+            &mut root,
+            Size {
+                width: Number::Defined(40.0),
+                height: Number::Defined(20.0),
+            },
+        );
+        let spatial = |current: usize, direction| {
+            spatial_focus(&root, &layout_tree, Some(ids[current]), direction)
+        };
+        assert_eq!(spatial(1, FocusDirection::Down), Some(ids[3]));
+        assert_eq!(spatial(3, FocusDirection::Right), Some(ids[4]));
+        assert_eq!(spatial(4, FocusDirection::Left), Some(ids[3]));
+        assert_eq!(spatial(3, FocusDirection::Down), Some(ids[5]));
+        assert_eq!(spatial(3, FocusDirection::Up), Some(ids[1]));
+        assert_eq!(spatial(1, FocusDirection::Up), None);
+    }
+}
@@ -0,0 +1,473 @@
+//! Inline styles are CSS-like declarations attached to a widget, such as
+//! `width: 50%; flex-grow: 1; padding: 1; margin: 0 2; border: rounded; color: cyan`.
+//!
+//! The layout properties are merged into the `stretch::style::Style` of the widget
+//! when the layout is computed, while the visual properties are merged into the
+//! style of the theme when the widget is drawn.
+//!
+//! Lengths are in cells, or in percent of the parent when followed by `%`.
+use crate::{
+    buffer,
+    StateStyle,
+};
+use crossterm::style::Attribute;
+use std::{
+    fmt,
+    str::FromStr,
+};
+use stretch::{
+    geometry::Rect,
+    style::{
+        AlignContent,
+        AlignItems,
+        AlignSelf,
+        Dimension,
+        FlexDirection,
+        FlexWrap,
+        JustifyContent,
+        Style,
+    },
+};
+
+/// An error when parsing an inline style
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleParseError {
+    /// the declaration which can not be parsed
+    pub declaration: String,
+    /// the reason why it can not be parsed
+    pub message: String,
+}
+
+impl fmt::Display for StyleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid style {:?}: {}", self.declaration, self.message)
+    }
+}
+
+impl std::error::Error for StyleParseError {}
+
+/// The properties declared in an inline style,
+/// the properties which are None are left as they are in the style of the widget.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InlineStyle {
+    /// `flex-direction`: row, column, row-reverse or column-reverse
+    pub flex_direction: Option<FlexDirection>,
+    /// `flex-wrap`: nowrap, wrap or wrap-reverse
+    pub flex_wrap: Option<FlexWrap>,
+    /// `justify-content`: flex-start, flex-end, center, space-between, space-around or space-evenly
+    pub justify_content: Option<JustifyContent>,
+    /// `align-items`: flex-start, flex-end, center, baseline or stretch
+    pub align_items: Option<AlignItems>,
+    /// `align-self`: auto, flex-start, flex-end, center, baseline or stretch
+    pub align_self: Option<AlignSelf>,
+    /// `align-content`: flex-start, flex-end, center, stretch, space-between or space-around
+    pub align_content: Option<AlignContent>,
+    /// `flex-grow`
+    pub flex_grow: Option<f32>,
+    /// `flex-shrink`
+    pub flex_shrink: Option<f32>,
+    /// `flex-basis`
+    pub flex_basis: Option<Dimension>,
+    /// `width`
+    pub width: Option<Dimension>,
+    /// `height`
+    pub height: Option<Dimension>,
+    /// `min-width`
+    pub min_width: Option<Dimension>,
+    /// `min-height`
+    pub min_height: Option<Dimension>,
+    /// `max-width`
+    pub max_width: Option<Dimension>,
+    /// `max-height`
+    pub max_height: Option<Dimension>,
+    /// `padding`, with 1 to 4 values in the order of top, right, bottom and left
+    pub padding: Option<Rect<Dimension>>,
+    /// `margin`, with 1 to 4 values in the order of top, right, bottom and left
+    pub margin: Option<Rect<Dimension>>,
+    /// `gap`, the space in cells between the children of a flex container.
+    /// It is not a property of the stretch style, the containers add it to the margin of their children.
+    pub gap: Option<f32>,
+    /// `color`, `background`, `border-color`, `border`, `font-weight` and `text-decoration`
+    pub visual: StateStyle,
+}
+
+impl FromStr for InlineStyle {
+    type Err = StyleParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut style = InlineStyle::default();
+        for declaration in s.split(';') {
+            let declaration = declaration.trim();
+            if declaration.is_empty() {
+                continue;
+            }
+            let mut parts = declaration.splitn(2, ':');
+            let property = parts.next().unwrap_or("").trim();
+            let value = parts.next().map(str::trim).ok_or_else(|| {
+                StyleParseError {
+                    declaration: declaration.to_string(),
+                    message: "expecting `property: value`".to_string(),
+                }
+            })?;
+            style.set_property(property, value).map_err(|message| {
+                StyleParseError {
+                    declaration: declaration.to_string(),
+                    message,
+                }
+            })?;
+        }
+        Ok(style)
+    }
+}
+
+impl InlineStyle {
+    /// set the property from its css name and value
+    pub fn set_property(
+        &mut self,
+        property: &str,
+        value: &str,
+    ) -> Result<(), String> {
+        match property {
+            "flex-direction" => {
+                self.flex_direction = Some(match value {
+                    "row" => FlexDirection::Row,
+                    "column" => FlexDirection::Column,
+                    "row-reverse" => FlexDirection::RowReverse,
+                    "column-reverse" => FlexDirection::ColumnReverse,
+                    _ => return Err(unknown_value(value)),
+                })
+            }
+            "flex-wrap" => {
+                self.flex_wrap = Some(match value {
+                    "nowrap" => FlexWrap::NoWrap,
+                    "wrap" => FlexWrap::Wrap,
+                    "wrap-reverse" => FlexWrap::WrapReverse,
+                    _ => return Err(unknown_value(value)),
+                })
+            }
+            "justify-content" => {
+                self.justify_content = Some(match value {
+                    "flex-start" => JustifyContent::FlexStart,
+                    "flex-end" => JustifyContent::FlexEnd,
+                    "center" => JustifyContent::Center,
+                    "space-between" => JustifyContent::SpaceBetween,
+                    "space-around" => JustifyContent::SpaceAround,
+                    "space-evenly" => JustifyContent::SpaceEvenly,
+                    _ => return Err(unknown_value(value)),
+                })
+            }
+            "align-items" => {
+                self.align_items = Some(match value {
+                    "flex-start" => AlignItems::FlexStart,
+                    "flex-end" => AlignItems::FlexEnd,
+                    "center" => AlignItems::Center,
+                    "baseline" => AlignItems::Baseline,
+                    "stretch" => AlignItems::Stretch,
+                    _ => return Err(unknown_value(value)),
+                })
+            }
+            "align-self" => {
+                self.align_self = Some(match value {
+                    "auto" => AlignSelf::Auto,
+                    "flex-start" => AlignSelf::FlexStart,
+                    "flex-end" => AlignSelf::FlexEnd,
+                    "center" => AlignSelf::Center,
+                    "baseline" => AlignSelf::Baseline,
+                    "stretch" => AlignSelf::Stretch,
+                    _ => return Err(unknown_value(value)),
+                })
+            }
+            "align-content" => {
+                self.align_content = Some(match value {
+                    "flex-start" => AlignContent::FlexStart,
+                    "flex-end" => AlignContent::FlexEnd,
+                    "center" => AlignContent::Center,
+                    "stretch" => AlignContent::Stretch,
+                    "space-between" => AlignContent::SpaceBetween,
+                    "space-around" => AlignContent::SpaceAround,
+                    _ => return Err(unknown_value(value)),
+                })
+            }
+            "flex-grow" => self.flex_grow = Some(parse_number(value)?),
+            "flex-shrink" => self.flex_shrink = Some(parse_number(value)?),
+            "flex-basis" => self.flex_basis = Some(parse_dimension(value)?),
+            "width" => self.width = Some(parse_dimension(value)?),
+            "height" => self.height = Some(parse_dimension(value)?),
+            "min-width" => self.min_width = Some(parse_dimension(value)?),
+            "min-height" => self.min_height = Some(parse_dimension(value)?),
+            "max-width" => self.max_width = Some(parse_dimension(value)?),
+            "max-height" => self.max_height = Some(parse_dimension(value)?),
+            "padding" => self.padding = Some(parse_edges(value)?),
+            "margin" => self.margin = Some(parse_edges(value)?),
+            "gap" => self.gap = Some(parse_number(value)?),
+            "color" => self.visual.foreground = Some(parse_color(value)?),
+            "background" | "background-color" => {
+                self.visual.background = Some(parse_color(value)?)
+            }
+            "border-color" => {
+                self.visual.border_color = Some(parse_color(value)?)
+            }
+            "border" => {
+                for keyword in value.split_whitespace() {
+                    match keyword {
+                        "rounded" => self.visual.rounded_border = Some(true),
+                        "square" => self.visual.rounded_border = Some(false),
+                        "thick" => self.visual.thick_border = Some(true),
+                        "thin" => self.visual.thick_border = Some(false),
+                        color => {
+                            self.visual.border_color = Some(parse_color(color)?)
+                        }
+                    }
+                }
+            }
+            "font-weight" => {
+                match value {
+                    "bold" => self.visual.attributes.push(Attribute::Bold),
+                    "normal" => {
+                        self.visual.attributes.retain(|a| *a != Attribute::Bold)
+                    }
+                    _ => return Err(unknown_value(value)),
+                }
+            }
+            "font-style" => {
+                match value {
+                    "italic" => self.visual.attributes.push(Attribute::Italic),
+                    "normal" => {
+                        self.visual
+                            .attributes
+                            .retain(|a| *a != Attribute::Italic)
+                    }
+                    _ => return Err(unknown_value(value)),
+                }
+            }
+            "text-decoration" => {
+                match value {
+                    "underline" => {
+                        self.visual.attributes.push(Attribute::Underlined)
+                    }
+                    "line-through" => {
+                        self.visual.attributes.push(Attribute::CrossedOut)
+                    }
+                    "none" => {
+                        self.visual.attributes.retain(|a| {
+                            *a != Attribute::Underlined
+                                && *a != Attribute::CrossedOut
+                        })
+                    }
+                    _ => return Err(unknown_value(value)),
+                }
+            }
+            _ => return Err(format!("unknown property `{}`", property)),
+        }
+        Ok(())
+    }
+
+    /// returns the style with the layout properties of this inline style overriding it
+    pub fn apply(&self, mut style: Style) -> Style {
+        if let Some(flex_direction) = self.flex_direction {
+            style.flex_direction = flex_direction;
+        }
+        if let Some(flex_wrap) = self.flex_wrap {
+            style.flex_wrap = flex_wrap;
+        }
+        if let Some(justify_content) = self.justify_content {
+            style.justify_content = justify_content;
+        }
+        if let Some(align_items) = self.align_items {
+            style.align_items = align_items;
+        }
+        if let Some(align_self) = self.align_self {
+            style.align_self = align_self;
+        }
+        if let Some(align_content) = self.align_content {
+            style.align_content = align_content;
+        }
+        if let Some(flex_grow) = self.flex_grow {
+            style.flex_grow = flex_grow;
+        }
+        if let Some(flex_shrink) = self.flex_shrink {
+            style.flex_shrink = flex_shrink;
+        }
+        if let Some(flex_basis) = self.flex_basis {
+            style.flex_basis = flex_basis;
+        }
+        if let Some(width) = self.width {
+            style.size.width = width;
+        }
+        if let Some(height) = self.height {
+            style.size.height = height;
+        }
+        if let Some(min_width) = self.min_width {
+            style.min_size.width = min_width;
+        }
+        if let Some(min_height) = self.min_height {
+            style.min_size.height = min_height;
+        }
+        if let Some(max_width) = self.max_width {
+            style.max_size.width = max_width;
+        }
+        if let Some(max_height) = self.max_height {
+            style.max_size.height = max_height;
+        }
+        if let Some(padding) = self.padding {
+            style.padding = padding;
+        }
+        if let Some(margin) = self.margin {
+            style.margin = margin;
+        }
+        style
+    }
+}
+
+fn unknown_value(value: &str) -> String {
+    format!("unknown value `{}`", value)
+}
+
+fn parse_number(value: &str) -> Result<f32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("expecting a number, found `{}`", value))
+}
+
+/// a length in cells, a percentage of the parent or auto
+fn parse_dimension(value: &str) -> Result<Dimension, String> {
+    if value == "auto" {
+        Ok(Dimension::Auto)
+    } else if let Some(percent) = value.strip_suffix('%') {
+        Ok(Dimension::Percent(parse_number(percent)? / 100.0))
+    } else {
+        Ok(Dimension::Points(parse_number(value)?))
+    }
+}
+
+/// the edges in the same order as css: top, right, bottom, left.
+/// The missing edges takes the value of the opposite edge.
+fn parse_edges(value: &str) -> Result<Rect<Dimension>, String> {
+    let edges = value
+        .split_whitespace()
+        .map(parse_dimension)
+        .collect::<Result<Vec<_>, _>>()?;
+    let (top, right, bottom, left) = match edges.as_slice() {
+        [all] => (*all, *all, *all, *all),
+        [vertical, horizontal] => {
+            (*vertical, *horizontal, *vertical, *horizontal)
+        }
+        [top, horizontal, bottom] => (*top, *horizontal, *bottom, *horizontal),
+        [top, right, bottom, left] => (*top, *right, *bottom, *left),
+        _ => return Err(format!("expecting 1 to 4 values, found `{}`", value)),
+    };
+    Ok(Rect {
+        start: left,
+        end: right,
+        top,
+        bottom,
+    })
+}
+
+/// the color names are the same as in the styled text of the buffer,
+/// with either `-` or `_` separating the words
+fn parse_color(value: &str) -> Result<crossterm::style::Color, String> {
+    buffer::parse_color(&value.replace('-', "_"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        layout,
+        theme,
+        Buffer,
+        Button,
+        FlexBox,
+        Theme,
+        Widget,
+    };
+    use crossterm::style::Color;
+    use stretch::{
+        geometry::Size,
+        number::Number,
+    };
+
+    #[test]
+    fn parse() {
+        let style: InlineStyle =
+            "width: 50%; flex-grow: 1; padding: 1; margin: 0 2; border: rounded; color: cyan;"
+                .parse()
+                .unwrap();
+        assert_eq!(style.width, Some(Dimension::Percent(0.5)));
+        assert_eq!(style.flex_grow, Some(1.0));
+        assert_eq!(
+            style.padding,
+            Some(Rect {
+                start: Dimension::Points(1.0),
+                end: Dimension::Points(1.0),
+                top: Dimension::Points(1.0),
+                bottom: Dimension::Points(1.0),
+            })
+        );
+        assert_eq!(
+            style.margin,
+            Some(Rect {
+                start: Dimension::Points(2.0),
+                end: Dimension::Points(2.0),
+                top: Dimension::Points(0.0),
+                bottom: Dimension::Points(0.0),
+            })
+        );
+        assert_eq!(style.visual.rounded_border, Some(true));
+        assert_eq!(style.visual.foreground, Some(Color::Cyan));
+
+        let merged = style.apply(Style::default());
+        assert_eq!(merged.size.width, Dimension::Percent(0.5));
+        assert_eq!(merged.flex_grow, 1.0);
+        assert_eq!(merged.size.height, Dimension::Auto);
+
+        assert_eq!(
+            "colour: red".parse::<InlineStyle>(),
+            Err(StyleParseError {
+                declaration: "colour: red".to_string(),
+                message: "unknown property `colour`".to_string(),
+            })
+        );
+        assert!("width 10".parse::<InlineStyle>().is_err());
+        assert!("margin: 1 2 3 4 5".parse::<InlineStyle>().is_err());
+    }
+
+    #[test]
+    fn merge_into_widgets() {
+        let mut root = FlexBox::<()>::new();
+        root.horizontal();
+        root.set_size(Some(20.0), Some(3.0));
+        let mut half = Button::new("half");
+        half.set_inline_style(
+            "width: 50%; color: cyan; border: square".parse().unwrap(),
+        );
+        root.add_child(Box::new(half));
+        let mut rest = Button::new("rest");
+        rest.set_inline_style("flex-grow: 1".parse().unwrap());
+        root.add_child(Box::new(rest));
+
+        let layout_tree = layout::compute_layout(
+            &mut root,
+            Size {
+                width: Number::Defined(20.0),
+                height: Number::Defined(3.0),
+            },
+        );
+        let widths: Vec<f32> = layout_tree
+            .children_layout
+            .iter()
+            .map(|child| child.layout.size.width)
+            .collect();
+        assert_eq!(widths, vec![10.0, 10.0]);
+
+        let mut buf = Buffer::new(20, 3);
+        theme::draw_widget(&mut root, &mut buf, &layout_tree, &Theme::plain());
+        let corner = buf.get_cell(0, 0).unwrap();
+        assert_eq!(corner.symbol, "┌");
+        assert_eq!(corner.foreground_color, Some(Color::Cyan));
+        assert_eq!(buf.get_cell(1, 1).unwrap().symbol, "h");
+        assert_eq!(buf.get_cell(10, 0).unwrap().symbol, "╭");
+        assert_eq!(buf.get_cell(10, 0).unwrap().foreground_color, None);
+    }
+}
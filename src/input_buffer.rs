@@ -1,6 +1,7 @@
 use crossterm::event::{
     KeyCode,
     KeyEvent,
+    KeyModifiers,
 };
 
 /// Input buffer is a 1 dimensional text buffer.
@@ -112,11 +113,20 @@ impl InputBuffer {
     /// - Delete
     /// - Backspace
     /// - Char(char)
+    ///
+    /// Returns true if the key is handled. Characters typed with Ctrl or Alt are not added,
+    /// so they can be used as shortcut keys by the other widgets.
     pub fn process_key_event(
         &mut self,
-        KeyEvent { code, modifiers: _ }: KeyEvent,
-    ) {
+        KeyEvent { code, modifiers }: KeyEvent,
+    ) -> bool {
         match code {
+            KeyCode::Char(_)
+                if modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                return false;
+            }
             KeyCode::Char(c) => {
                 self.add_char(c);
             }
@@ -138,8 +148,9 @@ impl InputBuffer {
             KeyCode::Delete => {
                 self.delete();
             }
-            _ => (),
+            _ => return false,
         }
+        true
     }
 }
 
@@ -0,0 +1,355 @@
+//! Global key bindings of the renderer.
+//!
+//! A binding is a sequence of one or more key chords, such as `Ctrl-x Ctrl-s`,
+//! which triggers a built-in action of the renderer or emits a MSG to the app.
+//! Keys which are not bound are passed through to the focused widget.
+use crate::{
+    Callback,
+    FocusDirection,
+};
+use crossterm::event::{
+    KeyCode,
+    KeyEvent,
+    KeyModifiers,
+};
+use std::fmt;
+
+/// The action that is executed when a key binding is matched
+pub enum Action<MSG> {
+    /// exit the event loop of the renderer
+    Quit,
+    /// move the focus to the next focusable widget
+    FocusNext,
+    /// move the focus to the previous focusable widget
+    FocusPrev,
+    /// move the focus to the nearest widget in this direction
+    FocusTowards(FocusDirection),
+    /// clear the terminal and repaint every cell
+    Redraw,
+    /// emit the msg returned by the callback and dispatch it to the app
+    Msg(Callback<KeyEvent, MSG>),
+}
+
+impl<MSG> Clone for Action<MSG> {
+    fn clone(&self) -> Self {
+        match self {
+            Action::Quit => Action::Quit,
+            Action::FocusNext => Action::FocusNext,
+            Action::FocusPrev => Action::FocusPrev,
+            Action::FocusTowards(direction) => Action::FocusTowards(*direction),
+            Action::Redraw => Action::Redraw,
+            Action::Msg(cb) => Action::Msg(cb.clone()),
+        }
+    }
+}
+
+impl<MSG> fmt::Debug for Action<MSG> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Action::Quit => write!(f, "Quit"),
+            Action::FocusNext => write!(f, "FocusNext"),
+            Action::FocusPrev => write!(f, "FocusPrev"),
+            Action::FocusTowards(direction) => {
+                write!(f, "FocusTowards({:?})", direction)
+            }
+            Action::Redraw => write!(f, "Redraw"),
+            Action::Msg(cb) => write!(f, "Msg({:?})", cb),
+        }
+    }
+}
+
+/// The result of feeding a key into the keymap
+#[derive(Debug)]
+pub enum KeyMatch<MSG> {
+    /// the keys typed so far completed a binding
+    Action(Action<MSG>),
+    /// the keys typed so far is the start of a longer binding,
+    /// wait for the next key
+    Pending,
+    /// the keys typed so far matched no binding,
+    /// these should be passed through to the focused widget
+    Unmatched(Vec<KeyEvent>),
+}
+
+/// An error when parsing a key binding
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyParseError {
+    /// the part of the key binding which can not be parsed
+    pub key: String,
+}
+
+impl fmt::Display for KeyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unrecognized key: {:?}", self.key)
+    }
+}
+
+impl std::error::Error for KeyParseError {}
+
+/// Maps key sequences into actions.
+pub struct KeyMap<MSG> {
+    bindings: Vec<(Vec<KeyEvent>, Action<MSG>)>,
+    /// the keys typed so far which is a prefix of a multi-key binding
+    pending: Vec<KeyEvent>,
+}
+
+impl<MSG> Default for KeyMap<MSG> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<MSG> KeyMap<MSG> {
+    /// create a keymap with the default bindings:
+    ///  - `Ctrl-c`, `Ctrl-q`, `Ctrl-d` and `Ctrl-z` quits
+    ///  - `Tab` and `BackTab` moves the focus to the next and previous widget
+    ///  - `Alt` + arrow keys moves the focus spatially
+    pub fn new() -> Self {
+        let mut keymap = Self::empty();
+        for quit in &["Ctrl-c", "Ctrl-q", "Ctrl-d", "Ctrl-z"] {
+            keymap
+                .bind(quit, Action::Quit)
+                .expect("must be a valid key");
+        }
+        keymap.bind_keys(vec![key(KeyCode::Tab)], Action::FocusNext);
+        keymap.bind_keys(vec![key(KeyCode::BackTab)], Action::FocusPrev);
+        for (code, direction) in [
+            (KeyCode::Up, FocusDirection::Up),
+            (KeyCode::Down, FocusDirection::Down),
+            (KeyCode::Left, FocusDirection::Left),
+            (KeyCode::Right, FocusDirection::Right),
+        ] {
+            keymap.bind_keys(
+                vec![KeyEvent::new(code, KeyModifiers::ALT)],
+                Action::FocusTowards(direction),
+            );
+        }
+        keymap
+    }
+
+    /// create a keymap with no bindings at all,
+    /// every key will be passed to the focused widget
+    pub fn empty() -> Self {
+        KeyMap {
+            bindings: vec![],
+            pending: vec![],
+        }
+    }
+
+    /// bind the key sequence such as `Ctrl-x Ctrl-s` to this action,
+    /// replacing the previous binding of the same key sequence.
+    pub fn bind(
+        &mut self,
+        keys: &str,
+        action: Action<MSG>,
+    ) -> Result<(), KeyParseError> {
+        let keys = parse_keys(keys)?;
+        self.bind_keys(keys, action);
+        Ok(())
+    }
+
+    /// bind the key sequence to emit a clone of this msg
+    pub fn bind_msg(
+        &mut self,
+        keys: &str,
+        msg: MSG,
+    ) -> Result<(), KeyParseError>
+    where
+        MSG: Clone + 'static,
+    {
+        self.bind(keys, Action::Msg(Callback::from(move |_| msg.clone())))
+    }
+
+    /// bind the already parsed key sequence to this action
+    pub fn bind_keys(&mut self, keys: Vec<KeyEvent>, action: Action<MSG>) {
+        let keys: Vec<KeyEvent> = keys.into_iter().map(normalize).collect();
+        self.bindings.retain(|(bound, _)| *bound != keys);
+        self.bindings.push((keys, action));
+    }
+
+    /// remove the binding of this key sequence
+    pub fn unbind(&mut self, keys: &str) -> Result<(), KeyParseError> {
+        let keys = parse_keys(keys)?;
+        self.bindings.retain(|(bound, _)| *bound != keys);
+        Ok(())
+    }
+
+    /// remove all the bindings
+    pub fn clear(&mut self) {
+        self.bindings.clear();
+        self.pending.clear();
+    }
+
+    /// feed the key into the keymap and return what matched
+    pub fn process(&mut self, key_event: KeyEvent) -> KeyMatch<MSG> {
+        self.pending.push(normalize(key_event));
+        if let Some((_, action)) =
+            self.bindings.iter().find(|(keys, _)| *keys == self.pending)
+        {
+            self.pending.clear();
+            KeyMatch::Action(action.clone())
+        } else if self.bindings.iter().any(|(keys, _)| {
+            keys.len() > self.pending.len() && keys.starts_with(&self.pending)
+        }) {
+            KeyMatch::Pending
+        } else {
+            KeyMatch::Unmatched(self.pending.drain(..).collect())
+        }
+    }
+}
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::empty())
+}
+
+/// terminals does not agree on the shift modifier of the typed characters,
+/// the case of the character is enough to tell them apart
+fn normalize(key_event: KeyEvent) -> KeyEvent {
+    match key_event.code {
+        KeyCode::Char(_) | KeyCode::BackTab => {
+            KeyEvent::new(
+                key_event.code,
+                key_event.modifiers - KeyModifiers::SHIFT,
+            )
+        }
+        _ => key_event,
+    }
+}
+
+/// parse a space separated key sequence such as `Ctrl-x Ctrl-s`
+pub fn parse_keys(keys: &str) -> Result<Vec<KeyEvent>, KeyParseError> {
+    let keys = keys
+        .split_whitespace()
+        .map(parse_key)
+        .collect::<Result<Vec<_>, _>>()?;
+    if keys.is_empty() {
+        Err(KeyParseError { key: String::new() })
+    } else {
+        Ok(keys)
+    }
+}
+
+/// parse a single key chord such as `Ctrl-Alt-Delete`, `Alt-Left` or `F5`
+fn parse_key(chord: &str) -> Result<KeyEvent, KeyParseError> {
+    let err = || {
+        KeyParseError {
+            key: chord.to_string(),
+        }
+    };
+    let mut modifiers = KeyModifiers::empty();
+    let mut rest = chord;
+    loop {
+        // a dash at the end is the key itself, as in `Ctrl--`
+        let (modifier, name) = match rest.find('-') {
+            Some(dash) if dash + 1 < rest.len() => {
+                (&rest[..dash], &rest[dash + 1..])
+            }
+            _ => break,
+        };
+        match modifier.to_lowercase().as_str() {
+            "ctrl" | "c" => modifiers |= KeyModifiers::CONTROL,
+            "alt" | "m" => modifiers |= KeyModifiers::ALT,
+            "shift" | "s" => modifiers |= KeyModifiers::SHIFT,
+            _ => return Err(err()),
+        }
+        rest = name;
+    }
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => {
+            match rest.to_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                name => {
+                    match name.strip_prefix('f').map(str::parse::<u8>) {
+                        Some(Ok(n)) if (1..=12).contains(&n) => KeyCode::F(n),
+                        _ => return Err(err()),
+                    }
+                }
+            }
+        }
+    };
+    Ok(normalize(KeyEvent::new(code, modifiers)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_keys("Ctrl-x Ctrl-s"), Ok(vec![ctrl('x'), ctrl('s')]));
+        assert_eq!(
+            parse_keys("Alt-Left"),
+            Ok(vec![KeyEvent::new(KeyCode::Left, KeyModifiers::ALT)])
+        );
+        assert_eq!(parse_keys("Ctrl--"), Ok(vec![ctrl('-')]));
+        assert_eq!(parse_keys("F5"), Ok(vec![key(KeyCode::F(5))]));
+        assert_eq!(parse_keys("space"), Ok(vec![key(KeyCode::Char(' '))]));
+        assert_eq!(
+            parse_keys("Hyper-x"),
+            Err(KeyParseError {
+                key: "Hyper-x".to_string()
+            })
+        );
+        assert!(parse_keys("").is_err());
+    }
+
+    #[test]
+    fn multi_key_binding() {
+        let mut keymap: KeyMap<&str> = KeyMap::empty();
+        keymap.bind_msg("Ctrl-x Ctrl-s", "save").unwrap();
+        keymap.bind_msg("Ctrl-x Ctrl-c", "quit").unwrap();
+
+        assert!(matches!(keymap.process(ctrl('x')), KeyMatch::Pending));
+        match keymap.process(ctrl('s')) {
+            KeyMatch::Action(Action::Msg(cb)) => {
+                assert_eq!(cb.emit(ctrl('s')), "save")
+            }
+            other => panic!("expecting a msg, got {:?}", other),
+        }
+        // the keys that went nowhere are passed through
+        assert!(matches!(keymap.process(ctrl('x')), KeyMatch::Pending));
+        match keymap.process(key(KeyCode::Char('a'))) {
+            KeyMatch::Unmatched(keys) => {
+                assert_eq!(keys, vec![ctrl('x'), key(KeyCode::Char('a'))])
+            }
+            other => panic!("expecting unmatched keys, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn default_quit_keys_can_be_unbound() {
+        let mut keymap: KeyMap<()> = KeyMap::new();
+        assert!(matches!(
+            keymap.process(ctrl('d')),
+            KeyMatch::Action(Action::Quit)
+        ));
+        keymap.unbind("Ctrl-d").unwrap();
+        assert!(matches!(keymap.process(ctrl('d')), KeyMatch::Unmatched(_)));
+        assert!(matches!(
+            keymap
+                .process(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT)),
+            KeyMatch::Action(Action::FocusPrev)
+        ));
+    }
+}
@@ -1,9 +1,17 @@
 use crate::{
     find_node::find_widget,
+    widget_id::{
+        self,
+        WidgetId,
+    },
     Widget,
 };
+use std::collections::HashMap;
 use stretch::{
-    geometry::Size,
+    geometry::{
+        Point,
+        Size,
+    },
     node::{
         Node,
         Stretch,
@@ -19,6 +27,7 @@ use stretch::{
 pub struct LayoutTree {
     pub(crate) layout: Layout,
     pub(crate) children_layout: Vec<LayoutTree>,
+    pub(crate) widget_id: WidgetId,
 }
 
 impl LayoutTree {
@@ -51,6 +60,55 @@ impl LayoutTree {
     pub fn hit(&self, x: f32, y: f32) -> Vec<usize> {
         self.at_location(x, y, &mut 0)
     }
+
+    /// the id of the widget this layout is computed for
+    pub fn widget_id(&self) -> WidgetId {
+        self.widget_id
+    }
+
+    /// get the ids of the widgets that are hit at this location,
+    /// from the outermost to the deepest widget
+    pub fn hit_widgets(&self, x: f32, y: f32) -> Vec<WidgetId> {
+        let mut hits = vec![];
+        self.widgets_at_location(x, y, &mut hits);
+        hits
+    }
+
+    fn widgets_at_location(&self, x: f32, y: f32, hits: &mut Vec<WidgetId>) {
+        let loc = self.layout.location;
+        let width = self.layout.size.width;
+        let height = self.layout.size.height;
+        if x >= loc.x && x < loc.x + width && y >= loc.y && y < loc.y + height {
+            hits.push(self.widget_id);
+        }
+        for child in self.children_layout.iter() {
+            child.widgets_at_location(x - loc.x, y - loc.y, hits);
+        }
+    }
+
+    /// returns the layout of each widget in this tree keyed by their widget id,
+    /// the locations are in absolute position instead of relative to the parent
+    pub fn widget_layouts(&self) -> HashMap<WidgetId, Layout> {
+        let mut layouts = HashMap::new();
+        self.collect_layouts(0.0, 0.0, &mut layouts);
+        layouts
+    }
+
+    fn collect_layouts(
+        &self,
+        parent_x: f32,
+        parent_y: f32,
+        layouts: &mut HashMap<WidgetId, Layout>,
+    ) {
+        let x = parent_x + self.layout.location.x;
+        let y = parent_y + self.layout.location.y;
+        let mut layout = self.layout;
+        layout.location = Point { x, y };
+        layouts.insert(self.widget_id, layout);
+        for child in self.children_layout.iter() {
+            child.collect_layouts(x, y, layouts);
+        }
+    }
 }
 
 #[allow(unused)]
@@ -69,39 +127,17 @@ pub fn widget_hit_at<'a, MSG>(
     }
 }
 
-/// get the widget that hits at this location
-pub fn widget_node_idx_at<'a>(
-    layout_tree: &LayoutTree,
-    x: f32,
-    y: f32,
-) -> Option<usize> {
-    layout_tree.hit(x, y).pop()
-}
-
-/// set the node with idx to be in focused
-pub fn set_focused_node<'a, MSG>(
-    node: &'a mut dyn Widget<MSG>,
-    node_idx: usize,
-) {
-    set_focused_widget(node, node_idx, &mut 0)
-}
-
-/// Set the node at node_idx as focused, while the rest
-/// should be set to false
-fn set_focused_widget<'a, MSG>(
-    node: &'a mut dyn Widget<MSG>,
-    node_idx: usize,
-    cur_index: &mut usize,
+/// Set the widget with this widget_id as focused, while the rest
+/// should be set to false. If widget_id is None all of the widgets will be unfocused
+pub fn set_focused_widget<MSG>(
+    node: &mut dyn Widget<MSG>,
+    widget_id: Option<WidgetId>,
 ) {
-    if node_idx == *cur_index {
-        node.set_focused(true);
-    } else if let Some(children) = node.children_mut() {
-        children.iter_mut().for_each(|child| {
-            *cur_index += 1;
-            set_focused_widget(child.as_mut(), node_idx, cur_index)
-        })
-    } else {
-        node.set_focused(false);
+    node.set_focused(widget_id.is_some() && node.get_widget_id() == widget_id);
+    if let Some(children) = node.children_mut() {
+        children
+            .iter_mut()
+            .for_each(|child| set_focused_widget(child.as_mut(), widget_id))
     }
 }
 
@@ -110,6 +146,7 @@ pub fn compute_layout<MSG>(
     control: &mut dyn Widget<MSG>,
     parent_size: Size<Number>,
 ) -> LayoutTree {
+    widget_id::assign_widget_ids(control);
     let mut stretch = Stretch::new();
     let node = control
         .style_node(&mut stretch)
@@ -118,23 +155,57 @@ pub fn compute_layout<MSG>(
         .compute_layout(node, parent_size)
         .expect("must compute layout");
 
-    derive_layout_tree(node, &stretch)
+    derive_layout_tree(node, control, &stretch)
+}
+
+/// Compute the layout of an overlay layer and its children apart from the tree the layer is in,
+/// since the layer itself takes no space in the layout of that tree
+pub(crate) fn compute_layer_layout<MSG>(
+    layer: &mut dyn Widget<MSG>,
+    parent_size: Size<Number>,
+) -> LayoutTree {
+    widget_id::assign_widget_ids(layer);
+    let mut stretch = Stretch::new();
+    let children = layer
+        .children()
+        .unwrap_or(&[])
+        .iter()
+        .filter_map(|child| child.style_node(&mut stretch))
+        .collect();
+    let node = stretch
+        .new_node(layer.computed_style(), children)
+        .expect("must compute style node");
+    stretch
+        .compute_layout(node, parent_size)
+        .expect("must compute layout");
+
+    derive_layout_tree(node, layer, &stretch)
 }
 
 /// retrieve the layout for each of the invidual unit in the node.
 /// The locatio is in absolute position by adding the parent position to the child position
 /// in order to easily draw the widgets independently
-fn derive_layout_tree(node: Node, stretch: &Stretch) -> LayoutTree {
+fn derive_layout_tree<MSG>(
+    node: Node,
+    widget: &dyn Widget<MSG>,
+    stretch: &Stretch,
+) -> LayoutTree {
     let layout = *stretch.layout(node).expect("must have layout");
     let children: Vec<Node> =
         stretch.children(node).expect("must get children");
     let children_layout: Vec<LayoutTree> = children
         .into_iter()
-        .map(|child| derive_layout_tree(child, stretch))
+        .zip(widget.children().unwrap_or(&[]))
+        .map(|(child, child_widget)| {
+            derive_layout_tree(child, child_widget.as_ref(), stretch)
+        })
         .collect();
     LayoutTree {
         layout,
         children_layout,
+        widget_id: widget
+            .get_widget_id()
+            .expect("widget ids must be assigned before the layout"),
     }
 }
 
@@ -39,50 +39,120 @@
     unstable_features,
     unused_import_braces
 )]
+pub use backend::{
+    Backend,
+    CrosstermBackend,
+    HeadlessBackend,
+};
 pub use buffer::{
     Buffer,
     Cell,
+    SnapshotError,
 };
 pub use cmd::Cmd;
+pub use component::{
+    Component,
+    Program,
+};
 pub use crossterm;
 pub use find_node::{
     find_widget,
     find_widget_by_id,
     find_widget_by_id_mut,
+    find_widget_by_widget_id,
+    find_widget_by_widget_id_mut,
     find_widget_mut,
+    find_widget_path,
+};
+pub use focus::FocusDirection;
+pub use inline_style::{
+    InlineStyle,
+    StyleParseError,
 };
 pub use input_buffer::InputBuffer;
+pub use keymap::{
+    Action,
+    KeyMap,
+    KeyMatch,
+    KeyParseError,
+};
 pub use layout::LayoutTree;
+pub use query::{
+    ancestors,
+    descendants,
+    find_all_of_type,
+    find_by_class,
+    find_by_id_as,
+    find_by_id_as_mut,
+    find_by_tag,
+    for_each_of_type_mut,
+    parent,
+    Descendants,
+};
+#[cfg(feature = "async")]
+pub use renderer::Task;
 pub use renderer::{
     Dispatch,
     Renderer,
+    Viewport,
 };
 pub use sauron_vdom::Callback;
 pub use stretch;
+pub use subscription::Subscriptions;
+pub use theme::{
+    Palette,
+    StateStyle,
+    Theme,
+    WidgetState,
+};
 pub use widget::{
     Button,
     Checkbox,
+    ComboBox,
+    Dialog,
+    Flex,
     FlexBox,
     GroupBox,
     Image,
+    Layer,
     ListBox,
+    ListSource,
+    MapMsg,
+    Placement,
     Radio,
     Slider,
     SvgImage,
     TabBox,
     TextArea,
     TextInput,
+    VirtualList,
     Widget,
 };
+pub use widget_event::{
+    HoverEvent,
+    Phase,
+    WidgetEvent,
+};
+pub use widget_id::WidgetId;
 
 mod area_buffer;
+pub mod backend;
 mod buffer;
 mod cmd;
 pub mod command;
+pub mod component;
 mod find_node;
+mod focus;
+mod inline_style;
 mod input_buffer;
+pub mod keymap;
 pub(crate) mod layout;
+mod query;
 pub mod renderer;
+mod subscription;
 #[allow(unused)]
 mod symbol;
+mod theme;
 mod widget;
+mod widget_event;
+mod widget_id;
@@ -0,0 +1,252 @@
+//! Query the widget tree by the type, id, class or tag of the widgets,
+//! and by their relation to the other widgets.
+//!
+//! The functions which return a concrete type does the downcast of the widgets,
+//! so the caller doesn't have to go through `as_any`.
+use crate::{
+    find_node::{
+        find_widget_by_id,
+        find_widget_by_id_mut,
+    },
+    widget_id::WidgetId,
+    Widget,
+};
+use std::any::Any;
+
+/// An iterator over the descendants of a widget in depth first order
+pub struct Descendants<'a, MSG> {
+    stack: Vec<&'a dyn Widget<MSG>>,
+}
+
+impl<'a, MSG> Iterator for Descendants<'a, MSG> {
+    type Item = &'a dyn Widget<MSG>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let widget = self.stack.pop()?;
+        if let Some(children) = widget.children() {
+            // pushed in reverse so the first child is visited first
+            self.stack
+                .extend(children.iter().rev().map(|child| child.as_ref()));
+        }
+        Some(widget)
+    }
+}
+
+/// iterate over all the descendants of this widget in depth first order,
+/// the widget itself is not included
+pub fn descendants<MSG>(widget: &dyn Widget<MSG>) -> Descendants<'_, MSG> {
+    let stack = match widget.children() {
+        Some(children) => {
+            children.iter().rev().map(|child| child.as_ref()).collect()
+        }
+        None => vec![],
+    };
+    Descendants { stack }
+}
+
+/// returns the widgets in the tree, including the root, which are of type T
+pub fn find_all_of_type<T, MSG>(root: &dyn Widget<MSG>) -> Vec<&T>
+where
+    T: Any,
+{
+    std::iter::once(root)
+        .chain(descendants(root))
+        .filter_map(|widget| widget.as_any().downcast_ref::<T>())
+        .collect()
+}
+
+/// call f on each of the widget in the tree, including the root, which is of type T
+pub fn for_each_of_type_mut<T, MSG>(
+    root: &mut dyn Widget<MSG>,
+    f: &mut dyn FnMut(&mut T),
+) where
+    T: Any,
+{
+    if let Some(widget) = root.as_any_mut().downcast_mut::<T>() {
+        f(widget);
+    }
+    if let Some(children) = root.children_mut() {
+        for child in children.iter_mut() {
+            for_each_of_type_mut(child.as_mut(), f);
+        }
+    }
+}
+
+/// returns the widget with this id downcasted into T,
+/// None if there is no widget with the id or if it is not of type T
+pub fn find_by_id_as<'a, T, MSG>(
+    root: &'a dyn Widget<MSG>,
+    id: &str,
+) -> Option<&'a T>
+where
+    T: Any,
+{
+    find_widget_by_id(root, id)?.as_any().downcast_ref::<T>()
+}
+
+/// returns a mutable reference to the widget with this id downcasted into T,
+/// None if there is no widget with the id or if it is not of type T
+pub fn find_by_id_as_mut<'a, T, MSG>(
+    root: &'a mut dyn Widget<MSG>,
+    id: &str,
+) -> Option<&'a mut T>
+where
+    T: Any,
+{
+    find_widget_by_id_mut(root, id)?
+        .as_any_mut()
+        .downcast_mut::<T>()
+}
+
+/// returns the widgets in the tree, including the root, which has this class
+pub fn find_by_class<'a, MSG>(
+    root: &'a dyn Widget<MSG>,
+    class: &str,
+) -> Vec<&'a dyn Widget<MSG>> {
+    std::iter::once(root)
+        .chain(descendants(root))
+        .filter(|widget| widget.get_classes().iter().any(|c| c == class))
+        .collect()
+}
+
+/// returns the widgets in the tree, including the root, which has this tag
+pub fn find_by_tag<'a, MSG>(
+    root: &'a dyn Widget<MSG>,
+    tag: &str,
+) -> Vec<&'a dyn Widget<MSG>> {
+    std::iter::once(root)
+        .chain(descendants(root))
+        .filter(|widget| widget.get_tag() == tag)
+        .collect()
+}
+
+/// returns the ancestors of the widget with this widget_id, the nearest first.
+/// Returns an empty vec if the widget is the root or is not in the tree.
+/// Only the widget itself needs a widget_id, the ancestors are found by their position.
+pub fn ancestors<MSG>(
+    root: &dyn Widget<MSG>,
+    widget_id: WidgetId,
+) -> Vec<&dyn Widget<MSG>> {
+    let mut path = vec![];
+    if path_to(root, widget_id, &mut path) {
+        path.reverse();
+        path
+    } else {
+        vec![]
+    }
+}
+
+/// push the widgets from this widget down to the parent of the widget with this widget_id,
+/// returns false and leaves the path unchanged if the widget is not found
+fn path_to<'a, MSG>(
+    widget: &'a dyn Widget<MSG>,
+    widget_id: WidgetId,
+    path: &mut Vec<&'a dyn Widget<MSG>>,
+) -> bool {
+    if widget.get_widget_id() == Some(widget_id) {
+        return true;
+    }
+    if let Some(children) = widget.children() {
+        path.push(widget);
+        for child in children.iter() {
+            if path_to(child.as_ref(), widget_id, path) {
+                return true;
+            }
+        }
+        path.pop();
+    }
+    false
+}
+
+/// returns the parent of the widget with this widget_id
+pub fn parent<MSG>(
+    root: &dyn Widget<MSG>,
+    widget_id: WidgetId,
+) -> Option<&dyn Widget<MSG>> {
+    ancestors(root, widget_id).into_iter().next()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::*;
+
+    /// ```ignore
+    /// FlexBox
+    ///   Button#save.primary
+    ///   FlexBox.form
+    ///     TextInput#name
+    ///     Button#cancel
+    /// ```
+    fn build() -> FlexBox<()> {
+        let mut root = FlexBox::new();
+        let mut save = Button::new("save");
+        save.set_id("save");
+        save.add_class("primary");
+        root.add_child(Box::new(save));
+        let mut form = FlexBox::new();
+        form.add_class("form");
+        let mut name = TextInput::new("");
+        Widget::<()>::set_id(&mut name, "name");
+        form.add_child(Box::new(name));
+        let mut cancel = Button::new("cancel");
+        cancel.set_id("cancel");
+        form.add_child(Box::new(cancel));
+        root.add_child(Box::new(form));
+        root
+    }
+
+    #[test]
+    fn by_type_and_id() {
+        let mut root = build();
+        assert_eq!(descendants(&root).count(), 4);
+        let buttons: Vec<&Button<()>> = find_all_of_type(&root);
+        assert_eq!(buttons.len(), 2);
+        assert_eq!(find_all_of_type::<FlexBox<()>, _>(&root).len(), 2);
+
+        assert!(find_by_id_as::<TextInput<()>, _>(&root, "name").is_some());
+        // the id exists, but it is not a text input
+        assert!(find_by_id_as::<TextInput<()>, _>(&root, "save").is_none());
+        find_by_id_as_mut::<TextInput<()>, _>(&mut root, "name")
+            .unwrap()
+            .set_value("ivan");
+        assert_eq!(
+            find_by_id_as::<TextInput<()>, _>(&root, "name")
+                .unwrap()
+                .get_value(),
+            "ivan"
+        );
+
+        let mut count = 0;
+        for_each_of_type_mut(&mut root, &mut |btn: &mut Button<()>| {
+            btn.set_label("ok");
+            count += 1;
+        });
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn by_class_tag_and_relation() {
+        let root = build();
+        let primary = find_by_class(&root, "primary");
+        assert_eq!(primary.len(), 1);
+        assert_eq!(primary[0].get_id().as_deref(), Some("save"));
+        assert_eq!(find_by_tag(&root, "button").len(), 2);
+        assert_eq!(find_by_tag(&root, "textinput").len(), 1);
+
+        let cancel = find_widget_by_id(&root, "cancel")
+            .unwrap()
+            .get_widget_id()
+            .unwrap();
+        let parent = parent(&root, cancel).unwrap();
+        assert_eq!(parent.get_classes(), &["form".to_string()]);
+        assert_eq!(ancestors(&root, cancel).len(), 2);
+        let save = find_widget_by_id(&root, "save")
+            .unwrap()
+            .get_widget_id()
+            .unwrap();
+        assert_eq!(ancestors(&root, save).len(), 1);
+        // the root was never inserted into a container, so it has no widget_id
+        assert!(root.get_widget_id().is_none());
+    }
+}
@@ -1,11 +1,35 @@
 //! Provides the core functionality of rendering to the terminal
 //! This has the event loop which calculates and process the events to the target widget
 use crate::{
+    backend::{
+        Backend,
+        CrosstermBackend,
+    },
     command,
     find_node,
+    focus::{
+        self,
+        FocusDirection,
+    },
+    keymap::{
+        Action,
+        KeyMap,
+        KeyMatch,
+    },
     layout,
+    subscription::Subscriptions,
+    theme,
+    widget_event::{
+        self,
+        HoverEvent,
+        WidgetEvent,
+    },
+    widget_id::WidgetId,
     Buffer,
+    Cmd,
     LayoutTree,
+    Placement,
+    Theme,
     Widget,
 };
 pub use crossterm::{
@@ -34,18 +58,85 @@ pub use crossterm::{
     Command,
     Result,
 };
-use std::io::Write;
+#[cfg(feature = "async")]
+use futures_timer::Delay;
+use std::{
+    collections::HashMap,
+    io::Write,
+    time::Instant,
+};
+#[cfg(feature = "async")]
+use std::{
+    future::Future,
+    pin::Pin,
+    time::Duration,
+};
 use stretch::{
-    geometry::Size,
+    geometry::{
+        Point,
+        Size,
+    },
     number::Number,
+    result::Layout,
 };
 
+/// how often `Renderer::run_async` polls a backend which has no event stream
+#[cfg(feature = "async")]
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A future which resolves into a msg that is dispatched back to the app
+#[cfg(feature = "async")]
+pub type Task<MSG> = Pin<Box<dyn Future<Output = MSG>>>;
+
 /// A Dispatch trait which the implementing APP will update
 /// its own state based on the supplied msg.
 pub trait Dispatch<MSG> {
     /// dispatch the msg and passed the root node for the implementing
     /// app to access it and change the state of the UI.
-    fn dispatch(&self, msg: MSG, root_node: &mut dyn Widget<MSG>);
+    ///
+    /// The returned cmds are then executed by the renderer,
+    /// such as quitting the app or moving the focus.
+    fn dispatch(&self, msg: MSG, root_node: &mut dyn Widget<MSG>) -> Vec<Cmd>;
+
+    /// dispatch the msg, and also returns the tasks such as network requests
+    /// which are run by `Renderer::run_async`. `Renderer::run` exits with an error
+    /// when it gets any task.
+    ///
+    /// Override this to return the tasks together with the cmds from `dispatch`.
+    #[cfg(feature = "async")]
+    fn dispatch_async(
+        &self,
+        msg: MSG,
+        root_node: &mut dyn Widget<MSG>,
+    ) -> (Vec<Cmd>, Vec<Task<MSG>>) {
+        (self.dispatch(msg, root_node), vec![])
+    }
+}
+
+/// Where in the terminal the widget tree is rendered
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Viewport {
+    /// take over the whole terminal using the alternate screen
+    Fullscreen,
+    /// render below the cursor, using as many lines as the layout needs
+    /// but not more than the supplied maximum.
+    /// The last frame is left in the scrollback when the renderer exits.
+    Inline(u16),
+}
+
+/// An overlay layer in the widget tree, laid out apart from the tree
+struct OpenLayer {
+    /// the widget ids from the root down to the layer
+    path: Vec<WidgetId>,
+    /// the layout of the layer and its children in absolute position
+    layout_tree: LayoutTree,
+    is_modal: bool,
+}
+
+impl OpenLayer {
+    fn widget_id(&self) -> WidgetId {
+        *self.path.last().expect("must have the layer in the path")
+    }
 }
 
 /// This provides the render loop of the terminal UI
@@ -55,18 +146,66 @@ pub struct Renderer<'a, MSG> {
     root_node: &'a mut dyn Widget<MSG>,
     layout_tree: LayoutTree,
     terminal_size: (u16, u16),
-    focused_widget_idx: Option<usize>,
+    focused_widget: Option<WidgetId>,
+    /// the layout of each widget in absolute position, updated with the layout tree
+    widget_layouts: HashMap<WidgetId, Layout>,
+    /// the overlay layers in the order they are drawn, the last one is on top
+    layers: Vec<OpenLayer>,
+    /// the path of the widgets under the mouse pointer, from the root to the deepest
+    hovered_path: Vec<WidgetId>,
+    /// the path of the widget which got the mouse down, it receives all the
+    /// drag and up events until the button is released even if the pointer leaves it
+    pointer_capture: Option<Vec<WidgetId>>,
+    /// the buffer of the last frame written into the terminal,
+    /// only the cells that differ from it are flushed on the next frame
+    last_buffer: Buffer,
+    backend: Box<dyn Backend>,
+    /// the theme the widgets are drawn with
+    theme: Theme,
+    viewport: Viewport,
+    /// the terminal row where the inline viewport starts
+    inline_top: u16,
+    /// the number of rows used by the inline viewport in the last frame
+    inline_height: u16,
+    keymap: KeyMap<MSG>,
+    subscriptions: Subscriptions<MSG>,
+    /// the tasks returned by the app which are not polled yet
+    #[cfg(feature = "async")]
+    tasks: Vec<Task<MSG>>,
+    /// set while the terminal is setup by the backend,
+    /// the terminal is restored on drop if the event loop did not finish
+    started: bool,
+    /// set when a quit is requested, the event loop exits at the end of the current event
+    quit: bool,
 }
 
-impl<'a, MSG> Renderer<'a, MSG> {
+impl<'a, MSG> Renderer<'a, MSG>
+where
+    MSG: 'static,
+{
     /// create a new renderer with the supplied root_node
     pub fn new(
         write: &'a mut dyn Write,
         program: Option<&'a dyn Dispatch<MSG>>,
         root_node: &'a mut dyn Widget<MSG>,
     ) -> Self {
-        let (width, height) =
-            terminal::size().expect("must get the terminal size");
+        Self::with_backend(
+            write,
+            program,
+            root_node,
+            Box::new(CrosstermBackend),
+        )
+    }
+
+    /// create a new renderer which uses the supplied backend
+    /// for the screen size, events and terminal setup
+    pub fn with_backend(
+        write: &'a mut dyn Write,
+        program: Option<&'a dyn Dispatch<MSG>>,
+        root_node: &'a mut dyn Widget<MSG>,
+        backend: Box<dyn Backend>,
+    ) -> Self {
+        let (width, height) = backend.size().expect("must get the screen size");
 
         root_node.set_size(Some((width) as f32), Some(height as f32));
 
@@ -77,134 +216,833 @@ impl<'a, MSG> Renderer<'a, MSG> {
                 height: Number::Defined(height as f32),
             },
         );
-        Renderer {
+        let widget_layouts = layout_tree.widget_layouts();
+        let mut renderer = Renderer {
             write,
             program,
             root_node,
             layout_tree,
             terminal_size: (width, height),
-            focused_widget_idx: None,
+            focused_widget: None,
+            widget_layouts,
+            layers: vec![],
+            hovered_path: vec![],
+            pointer_capture: None,
+            last_buffer: Buffer::new(width as usize, height as usize),
+            backend,
+            theme: Theme::default(),
+            viewport: Viewport::Fullscreen,
+            inline_top: 0,
+            inline_height: 0,
+            keymap: KeyMap::new(),
+            subscriptions: Subscriptions::new(),
+            #[cfg(feature = "async")]
+            tasks: vec![],
+            started: false,
+            quit: false,
+        };
+        renderer.update_layers();
+        renderer.confine_focus();
+        renderer
+    }
+
+    /// set where in the terminal the widget tree is rendered,
+    /// this needs to be set before running the renderer.
+    pub fn set_viewport(&mut self, viewport: Viewport) {
+        self.viewport = viewport;
+        let (width, height) = self.terminal_size;
+        match viewport {
+            Viewport::Fullscreen => {
+                self.root_node
+                    .set_size(Some(width as f32), Some(height as f32))
+            }
+            // the height of the root is derived from its content
+            Viewport::Inline(_) => {
+                self.root_node.set_size(Some(width as f32), None)
+            }
         }
+        self.recompute_layout();
+    }
+
+    /// set the theme the widgets are drawn with,
+    /// the widgets which has their own theme are not affected
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    /// returns the theme the widgets are drawn with
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// returns the global key bindings, which can be modified
+    /// to bind keys to msgs or remove the default bindings
+    pub fn keymap_mut(&mut self) -> &mut KeyMap<MSG> {
+        &mut self.keymap
+    }
+
+    /// replace the global key bindings
+    pub fn set_keymap(&mut self, keymap: KeyMap<MSG>) {
+        self.keymap = keymap;
+    }
+
+    /// returns the timers and channels the renderer listens to,
+    /// the msgs they emit are dispatched to the app
+    pub fn subscriptions_mut(&mut self) -> &mut Subscriptions<MSG> {
+        &mut self.subscriptions
+    }
+
+    /// returns the buffer of the last rendered frame
+    pub fn buffer(&self) -> &Buffer {
+        &self.last_buffer
     }
 
     fn recompute_layout(&mut self) {
         let (width, height) = self.terminal_size;
+        let height = match self.viewport {
+            Viewport::Fullscreen => Number::Defined(height as f32),
+            Viewport::Inline(_) => Number::Undefined,
+        };
         self.layout_tree = layout::compute_layout(
             self.root_node,
             Size {
                 width: Number::Defined(width as f32),
-                height: Number::Defined(height as f32),
+                height,
             },
         );
+        self.widget_layouts = self.layout_tree.widget_layouts();
+        self.update_layers();
+    }
+
+    /// lay out the overlay layers in the tree apart from it,
+    /// each layer is placed on the screen relative to the widget it is anchored to.
+    /// The layers which are anchored to a widget that is not in the tree are not shown.
+    fn update_layers(&mut self) {
+        let (width, height) = self.frame_size();
+        let screen = Size {
+            width: width as f32,
+            height: height as f32,
+        };
+        let mut paths = vec![];
+        find_layers(self.root_node, &mut vec![], &mut paths);
+        self.layers = vec![];
+        for path in paths {
+            let widget_id = *path.last().expect("must have the layer");
+            let layer = match find_node::find_widget_by_widget_id_mut(
+                self.root_node,
+                widget_id,
+            ) {
+                Some(layer) => layer,
+                None => continue,
+            };
+            let placement = layer.layer_placement().expect("must be a layer");
+            let is_modal = layer.is_modal();
+            let mut layout_tree = layout::compute_layer_layout(
+                layer,
+                Size {
+                    width: Number::Defined(screen.width),
+                    height: Number::Defined(screen.height),
+                },
+            );
+            let location = match place_layer(
+                placement,
+                layout_tree.layout.size,
+                screen,
+                &self.widget_layouts,
+            ) {
+                Some(location) => location,
+                None => continue,
+            };
+            layout_tree.layout.location = location;
+            // the layers on top can be anchored to the widgets in this layer
+            self.widget_layouts.extend(layout_tree.widget_layouts());
+            self.layers.push(OpenLayer {
+                path,
+                layout_tree,
+                is_modal,
+            });
+        }
+    }
+
+    /// the widget id of the topmost modal layer
+    fn modal_layer(&self) -> Option<WidgetId> {
+        self.layers
+            .iter()
+            .rev()
+            .find(|layer| layer.is_modal)
+            .map(OpenLayer::widget_id)
+    }
+
+    /// the widget which contains all the widgets the focus can move to,
+    /// which is the topmost modal layer when there is one
+    fn focus_root(&self) -> &dyn Widget<MSG> {
+        self.modal_layer()
+            .and_then(|widget_id| {
+                find_node::find_widget_by_widget_id(self.root_node, widget_id)
+            })
+            .unwrap_or(self.root_node)
+    }
+
+    /// move the focus inside of the topmost modal layer,
+    /// if the focused widget is outside of it
+    fn confine_focus(&mut self) {
+        if self.modal_layer().is_none() {
+            return;
+        }
+        let focusable = focus::focusable_widgets(self.focus_root());
+        let is_inside = self
+            .focused_widget
+            .map(|focused| focusable.contains(&focused))
+            .unwrap_or(false);
+        if !is_inside {
+            self.set_focus(focusable.first().copied());
+        }
+    }
+
+    /// the widgets that are hit at this location from the root down to the deepest widget.
+    /// The layers are hit before the widget tree, starting from the top.
+    /// Returns None if the location is outside of a modal layer, since the widgets
+    /// below it can not be hit.
+    fn hit_widgets(&self, x: f32, y: f32) -> Option<Vec<WidgetId>> {
+        for layer in self.layers.iter().rev() {
+            let hits = layer.layout_tree.hit_widgets(x, y);
+            if !hits.is_empty() {
+                // the path to the parent of the layer, followed by the layer itself
+                let mut path = layer.path.clone();
+                path.pop();
+                path.extend(hits);
+                return Some(path);
+            }
+            if layer.is_modal {
+                return None;
+            }
+        }
+        Some(self.layout_tree.hit_widgets(x, y))
+    }
+
+    /// returns the layout of the widget with this widget id in absolute position,
+    /// as computed in the last layout of the widget tree
+    pub fn widget_layout(&self, widget_id: WidgetId) -> Option<&Layout> {
+        self.widget_layouts.get(&widget_id)
+    }
+
+    /// the size of the buffer the widget tree is drawn into
+    fn frame_size(&self) -> (u16, u16) {
+        let (width, height) = self.terminal_size;
+        match self.viewport {
+            Viewport::Fullscreen => (width, height),
+            Viewport::Inline(max_height) => {
+                let content_height =
+                    self.layout_tree.layout.size.height.ceil() as u16;
+                (width, content_height.max(1).min(max_height).min(height))
+            }
+        }
+    }
+
+    /// the terminal row where the buffer is drawn
+    fn frame_top(&self) -> u16 {
+        match self.viewport {
+            Viewport::Fullscreen => 0,
+            Viewport::Inline(_) => self.inline_top,
+        }
+    }
+
+    /// grow or shrink the inline viewport to this height,
+    /// scrolling the terminal up when there is not enough lines below the viewport
+    fn fit_inline(&mut self, width: u16, height: u16) -> Result<()> {
+        if height == self.inline_height {
+            return Ok(());
+        }
+        let (_, terminal_height) = self.terminal_size;
+        let bottom = self.inline_top + height;
+        if bottom > terminal_height {
+            let scroll = bottom - terminal_height;
+            queue!(self.write, cursor::MoveTo(0, terminal_height - 1))?;
+            for _ in 0..scroll {
+                writeln!(self.write)?;
+            }
+            self.inline_top = self.inline_top.saturating_sub(scroll);
+        }
+        // clear the previous frame, the viewport is repainted entirely
+        queue!(
+            self.write,
+            cursor::MoveTo(0, self.inline_top),
+            terminal::Clear(ClearType::FromCursorDown)
+        )?;
+        self.inline_height = height;
+        self.last_buffer = Buffer::new(width as usize, height as usize);
+        Ok(())
+    }
+
+    /// clear the screen, everything is repainted on the next frame
+    fn reset_screen(&mut self) -> Result<()> {
+        match self.viewport {
+            Viewport::Fullscreen => {
+                command::reset_top(&mut self.write)?;
+                let (width, height) = self.terminal_size;
+                self.last_buffer = Buffer::new(width as usize, height as usize);
+            }
+            Viewport::Inline(_) => {
+                let (_, terminal_height) = self.terminal_size;
+                self.inline_top =
+                    self.inline_top.min(terminal_height.saturating_sub(1));
+                // forces the viewport to be cleared on the next frame
+                self.inline_height = 0;
+            }
+        }
+        Ok(())
     }
 
-    fn dispatch_msg(&mut self, msgs: Vec<MSG>) {
+    /// setup the terminal before the event loop
+    fn start(&mut self) -> Result<()> {
+        command::install_panic_hook();
+        // set before the init, so a partially setup terminal is also restored on drop
+        self.started = true;
+        self.backend.init(self.write, self.viewport)?;
+        match self.viewport {
+            Viewport::Fullscreen => command::reset_top(&mut self.write)?,
+            Viewport::Inline(_) => {
+                let (_, row) = self.backend.cursor_position()?;
+                self.inline_top = row;
+                self.inline_height = 0;
+            }
+        }
+        Ok(())
+    }
+
+    /// restore the terminal after the event loop
+    fn stop(&mut self) -> Result<()> {
+        if !self.started {
+            return Ok(());
+        }
+        self.started = false;
+        if let Viewport::Inline(_) = self.viewport {
+            // leave the last frame and move the cursor below it
+            let last_row = self.inline_top + self.inline_height.max(1) - 1;
+            queue!(self.write, cursor::MoveTo(0, last_row))?;
+            write!(self.write, "\r\n")?;
+        }
+        self.backend.finalize(self.write, self.viewport)
+    }
+
+    fn dispatch_msg(&mut self, msgs: Vec<MSG>) -> Result<()> {
+        let mut cmds = vec![];
         if let Some(program) = self.program {
             for msg in msgs {
-                program.dispatch(msg, self.root_node);
+                #[cfg(feature = "async")]
+                {
+                    let (msg_cmds, tasks) =
+                        program.dispatch_async(msg, self.root_node);
+                    cmds.extend(msg_cmds);
+                    self.tasks.extend(tasks);
+                }
+                #[cfg(not(feature = "async"))]
+                cmds.extend(program.dispatch(msg, self.root_node));
             }
         }
         self.recompute_layout();
+        // the tree may have changed, look for the widget that is still focused
+        self.focused_widget = focus::focused_widget(self.root_node);
+        self.confine_focus();
+        self.execute_cmds(cmds)
+    }
+
+    /// execute the cmds that are handled by the renderer,
+    /// the rest are written into the terminal
+    fn execute_cmds(&mut self, cmds: Vec<Cmd>) -> Result<()> {
+        for cmd in cmds {
+            match cmd {
+                Cmd::Quit => self.quit = true,
+                Cmd::FocusNext => self.focus_next(),
+                Cmd::FocusPrev => self.focus_prev(),
+                Cmd::Focus(id) => {
+                    if let Some(widget_id) =
+                        focus::widget_with_id(self.root_node, &id)
+                    {
+                        self.set_focus(Some(widget_id));
+                    }
+                }
+                Cmd::Relayout => self.recompute_layout(),
+                // the widgets locations are relative to the top of the viewport
+                Cmd::MoveTo(x, y) => {
+                    let top = self.frame_top() as usize;
+                    Cmd::MoveTo(x, top + y).execute(self.write)?
+                }
+                _ => cmd.execute(self.write)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// set the focus to the widget with this widget id,
+    /// the focus is removed from all widgets if it is None
+    fn set_focus(&mut self, widget_id: Option<WidgetId>) {
+        self.focused_widget = widget_id;
+        layout::set_focused_widget(self.root_node, widget_id);
+    }
+
+    /// returns the widget id of the focused widget
+    pub fn focused_widget(&self) -> Option<WidgetId> {
+        self.focused_widget
+    }
+
+    /// move the focus to the widget with this widget id,
+    /// the focus is not changed if there is no such widget in the tree
+    pub fn focus_widget(&mut self, widget_id: WidgetId) {
+        if find_node::find_widget_by_widget_id(self.root_node, widget_id)
+            .is_some()
+        {
+            self.set_focus(Some(widget_id));
+        }
+    }
+
+    /// move the focus to the next focusable widget
+    pub fn focus_next(&mut self) {
+        let next = focus::next_focus(self.focus_root(), self.focused_widget);
+        self.set_focus(next);
+    }
+
+    /// move the focus to the previous focusable widget
+    pub fn focus_prev(&mut self) {
+        let prev = focus::prev_focus(self.focus_root(), self.focused_widget);
+        self.set_focus(prev);
+    }
+
+    /// move the focus to the nearest focusable widget in this direction
+    pub fn focus_towards(&mut self, direction: FocusDirection) {
+        let layout_tree = self
+            .layers
+            .iter()
+            .rev()
+            .find(|layer| layer.is_modal)
+            .map(|layer| &layer.layout_tree)
+            .unwrap_or(&self.layout_tree);
+        let nearest = focus::spatial_focus(
+            self.focus_root(),
+            layout_tree,
+            self.focused_widget,
+            direction,
+        );
+        if nearest.is_some() {
+            self.set_focus(nearest);
+        }
+    }
+
+    /// send the key event to the focused widget,
+    /// if the focused widget doesn't consume it, it bubbles up to the ancestors
+    fn send_key(&mut self, key_event: KeyEvent) -> Result<()> {
+        if let Some(widget_id) = self.focused_widget {
+            let path = find_node::find_widget_path(self.root_node, widget_id);
+            if !path.is_empty() {
+                let mut widget_event =
+                    WidgetEvent::new(Event::Key(key_event), path);
+                self.propagate(&mut widget_event)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// returns true if the focused widget handles this key itself,
+    /// bypassing the keymap
+    fn focused_claims_key(&self, key_event: &KeyEvent) -> bool {
+        self.focused_widget
+            .and_then(|widget_id| {
+                find_node::find_widget_by_widget_id(self.root_node, widget_id)
+            })
+            .map(|widget| widget.claims_key(key_event))
+            .unwrap_or(false)
+    }
+
+    /// propagate the event through the widgets in its path.
+    /// The msgs are dispatched only after the propagation is done,
+    /// since the app may change the widget tree the path is pointing to.
+    fn propagate(&mut self, widget_event: &mut WidgetEvent) -> Result<()> {
+        let msgs = widget_event::propagate(
+            self.root_node,
+            widget_event,
+            &self.widget_layouts,
+            (0.0, 0.0),
+        );
+        self.dispatch_msg(msgs)
+    }
+
+    /// execute the action of a matched key binding
+    fn run_action(
+        &mut self,
+        action: Action<MSG>,
+        key_event: KeyEvent,
+    ) -> Result<()> {
+        match action {
+            Action::Quit => self.quit = true,
+            Action::FocusNext => self.focus_next(),
+            Action::FocusPrev => self.focus_prev(),
+            Action::FocusTowards(direction) => self.focus_towards(direction),
+            Action::Redraw => self.reset_screen()?,
+            Action::Msg(cb) => self.dispatch_msg(vec![cb.emit(key_event)])?,
+        }
+        Ok(())
+    }
+
+    /// draw the widget tree and write the changed cells into the terminal
+    fn draw_frame(&mut self) -> Result<()> {
+        let (width, height) = self.frame_size();
+        if let Viewport::Inline(_) = self.viewport {
+            self.fit_inline(width, height)?;
+        }
+        let mut buf = Buffer::new(width as usize, height as usize);
+        if let Some(bg) = self.theme.normal.background {
+            buf.set_background(bg);
+        }
+        let mut cmds = theme::draw_widget(
+            self.root_node,
+            &mut buf,
+            &self.layout_tree,
+            &self.theme,
+        );
+        // the layers are drawn over the widget tree, the last one on top
+        for layer in self.layers.iter() {
+            if let Some(widget) = find_node::find_widget_by_widget_id_mut(
+                self.root_node,
+                layer.widget_id(),
+            ) {
+                cmds.extend(theme::draw_widget(
+                    widget,
+                    &mut buf,
+                    &layer.layout_tree,
+                    &self.theme,
+                ));
+            }
+        }
+        buf.render_diff_at(&self.last_buffer, self.frame_top(), self.write)?;
+        self.execute_cmds(cmds)?;
+        self.write.flush()?;
+        self.last_buffer = buf;
+        Ok(())
+    }
+
+    /// process the terminal event, sending it to the widgets
+    /// and dispatching the msgs they emit
+    fn process_event(&mut self, event: Event) -> Result<()> {
+        match event {
+            Event::Key(key_event) if self.focused_claims_key(&key_event) => {
+                self.send_key(key_event)?
+            }
+            Event::Key(key_event) => {
+                match self.keymap.process(key_event) {
+                    KeyMatch::Action(action) => {
+                        self.run_action(action, key_event)?
+                    }
+                    KeyMatch::Pending => (),
+                    KeyMatch::Unmatched(key_events) => {
+                        for key_event in key_events {
+                            self.send_key(key_event)?;
+                        }
+                    }
+                }
+            }
+            // mouse clicks sets the focus to the deepest focusable widget underneath
+            // the focus is not changed when clicking outside of a modal layer
+            Event::Mouse(MouseEvent::Down(_btn, x, y, _modifier)) => {
+                if let Some(hits) = self.hit_widgets(x as f32, y as f32) {
+                    let focusable = focus::focusable_widgets(self.root_node);
+                    let hit = hits
+                        .into_iter()
+                        .rev()
+                        .find(|widget_id| focusable.contains(widget_id));
+                    self.set_focus(hit);
+                }
+            }
+            Event::Resize(width, height) => {
+                self.terminal_size = (width, height);
+                // the content of the terminal is unknown after a resize,
+                // so clear it and repaint everything on the next frame
+                self.reset_screen()?;
+                self.recompute_layout();
+            }
+            _ => (),
+        }
+        // any other activities, such as mouse scroll is
+        // sent the widget underneath the location, regardless
+        // if it focused or not.
+        // The deepest widget that is hit is the target, the rest are its ancestors.
+        if let Some((x, y)) = extract_location(&event) {
+            let hit = self.hit_widgets(x as f32, y as f32).unwrap_or_default();
+            self.update_hover(hit.clone())?;
+            let path = match event {
+                Event::Mouse(MouseEvent::Down(..)) => {
+                    if !hit.is_empty() {
+                        self.pointer_capture = Some(hit.clone());
+                    }
+                    hit
+                }
+                Event::Mouse(MouseEvent::Drag(..)) => {
+                    self.pointer_capture.clone().unwrap_or(hit)
+                }
+                Event::Mouse(MouseEvent::Up(..)) => {
+                    self.pointer_capture.take().unwrap_or(hit)
+                }
+                _ => hit,
+            };
+            if !path.is_empty() {
+                self.propagate(&mut WidgetEvent::new(event, path))?;
+            }
+        }
+        Ok(())
     }
 
-    /// run the event loop of the renderer
+    /// set the hover state of the widgets along the new path under the pointer.
+    /// The widgets that are no longer hit receive a MouseLeave, the deepest first,
+    /// then the newly hit widgets receive a MouseEnter, the outermost first.
+    fn update_hover(&mut self, path: Vec<WidgetId>) -> Result<()> {
+        if path == self.hovered_path {
+            return Ok(());
+        }
+        let left: Vec<WidgetId> = self
+            .hovered_path
+            .iter()
+            .rev()
+            .filter(|idx| !path.contains(idx))
+            .copied()
+            .collect();
+        let entered: Vec<WidgetId> = path
+            .iter()
+            .filter(|idx| !self.hovered_path.contains(idx))
+            .copied()
+            .collect();
+        self.hovered_path = path;
+
+        let mut msgs = vec![];
+        for widget_id in left {
+            if let Some(widget) = find_node::find_widget_by_widget_id_mut(
+                self.root_node,
+                widget_id,
+            ) {
+                widget.set_hovered(false);
+                msgs.extend(widget.hover_event(HoverEvent::MouseLeave));
+            }
+        }
+        for widget_id in entered {
+            if let Some(widget) = find_node::find_widget_by_widget_id_mut(
+                self.root_node,
+                widget_id,
+            ) {
+                widget.set_hovered(true);
+                msgs.extend(widget.hover_event(HoverEvent::MouseEnter));
+            }
+        }
+        if msgs.is_empty() {
+            Ok(())
+        } else {
+            self.dispatch_msg(msgs)
+        }
+    }
+
+    /// run the event loop of the renderer.
+    ///
+    /// The tasks returned from `Dispatch::dispatch_async` are not run by this loop,
+    /// it exits with an error if the app returns any, use `run_async` instead.
     pub fn run(&mut self) -> Result<()> {
-        command::init(&mut self.write)?;
-        command::reset_top(&mut self.write)?;
-        let (width, height) = self.terminal_size;
+        self.start()?;
+        let result = self.event_loop();
+        // the terminal is restored even if the loop exits early with an error
+        let stopped = self.stop();
+        result.and(stopped)
+    }
 
+    fn event_loop(&mut self) -> Result<()> {
         loop {
-            let mut buf = Buffer::new(width as usize, height as usize);
-
-            buf.reset();
+            self.draw_frame()?;
+            if self.quit {
+                break;
+            }
+            // the tasks are only polled in run_async, they are not dropped silently
+            #[cfg(feature = "async")]
             {
-                let tty_cmds = self.root_node.draw(&mut buf, &self.layout_tree);
-                buf.render(self.write)?;
-                tty_cmds.iter().for_each(|cmd| {
-                    cmd.execute(self.write).expect("must execute")
-                });
+                if !self.tasks.is_empty() {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::Other,
+                        "the tasks returned by the app can only be run with `Renderer::run_async`",
+                    )
+                    .into());
+                }
+            }
+
+            let msgs = self.subscriptions.poll(Instant::now());
+            if !msgs.is_empty() {
+                self.dispatch_msg(msgs)?;
+                // redraw before waiting for the next event
+                continue;
+            }
+            // wait for the terminal events only until the next timer is due
+            if let Some(timeout) = self.subscriptions.timeout(Instant::now()) {
+                if !self.backend.poll(timeout)? {
+                    continue;
+                }
             }
-            self.write.flush()?;
 
-            if let Ok(event) = event::read() {
+            if let Ok(event) = self.backend.read_event() {
                 match event {
-                    Event::Key(key_event) => {
-                        // To quite, press any of the following:
-                        //  - CTRL-c
-                        //  - CTRL-q
-                        //  - CTRL-d
-                        //  - CTRL-z
-                        if key_event.modifiers.contains(KeyModifiers::CONTROL) {
-                            match key_event.code {
-                                KeyCode::Char(c) => {
-                                    match c {
-                                        'c' | 'q' | 'd' | 'z' => {
-                                            break;
-                                        }
-                                        _ => (),
-                                    }
-                                }
-                                _ => (),
-                            }
+                    Some(event) => self.process_event(event)?,
+                    None => break,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// run the event loop of the renderer asynchronously,
+    /// the terminal events are read from the event stream of the backend,
+    /// or polled from it when it has none.
+    ///
+    /// The tasks returned from `Dispatch::dispatch_async` are polled along with the events,
+    /// and the msg they resolve into is dispatched back to the app.
+    #[cfg(feature = "async")]
+    pub async fn run_async(&mut self) -> Result<()> {
+        self.start()?;
+        let result = self.event_loop_async().await;
+        let stopped = self.stop();
+        result.and(stopped)
+    }
+
+    #[cfg(feature = "async")]
+    async fn event_loop_async(&mut self) -> Result<()> {
+        use futures::{
+            future::{
+                self,
+                Either,
+            },
+            stream::FuturesUnordered,
+            FutureExt,
+            StreamExt,
+        };
+
+        let mut events = self.backend.event_stream().map(StreamExt::fuse);
+        let mut tasks = FuturesUnordered::new();
+        loop {
+            self.draw_frame()?;
+            if self.quit {
+                break;
+            }
+            tasks.extend(self.tasks.drain(..));
+
+            let msgs = self.subscriptions.poll(Instant::now());
+            if !msgs.is_empty() {
+                self.dispatch_msg(msgs)?;
+                continue;
+            }
+            let mut timeout =
+                match self.subscriptions.timeout(Instant::now()) {
+                    Some(timeout) => Either::Left(Delay::new(timeout)),
+                    None => Either::Right(future::pending()),
+                }
+                .fuse();
+            // resolves into None when the backend without an event stream is to be polled,
+            // which is right away if it has an event ready, or else after an interval
+            let mut next_event = match events.as_mut() {
+                Some(events) => events.next().map(Some).left_future(),
+                None => {
+                    let interval =
+                        if self.backend.poll(Duration::from_secs(0))? {
+                            Duration::from_secs(0)
                         } else {
-                            // send the keypresses to the focused widget
-                            if let Some(idx) = self.focused_widget_idx.as_ref()
-                            {
-                                let active_widget: Option<
-                                    &mut dyn Widget<MSG>,
-                                > = find_node::find_widget_mut(
-                                    self.root_node,
-                                    *idx,
-                                );
-                                if let Some(focused_widget) = active_widget {
-                                    let msgs =
-                                        focused_widget.process_event(event);
-                                    self.dispatch_msg(msgs);
+                            POLL_INTERVAL
+                        };
+                    Delay::new(interval).map(|()| None).right_future()
+                }
+            }
+            .fuse();
+
+            futures::select_biased! {
+                msg = tasks.select_next_some() => self.dispatch_msg(vec![msg])?,
+                _ = timeout => (),
+                event = next_event => {
+                    match event {
+                        Some(Some(event)) => self.process_event(event?)?,
+                        Some(None) => break,
+                        None => {
+                            if self.backend.poll(Duration::from_secs(0))? {
+                                match self.backend.read_event()? {
+                                    Some(event) => self.process_event(event)?,
+                                    None => break,
                                 }
                             }
                         }
                     }
-                    // mouse clicks sets the focused the widget underneath
-                    Event::Mouse(MouseEvent::Down(_btn, x, y, _modifier)) => {
-                        self.focused_widget_idx = layout::widget_node_idx_at(
-                            &self.layout_tree,
-                            x as f32,
-                            y as f32,
-                        );
-
-                        if let Some(idx) = self.focused_widget_idx.as_ref() {
-                            layout::set_focused_node(self.root_node, *idx);
-                        }
-                    }
-                    Event::Resize(width, height) => {
-                        self.terminal_size = (width, height);
-                        self.recompute_layout();
-                    }
-                    _ => (),
-                }
-                // any other activities, such as mouse scroll is
-                // sent the widget underneath the location, regardless
-                // if it focused or not.
-                if let Some((x, y)) = extract_location(&event) {
-                    let hits = self.layout_tree.hit(x as f32, y as f32);
-                    //let hit = hits.pop().expect("process only 1 for now");
-                    for hit in hits.iter().rev() {
-                        let mut hit_widget: Option<&mut dyn Widget<MSG>> =
-                            find_node::find_widget_mut(self.root_node, *hit);
-
-                        if let Some(hit_widget) = &mut hit_widget {
-                            let msgs = hit_widget.process_event(event);
-                            self.dispatch_msg(msgs);
-                        }
-                    }
                 }
             }
         }
-        command::finalize(self.write)?;
         Ok(())
     }
 }
 
+impl<'a, MSG> Drop for Renderer<'a, MSG> {
+    fn drop(&mut self) {
+        if self.started {
+            self.started = false;
+            let _ = self.backend.finalize(self.write, self.viewport);
+        }
+    }
+}
+
+/// collect the path of each overlay layer in the tree, in the order they are drawn
+fn find_layers<MSG>(
+    node: &dyn Widget<MSG>,
+    path: &mut Vec<WidgetId>,
+    layers: &mut Vec<Vec<WidgetId>>,
+) {
+    let widget_id = match node.get_widget_id() {
+        Some(widget_id) => widget_id,
+        None => return,
+    };
+    path.push(widget_id);
+    if node.layer_placement().is_some() {
+        layers.push(path.clone());
+    }
+    if let Some(children) = node.children() {
+        for child in children.iter() {
+            find_layers(child.as_ref(), path, layers);
+        }
+    }
+    path.pop();
+}
+
+/// the location of a layer with this size on the screen,
+/// returns None if the widget it is anchored to has no layout
+fn place_layer(
+    placement: Placement,
+    size: Size<f32>,
+    screen: Size<f32>,
+    layouts: &HashMap<WidgetId, Layout>,
+) -> Option<Point<f32>> {
+    let (x, y) = match placement {
+        Placement::Center => {
+            (
+                ((screen.width - size.width) / 2.0).floor(),
+                ((screen.height - size.height) / 2.0).floor(),
+            )
+        }
+        Placement::Below(anchor) | Placement::Above(anchor) => {
+            let anchor = layouts.get(&anchor)?;
+            let below = anchor.location.y + anchor.size.height;
+            let above = anchor.location.y - size.height;
+            let fits_below = below + size.height <= screen.height;
+            let fits_above = above >= 0.0;
+            let y = match placement {
+                Placement::Below(_) if fits_below || !fits_above => below,
+                Placement::Above(_) if !fits_above && fits_below => below,
+                _ => above,
+            };
+            (anchor.location.x, y)
+        }
+        Placement::At(x, y) => (x, y),
+    };
+    // keep the layer inside the screen as much as possible
+    Some(Point {
+        x: x.min(screen.width - size.width).max(0.0),
+        y: y.min(screen.height - size.height).max(0.0),
+    })
+}
+
 /// extract the x and y location of a mouse event
 fn extract_location(event: &Event) -> Option<(u16, u16)> {
     match event {
@@ -217,3 +1055,543 @@ fn extract_location(event: &Event) -> Option<(u16, u16)> {
         Event::Resize(_, _) => None,
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        command::SetTitle,
+        FlexBox,
+        HeadlessBackend,
+        TextInput,
+    };
+
+    /// the output of setting the terminal title
+    fn title(title: &str) -> String {
+        SetTitle(title).ansi_code()
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Msg {
+        Save,
+        Progress(u8),
+        RemoveFirst,
+        #[cfg(feature = "async")]
+        Fetch,
+    }
+
+    /// records the msgs it receives
+    #[derive(Default)]
+    struct App {
+        msgs: std::cell::RefCell<Vec<Msg>>,
+    }
+
+    impl Dispatch<Msg> for App {
+        fn dispatch(
+            &self,
+            msg: Msg,
+            root_node: &mut dyn Widget<Msg>,
+        ) -> Vec<Cmd> {
+            self.msgs.borrow_mut().push(msg.clone());
+            match msg {
+                Msg::Save => {
+                    vec![
+                        Cmd::Focus("name".to_string()),
+                        Cmd::SetTitle("saved".to_string()),
+                        Cmd::Quit,
+                    ]
+                }
+                Msg::Progress(percent) => {
+                    vec![Cmd::SetTitle(format!("{}%", percent))]
+                }
+                Msg::RemoveFirst => {
+                    root_node.take_child(0);
+                    vec![]
+                }
+                #[cfg(feature = "async")]
+                Msg::Fetch => vec![],
+            }
+        }
+
+        #[cfg(feature = "async")]
+        fn dispatch_async(
+            &self,
+            msg: Msg,
+            root_node: &mut dyn Widget<Msg>,
+        ) -> (Vec<Cmd>, Vec<Task<Msg>>) {
+            match msg {
+                Msg::Fetch => {
+                    (vec![], vec![Box::pin(async { Msg::Progress(100) })])
+                }
+                msg => (self.dispatch(msg, root_node), vec![]),
+            }
+        }
+    }
+
+    #[test]
+    fn cmds_from_dispatch() {
+        let mut root = FlexBox::new();
+        root.vertical();
+        root.add_child(Box::new(TextInput::new("first")));
+        let mut name = TextInput::new("second");
+        Widget::<Msg>::set_id(&mut name, "name");
+        root.add_child(Box::new(name));
+
+        let backend = HeadlessBackend::new(
+            20,
+            8,
+            vec![
+                Event::Key(KeyEvent::new(
+                    KeyCode::Char('s'),
+                    KeyModifiers::CONTROL,
+                )),
+                // never processed, the app has quit
+                Event::Key(KeyCode::Char('X').into()),
+            ],
+        );
+        let mut out = vec![];
+        let app = App::default();
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            Some(&app),
+            &mut root,
+            Box::new(backend),
+        );
+        renderer.keymap_mut().bind_msg("Ctrl-s", Msg::Save).unwrap();
+        renderer.run().expect("must run");
+        drop(renderer);
+        assert_eq!(
+            focus::focused_widget(&root),
+            root.children().unwrap()[1].get_widget_id()
+        );
+        let name: &TextInput<Msg> = root
+            .child_mut(1)
+            .and_then(|child| child.as_any().downcast_ref())
+            .unwrap();
+        assert_eq!(name.get_value(), "second");
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains(&title("saved")));
+    }
+
+    #[test]
+    fn msgs_from_worker_thread() {
+        let mut root = FlexBox::new();
+        root.add_child(Box::new(TextInput::new("log")));
+        let backend = HeadlessBackend::new(
+            20,
+            8,
+            vec![Event::Key(KeyCode::Char('X').into())],
+        );
+        let mut out = vec![];
+        let app = App::default();
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            Some(&app),
+            &mut root,
+            Box::new(backend),
+        );
+        let sender = renderer.subscriptions_mut().sender();
+        std::thread::spawn(move || {
+            for percent in &[50, 100] {
+                sender.send(Msg::Progress(*percent)).unwrap();
+            }
+        })
+        .join()
+        .unwrap();
+        renderer.run().expect("must run");
+        drop(renderer);
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains(&title("50%")));
+        assert!(out.contains(&title("100%")));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn msgs_from_tasks() {
+        let mut root = FlexBox::new();
+        root.add_child(Box::new(TextInput::new("log")));
+        let backend = HeadlessBackend::new(
+            20,
+            8,
+            vec![
+                Event::Key(KeyCode::Char('f').into()),
+                Event::Key(KeyCode::Char('X').into()),
+            ],
+        );
+        let mut out = vec![];
+        let app = App::default();
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            Some(&app),
+            &mut root,
+            Box::new(backend),
+        );
+        renderer.keymap_mut().bind_msg("f", Msg::Fetch).unwrap();
+        futures::executor::block_on(renderer.run_async()).expect("must run");
+        drop(renderer);
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains(&title("100%")));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn tasks_need_run_async() {
+        let mut root = FlexBox::new();
+        root.add_child(Box::new(TextInput::new("log")));
+        let backend = HeadlessBackend::new(
+            20,
+            8,
+            vec![Event::Key(KeyCode::Char('f').into())],
+        );
+        let mut out = vec![];
+        let app = App::default();
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            Some(&app),
+            &mut root,
+            Box::new(backend),
+        );
+        renderer.keymap_mut().bind_msg("f", Msg::Fetch).unwrap();
+        assert!(renderer.run().is_err());
+    }
+
+    #[test]
+    fn inline_viewport() {
+        let mut root = FlexBox::<Msg>::new();
+        root.vertical();
+        root.add_child(Box::new(TextInput::new("name")));
+        root.add_child(Box::new(TextInput::new("email")));
+
+        let backend =
+            HeadlessBackend::new(20, 10, vec![Event::Key(KeyCode::Tab.into())]);
+        let mut out = vec![];
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            None,
+            &mut root,
+            Box::new(backend),
+        );
+        renderer.set_viewport(Viewport::Inline(8));
+        renderer.run().expect("must run");
+        // only the lines needed by the 2 text inputs are used
+        let buf = renderer.buffer();
+        assert_eq!(buf.cells.len(), 6);
+        assert_eq!(
+            buf.to_plain_text().lines().nth(1),
+            Some("┃name              ┃")
+        );
+        drop(renderer);
+        let out = String::from_utf8(out).unwrap();
+        // the whole screen is not cleared
+        assert!(!out.contains("\x1b[2J"));
+    }
+
+    /// a backend that panics on reading events, and records if the terminal is restored
+    struct PanickingBackend {
+        finalized: std::rc::Rc<std::cell::Cell<bool>>,
+    }
+
+    impl Backend for PanickingBackend {
+        fn size(&self) -> Result<(u16, u16)> {
+            Ok((20, 5))
+        }
+
+        fn read_event(&mut self) -> Result<Option<Event>> {
+            panic!("widget panicked");
+        }
+
+        fn init(
+            &mut self,
+            _w: &mut dyn Write,
+            _viewport: Viewport,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        fn finalize(
+            &mut self,
+            _w: &mut dyn Write,
+            _viewport: Viewport,
+        ) -> Result<()> {
+            self.finalized.set(true);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn restore_terminal_on_panic() {
+        let finalized = std::rc::Rc::new(std::cell::Cell::new(false));
+        let backend = PanickingBackend {
+            finalized: finalized.clone(),
+        };
+        let result =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                let mut root = FlexBox::<Msg>::new();
+                let mut out = vec![];
+                let mut renderer = Renderer::with_backend(
+                    &mut out,
+                    None,
+                    &mut root,
+                    Box::new(backend),
+                );
+                renderer.run()
+            }));
+        assert!(result.is_err());
+        assert!(finalized.get());
+    }
+
+    /// a container which prevents its children from reacting to the events
+    #[derive(Debug, Default)]
+    struct ReadOnly {
+        children: Vec<Box<dyn Widget<Msg>>>,
+        id: Option<String>,
+        widget_id: Option<WidgetId>,
+    }
+
+    impl Widget<Msg> for ReadOnly {
+        fn style(&self) -> stretch::style::Style {
+            stretch::style::Style::default()
+        }
+
+        fn add_child(&mut self, child: Box<dyn Widget<Msg>>) -> bool {
+            self.children.push(child);
+            true
+        }
+
+        fn children(&self) -> Option<&[Box<dyn Widget<Msg>>]> {
+            Some(&self.children)
+        }
+
+        fn children_mut(&mut self) -> Option<&mut [Box<dyn Widget<Msg>>]> {
+            Some(&mut self.children)
+        }
+
+        fn draw(
+            &mut self,
+            buf: &mut Buffer,
+            layout_tree: &LayoutTree,
+            theme: &Theme,
+        ) -> Vec<Cmd> {
+            self.children
+                .iter_mut()
+                .zip(layout_tree.children_layout.iter())
+                .flat_map(|(child, child_layout)| {
+                    child.draw(buf, child_layout, theme)
+                })
+                .collect()
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+            self
+        }
+
+        fn set_size(&mut self, _width: Option<f32>, _height: Option<f32>) {}
+
+        fn set_id(&mut self, id: &str) {
+            self.id = Some(id.to_string());
+        }
+
+        fn get_id(&self) -> &Option<String> {
+            &self.id
+        }
+
+        fn set_widget_id(&mut self, widget_id: WidgetId) {
+            self.widget_id = Some(widget_id);
+        }
+
+        fn get_widget_id(&self) -> Option<WidgetId> {
+            self.widget_id
+        }
+
+        fn handle_event(&mut self, event: &mut WidgetEvent) -> Vec<Msg> {
+            if event.phase == crate::Phase::Capture {
+                event.prevent_default();
+            }
+            vec![]
+        }
+    }
+
+    #[test]
+    fn prevent_default_in_capture_phase() {
+        let click = Event::Mouse(MouseEvent::Down(
+            event::MouseButton::Left,
+            1,
+            0,
+            KeyModifiers::empty(),
+        ));
+        let mut checkbox = crate::Checkbox::new("agree");
+        checkbox
+            .add_input_listener(crate::Callback::from(|_| Msg::Progress(1)));
+
+        let mut root = ReadOnly::default();
+        root.add_child(Box::new(checkbox));
+        let backend = HeadlessBackend::new(20, 5, vec![click]);
+        let mut out = vec![];
+        let app = App::default();
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            Some(&app),
+            &mut root,
+            Box::new(backend),
+        );
+        assert_eq!(renderer.layout_tree.hit(1.0, 0.0), vec![0, 1]);
+        renderer.run().expect("must run");
+        drop(renderer);
+        // the checkbox received the click but did not toggle
+        let checkbox = root.children().unwrap()[0]
+            .as_any()
+            .downcast_ref::<crate::Checkbox<Msg>>()
+            .unwrap();
+        assert!(!checkbox.is_checked());
+        assert!(!app.msgs.borrow().contains(&Msg::Progress(1)));
+        let out = String::from_utf8(out).unwrap();
+        assert!(!out.contains(&title("1%")));
+    }
+
+    #[test]
+    fn unconsumed_keys_bubble_to_ancestors() {
+        let mut tab_box = crate::TabBox::<Msg>::new();
+        tab_box.set_tab_labels(vec!["one".to_string(), "two".to_string()]);
+        tab_box.add_child(Box::new(TextInput::new("")));
+        let mut root = FlexBox::new();
+        root.add_child(Box::new(tab_box));
+
+        let ctrl_page_down =
+            KeyEvent::new(KeyCode::PageDown, KeyModifiers::CONTROL);
+        let backend = HeadlessBackend::new(
+            30,
+            10,
+            vec![
+                Event::Key(KeyCode::Tab.into()),
+                Event::Key(KeyCode::Char('a').into()),
+                Event::Key(ctrl_page_down),
+                // typed with ctrl, this is not added to the text input
+                Event::Key(KeyEvent::new(
+                    KeyCode::Char('b'),
+                    KeyModifiers::CONTROL,
+                )),
+            ],
+        );
+        let mut out = vec![];
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            None,
+            &mut root,
+            Box::new(backend),
+        );
+        renderer.keymap_mut().clear();
+        renderer
+            .keymap_mut()
+            .bind("Tab", Action::FocusNext)
+            .unwrap();
+        renderer.run().expect("must run");
+        drop(renderer);
+
+        let tab_box = root.children().unwrap()[0]
+            .as_any()
+            .downcast_ref::<crate::TabBox<Msg>>()
+            .unwrap();
+        assert_eq!(tab_box.get_active_tab(), 1);
+        let input = tab_box.children().unwrap()[0]
+            .as_any()
+            .downcast_ref::<TextInput<Msg>>()
+            .unwrap();
+        assert_eq!(input.get_value(), "a");
+    }
+
+    #[test]
+    fn pointer_capture_and_hover() {
+        let left = event::MouseButton::Left;
+        let none = KeyModifiers::empty();
+        let mut root = FlexBox::<Msg>::new();
+        root.vertical();
+        root.add_child(Box::new(crate::Slider::new(0.5)));
+        root.add_child(Box::new(crate::Button::new("hello")));
+
+        let backend = HeadlessBackend::new(
+            30,
+            10,
+            vec![
+                // start dragging the slider, then leave its row over the button
+                Event::Mouse(MouseEvent::Down(left, 15, 0, none)),
+                Event::Mouse(MouseEvent::Drag(left, 3, 2, none)),
+                Event::Mouse(MouseEvent::Up(left, 3, 2, none)),
+            ],
+        );
+        let mut out = vec![];
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            None,
+            &mut root,
+            Box::new(backend),
+        );
+        renderer.run().expect("must run");
+        let button_id =
+            renderer.root_node.children().unwrap()[1].get_widget_id();
+        assert_eq!(
+            renderer.hovered_path,
+            vec![
+                renderer.root_node.get_widget_id().unwrap(),
+                button_id.unwrap()
+            ]
+        );
+        assert!(renderer.pointer_capture.is_none());
+        drop(renderer);
+
+        let slider = root.children().unwrap()[0]
+            .as_any()
+            .downcast_ref::<crate::Slider<Msg>>()
+            .unwrap();
+        assert!((slider.get_value() - 0.1).abs() < 0.001);
+        assert!(!Widget::<Msg>::is_hovered(slider));
+        let button = root.children().unwrap()[1]
+            .as_any()
+            .downcast_ref::<crate::Button<Msg>>()
+            .unwrap();
+        assert!(button.is_hovered());
+    }
+
+    #[test]
+    fn focus_follows_widget_id() {
+        let mut root = FlexBox::<Msg>::new();
+        root.add_child(Box::new(crate::Button::new("first")));
+        root.add_child(Box::new(TextInput::new("name")));
+        let input_id = root.children().unwrap()[1].get_widget_id();
+
+        let backend = HeadlessBackend::new(
+            30,
+            10,
+            vec![
+                Event::Key(KeyCode::Tab.into()),
+                Event::Key(KeyCode::Tab.into()),
+                Event::Key(KeyEvent::new(
+                    KeyCode::Char('r'),
+                    KeyModifiers::CONTROL,
+                )),
+            ],
+        );
+        let mut out = vec![];
+        let app = App::default();
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            Some(&app),
+            &mut root,
+            Box::new(backend),
+        );
+        renderer
+            .keymap_mut()
+            .bind_msg("Ctrl-r", Msg::RemoveFirst)
+            .unwrap();
+        renderer.run().expect("must run");
+        // the input moved to the first child, but is still the focused widget
+        assert_eq!(renderer.focused_widget(), input_id);
+        let layout = renderer.widget_layout(input_id.unwrap()).unwrap();
+        assert_eq!(layout.location.y, 0.0);
+        drop(renderer);
+        assert!(root.children().unwrap()[0].is_focused());
+    }
+}
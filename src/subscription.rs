@@ -0,0 +1,188 @@
+//! Subscriptions are sources of msgs other than the terminal events,
+//! such as timers and messages sent from the worker threads.
+//!
+//! The renderer waits for the terminal events only until the next timer is due,
+//! then the msgs of the due timers and the received msgs are dispatched to the app.
+use crate::Callback;
+use std::{
+    sync::mpsc::{
+        self,
+        Receiver,
+        Sender,
+        TryRecvError,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+/// how often the channels are checked while waiting for terminal events,
+/// since a channel can not be waited on together with the terminal input.
+const CHANNEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// the shortest interval of a timer, shorter intervals are clamped to this
+const MIN_INTERVAL: Duration = Duration::from_millis(1);
+
+/// A timer that emits a msg every interval
+struct Interval<MSG> {
+    every: Duration,
+    next: Instant,
+    cb: Callback<Instant, MSG>,
+}
+
+/// The timers and channels that the renderer listens to
+pub struct Subscriptions<MSG> {
+    intervals: Vec<Interval<MSG>>,
+    receivers: Vec<Receiver<MSG>>,
+}
+
+impl<MSG> Default for Subscriptions<MSG>
+where
+    MSG: 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<MSG> Subscriptions<MSG>
+where
+    MSG: 'static,
+{
+    /// create an empty subscription
+    pub fn new() -> Self {
+        Subscriptions {
+            intervals: vec![],
+            receivers: vec![],
+        }
+    }
+
+    /// returns true if there are no timers and channels
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty() && self.receivers.is_empty()
+    }
+
+    /// emit the msg returned by the callback every interval,
+    /// the callback is called with the time the timer fires.
+    /// Intervals shorter than a millisecond are clamped to a millisecond.
+    pub fn interval(&mut self, every: Duration, cb: Callback<Instant, MSG>) {
+        let every = every.max(MIN_INTERVAL);
+        self.intervals.push(Interval {
+            every,
+            next: Instant::now() + every,
+            cb,
+        });
+    }
+
+    /// dispatch the msgs received from this channel,
+    /// the channel is removed once all of its senders are dropped.
+    pub fn receiver(&mut self, receiver: Receiver<MSG>) {
+        self.receivers.push(receiver);
+    }
+
+    /// create a channel and returns the sender, which can be moved into a worker thread
+    pub fn sender(&mut self) -> Sender<MSG> {
+        let (sender, receiver) = mpsc::channel();
+        self.receiver(receiver);
+        sender
+    }
+
+    /// remove all the timers and channels
+    pub fn clear(&mut self) {
+        self.intervals.clear();
+        self.receivers.clear();
+    }
+
+    /// how long to wait for terminal events before the subscriptions needs to be polled.
+    /// Returns None if there is nothing to wait for.
+    pub fn timeout(&self, now: Instant) -> Option<Duration> {
+        let next_timer = self
+            .intervals
+            .iter()
+            .map(|interval| interval.next.saturating_duration_since(now))
+            .min();
+        if self.receivers.is_empty() {
+            next_timer
+        } else {
+            Some(
+                next_timer
+                    .map(|timer| timer.min(CHANNEL_POLL_INTERVAL))
+                    .unwrap_or(CHANNEL_POLL_INTERVAL),
+            )
+        }
+    }
+
+    /// returns the msgs of the timers which are due and the msgs received from the channels
+    pub fn poll(&mut self, now: Instant) -> Vec<MSG> {
+        let mut msgs = vec![];
+        for interval in self.intervals.iter_mut() {
+            if interval.next <= now {
+                msgs.push(interval.cb.emit(now));
+                // skip the ticks that were missed instead of firing them all at once
+                let every = interval.every.as_nanos();
+                let missed = (now - interval.next).as_nanos() / every + 1;
+                interval.next += Duration::from_nanos((missed * every) as u64);
+            }
+        }
+        self.receivers.retain(|receiver| {
+            loop {
+                match receiver.try_recv() {
+                    Ok(msg) => msgs.push(msg),
+                    Err(TryRecvError::Empty) => return true,
+                    Err(TryRecvError::Disconnected) => return false,
+                }
+            }
+        });
+        msgs
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn interval() {
+        let mut subs = Subscriptions::new();
+        subs.interval(Duration::from_secs(1), Callback::from(|_| "tick"));
+        let start = Instant::now();
+        assert!(subs.timeout(start).unwrap() <= Duration::from_secs(1));
+        assert!(subs.poll(start).is_empty());
+
+        let later = start + Duration::from_millis(3500);
+        assert_eq!(subs.timeout(later), Some(Duration::from_secs(0)));
+        // the missed ticks are coalesced
+        assert_eq!(subs.poll(later), vec!["tick"]);
+        assert!(subs.poll(later).is_empty());
+        assert!(subs.timeout(later).unwrap() <= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn zero_interval() {
+        let mut subs = Subscriptions::new();
+        subs.interval(Duration::from_secs(0), Callback::from(|_| "tick"));
+        let later = Instant::now() + Duration::from_secs(1);
+        assert_eq!(subs.poll(later), vec!["tick"]);
+        assert!(subs.poll(later).is_empty());
+        let timeout = subs.timeout(later).unwrap();
+        assert!(timeout > Duration::from_secs(0) && timeout <= MIN_INTERVAL);
+    }
+
+    #[test]
+    fn channel() {
+        let mut subs = Subscriptions::new();
+        let sender = subs.sender();
+        assert_eq!(subs.timeout(Instant::now()), Some(CHANNEL_POLL_INTERVAL));
+        thread::spawn(move || {
+            sender.send(1).unwrap();
+            sender.send(2).unwrap();
+        })
+        .join()
+        .unwrap();
+        assert_eq!(subs.poll(Instant::now()), vec![1, 2]);
+        // the sender is dropped, so the channel is removed
+        assert!(subs.is_empty());
+    }
+}
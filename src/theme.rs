@@ -0,0 +1,454 @@
+//! Themes decide the colors, border styles and text attributes of the widgets.
+//!
+//! The theme is passed down to the widgets as they are drawn. A widget can override
+//! the theme it inherits with `Widget::set_theme`, which then applies to it and all of its children.
+use crate::{
+    buffer::{
+        Buffer,
+        Cell,
+    },
+    Cmd,
+    LayoutTree,
+    Widget,
+};
+use crossterm::style::{
+    Attribute,
+    Color,
+};
+use ito_canvas::unicode_canvas::{
+    Border,
+    Canvas,
+};
+use unicode_width::UnicodeWidthChar;
+
+/// The state of a widget which decides which of the styles of the theme is used
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WidgetState {
+    /// the widget is neither focused, hovered nor disabled
+    Normal,
+    /// the widget has the keyboard focus
+    Focused,
+    /// the mouse pointer is over the widget
+    Hovered,
+    /// the widget doesn't react to the events
+    Disabled,
+}
+
+/// The named colors of a theme
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Palette {
+    /// the color of the text
+    pub foreground: Option<Color>,
+    /// the color behind the text
+    pub background: Option<Color>,
+    /// used to highlight the active or selected parts such as the active tab
+    pub accent: Option<Color>,
+    /// the color of the borders
+    pub border: Option<Color>,
+    /// used for the disabled widgets
+    pub muted: Option<Color>,
+}
+
+/// The colors, border and text attributes of a widget in one of its state.
+/// The properties which are None are inherited from the normal state.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StateStyle {
+    /// the color of the text
+    pub foreground: Option<Color>,
+    /// the color behind the text and the border
+    pub background: Option<Color>,
+    /// the color of the border, defaults to the foreground
+    pub border_color: Option<Color>,
+    /// use a thick border
+    pub thick_border: Option<bool>,
+    /// use rounded corners on the border, defaults to the setting of the widget
+    pub rounded_border: Option<bool>,
+    /// the attributes of the text such as bold or underlined
+    pub attributes: Vec<Attribute>,
+}
+
+impl StateStyle {
+    /// returns this style with the properties that are set in the other style overriding it,
+    /// the attributes of both styles are combined.
+    pub fn merge(&self, other: &StateStyle) -> StateStyle {
+        let mut attributes = self.attributes.clone();
+        for attr in other.attributes.iter() {
+            if !attributes.contains(attr) {
+                attributes.push(*attr);
+            }
+        }
+        StateStyle {
+            foreground: other.foreground.or(self.foreground),
+            background: other.background.or(self.background),
+            border_color: other.border_color.or(self.border_color),
+            thick_border: other.thick_border.or(self.thick_border),
+            rounded_border: other.rounded_border.or(self.rounded_border),
+            attributes,
+        }
+    }
+
+    /// whether the border is thick
+    pub fn is_thick_border(&self) -> bool {
+        self.thick_border.unwrap_or(false)
+    }
+
+    /// set the colors and attributes of this style to the cell
+    pub fn paint(&self, cell: &mut Cell) {
+        if let Some(fg) = self.foreground {
+            cell.color(fg);
+        }
+        if let Some(bg) = self.background {
+            cell.background(bg);
+        }
+        cell.attributes(self.attributes.clone());
+    }
+
+    /// create a cell of the text with this style
+    pub fn cell<S: ToString>(&self, symbol: S) -> Cell {
+        let mut cell = Cell::new(symbol);
+        self.paint(&mut cell);
+        cell
+    }
+
+    /// write the text with this style, incrementing on the x component
+    /// by the display width of each character
+    pub fn write_str<S: ToString>(
+        &self,
+        buf: &mut Buffer,
+        x: usize,
+        y: usize,
+        s: S,
+    ) {
+        self.write_str_clipped(buf, x, y, s, usize::MAX)
+    }
+
+    /// write the text the same as `write_str`, up to this display width,
+    /// the characters which doesn't fit are left out
+    pub fn write_str_clipped<S: ToString>(
+        &self,
+        buf: &mut Buffer,
+        x: usize,
+        y: usize,
+        s: S,
+        width: usize,
+    ) {
+        let mut offset = 0;
+        for ch in s.to_string().chars() {
+            let ch_width = ch.width().unwrap_or(0).max(1);
+            if offset + ch_width > width {
+                break;
+            }
+            buf.set_cell(x + offset, y, self.cell(ch));
+            offset += ch_width;
+        }
+    }
+
+    /// fill the area with the background color, if this style has one
+    pub(crate) fn fill(
+        &self,
+        buf: &mut Buffer,
+        (left, top): (usize, usize),
+        (right, bottom): (usize, usize),
+    ) {
+        if let Some(bg) = self.background {
+            for y in top..=bottom {
+                for x in left..=right {
+                    let mut cell = Cell::empty();
+                    cell.background(bg);
+                    buf.set_cell(x, y, cell);
+                }
+            }
+        }
+    }
+
+    /// write the lines drawn in the canvas using the border color
+    pub(crate) fn write_canvas(&self, buf: &mut Buffer, canvas: Canvas) {
+        for (x, y, ch) in canvas.get_cells() {
+            let mut cell = Cell::new(ch);
+            if let Some(color) = self.border_color.or(self.foreground) {
+                cell.color(color);
+            }
+            if let Some(bg) = self.background {
+                cell.background(bg);
+            }
+            buf.set_cell(x, y, cell);
+        }
+    }
+
+    /// draw a rectangular border with the thickness and color of this style,
+    /// the corners are rounded if this style doesn't say otherwise
+    pub(crate) fn draw_border(
+        &self,
+        buf: &mut Buffer,
+        top_left: (usize, usize),
+        bottom_right: (usize, usize),
+        rounded: bool,
+    ) {
+        let rounded = self.rounded_border.unwrap_or(rounded);
+        let border = Border {
+            use_thick_border: self.is_thick_border(),
+            has_top: true,
+            has_bottom: true,
+            has_left: true,
+            has_right: true,
+            is_top_left_rounded: rounded,
+            is_top_right_rounded: rounded,
+            is_bottom_left_rounded: rounded,
+            is_bottom_right_rounded: rounded,
+        };
+        let mut canvas = Canvas::new();
+        canvas.draw_rect(top_left, bottom_right, border);
+        self.write_canvas(buf, canvas);
+    }
+}
+
+/// The palette and the style of the widgets in each of their state
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// the named colors of this theme
+    pub palette: Palette,
+    /// the style of the widgets in normal state, the other styles are merged into this
+    pub normal: StateStyle,
+    /// the style of the focused widget
+    pub focused: StateStyle,
+    /// the style of the widget under the mouse pointer
+    pub hovered: StateStyle,
+    /// the style of the disabled widgets
+    pub disabled: StateStyle,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::plain()
+    }
+}
+
+impl Theme {
+    /// uses the colors of the terminal, the focused and hovered widgets are
+    /// distinguished with a thick border
+    pub fn plain() -> Self {
+        Theme {
+            palette: Palette::default(),
+            normal: StateStyle::default(),
+            focused: StateStyle {
+                thick_border: Some(true),
+                attributes: vec![Attribute::Bold],
+                ..Default::default()
+            },
+            hovered: StateStyle {
+                thick_border: Some(true),
+                ..Default::default()
+            },
+            disabled: StateStyle {
+                attributes: vec![Attribute::Dim],
+                ..Default::default()
+            },
+        }
+    }
+
+    /// light text on a dark background
+    pub fn dark() -> Self {
+        Self::from_palette(Palette {
+            foreground: Some(Color::Grey),
+            background: Some(Color::Black),
+            accent: Some(Color::Cyan),
+            border: Some(Color::DarkGrey),
+            muted: Some(Color::DarkGrey),
+        })
+    }
+
+    /// dark text on a light background
+    pub fn light() -> Self {
+        Self::from_palette(Palette {
+            foreground: Some(Color::Black),
+            background: Some(Color::White),
+            accent: Some(Color::DarkBlue),
+            border: Some(Color::Grey),
+            muted: Some(Color::Grey),
+        })
+    }
+
+    /// bright colors and underlined focus for low vision users
+    pub fn high_contrast() -> Self {
+        let mut theme = Self::from_palette(Palette {
+            foreground: Some(Color::White),
+            background: Some(Color::Black),
+            accent: Some(Color::Yellow),
+            border: Some(Color::White),
+            muted: Some(Color::Grey),
+        });
+        theme.focused.attributes.push(Attribute::Underlined);
+        theme
+    }
+
+    /// derive the style of each of the state from the colors in the palette
+    pub fn from_palette(palette: Palette) -> Self {
+        Theme {
+            palette,
+            normal: StateStyle {
+                foreground: palette.foreground,
+                background: palette.background,
+                border_color: palette.border,
+                thick_border: Some(false),
+                ..Default::default()
+            },
+            focused: StateStyle {
+                border_color: palette.accent,
+                thick_border: Some(true),
+                attributes: vec![Attribute::Bold],
+                ..Default::default()
+            },
+            hovered: StateStyle {
+                border_color: palette.foreground,
+                thick_border: Some(true),
+                ..Default::default()
+            },
+            disabled: StateStyle {
+                foreground: palette.muted,
+                border_color: palette.muted,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// the style of the widgets in this state
+    pub fn style(&self, state: WidgetState) -> StateStyle {
+        match state {
+            WidgetState::Normal => self.normal.clone(),
+            WidgetState::Focused => self.normal.merge(&self.focused),
+            WidgetState::Hovered => self.normal.merge(&self.hovered),
+            WidgetState::Disabled => self.normal.merge(&self.disabled),
+        }
+    }
+
+    /// the style of the widget based on its current state,
+    /// a widget which is both hovered and focused uses the focused style on top of the hovered.
+    ///
+    /// The visual properties in the inline style of the widget overrides the normal style.
+    pub fn widget_style<MSG, W>(&self, widget: &W) -> StateStyle
+    where
+        W: Widget<MSG> + ?Sized,
+    {
+        let mut style = match widget.get_inline_style() {
+            Some(inline_style) => self.normal.merge(&inline_style.visual),
+            None => self.normal.clone(),
+        };
+        if widget.is_disabled() {
+            return style.merge(&self.disabled);
+        }
+        if widget.is_hovered() {
+            style = style.merge(&self.hovered);
+        }
+        if widget.is_focused() {
+            style = style.merge(&self.focused);
+        }
+        style
+    }
+
+    /// the style of the highlighted parts such as the active tab
+    pub fn accent_style(&self) -> StateStyle {
+        self.normal.merge(&StateStyle {
+            foreground: self.palette.accent,
+            attributes: vec![Attribute::Bold],
+            ..Default::default()
+        })
+    }
+}
+
+/// draw the widget with its own theme if it overrides the theme of its parent
+pub(crate) fn draw_widget<MSG>(
+    widget: &mut dyn Widget<MSG>,
+    buf: &mut Buffer,
+    layout_tree: &LayoutTree,
+    theme: &Theme,
+) -> Vec<Cmd> {
+    match widget.get_theme().cloned() {
+        Some(own_theme) => widget.draw(buf, layout_tree, &own_theme),
+        None => widget.draw(buf, layout_tree, theme),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        layout,
+        Button,
+        FlexBox,
+    };
+    use stretch::{
+        geometry::Size,
+        number::Number,
+    };
+
+    #[test]
+    fn write_wide_characters() {
+        let style = StateStyle::default();
+        let mut buf = Buffer::new(8, 1);
+        style.write_str_clipped(&mut buf, 0, 0, "日本語です", 5);
+        // the third character doesn't fit in the width
+        assert_eq!(buf.to_plain_text().lines().next(), Some("日本    "));
+        style.write_str(&mut buf, 4, 0, "語x");
+        assert_eq!(buf.to_plain_text().lines().next(), Some("日本語x "));
+    }
+
+    #[test]
+    fn merge_states() {
+        let theme = Theme::dark();
+        let focused = theme.style(WidgetState::Focused);
+        assert_eq!(focused.foreground, Some(Color::Grey));
+        assert_eq!(focused.border_color, Some(Color::Cyan));
+        assert!(focused.is_thick_border());
+        assert_eq!(focused.attributes, vec![Attribute::Bold]);
+
+        let disabled = theme.style(WidgetState::Disabled);
+        assert_eq!(disabled.foreground, Some(Color::DarkGrey));
+        assert!(!disabled.is_thick_border());
+
+        let high_contrast = Theme::high_contrast().style(WidgetState::Focused);
+        assert_eq!(
+            high_contrast.attributes,
+            vec![Attribute::Bold, Attribute::Underlined]
+        );
+    }
+
+    #[test]
+    fn override_per_widget() {
+        let mut root = FlexBox::<()>::new();
+        root.horizontal();
+        let mut focused = Button::new("ok");
+        focused.set_focused(true);
+        root.add_child(Box::new(focused));
+        let mut light = Button::new("light");
+        light.set_theme(Theme::light());
+        root.add_child(Box::new(light));
+        let mut disabled = Button::new("no");
+        disabled.set_disabled(true);
+        root.add_child(Box::new(disabled));
+
+        let layout_tree = layout::compute_layout(
+            &mut root,
+            Size {
+                width: Number::Defined(20.0),
+                height: Number::Defined(3.0),
+            },
+        );
+        let mut buf = Buffer::new(20, 3);
+        draw_widget(&mut root, &mut buf, &layout_tree, &Theme::dark());
+
+        // the border of the focused button uses the accent color
+        let corner = buf.get_cell(0, 0).unwrap();
+        assert_eq!(corner.foreground_color, Some(Color::Cyan));
+        assert_eq!(corner.background_color, Some(Color::Black));
+        assert!(buf.get_cell(1, 1).unwrap().attributes.has(Attribute::Bold));
+        // the second button overrides the theme
+        let label = buf.get_cell(6, 1).unwrap();
+        assert_eq!(label.symbol, "l");
+        assert_eq!(label.foreground_color, Some(Color::Black));
+        assert_eq!(label.background_color, Some(Color::White));
+        // the third button is disabled
+        let label = buf.get_cell(13, 1).unwrap();
+        assert_eq!(label.symbol, "n");
+        assert_eq!(label.foreground_color, Some(Color::DarkGrey));
+    }
+}
@@ -1,27 +1,54 @@
 use crate::{
     buffer::Buffer,
+    widget_event::{
+        HoverEvent,
+        Phase,
+        WidgetEvent,
+    },
+    widget_id::WidgetId,
     Cmd,
+    InlineStyle,
     LayoutTree,
+    Theme,
 };
 pub use button::Button;
 pub use checkbox::Checkbox;
-use crossterm::event::Event;
+pub use combo_box::ComboBox;
+use crossterm::event::{
+    Event,
+    KeyEvent,
+};
+pub use dialog::Dialog;
 pub use flex_box::FlexBox;
 pub use group_box::GroupBox;
 pub use image_control::Image;
+pub use layer::{
+    Layer,
+    Placement,
+};
 pub use list_box::ListBox;
+pub use map_msg::MapMsg;
 pub use radio::Radio;
+use sauron_vdom::{
+    Callback,
+    Value,
+};
 pub use slider::Slider;
 use std::{
     any::Any,
     fmt,
 };
 use stretch::{
+    geometry::Size,
     node::{
         Node,
         Stretch,
     },
-    style::Style,
+    style::{
+        Dimension,
+        PositionType,
+        Style,
+    },
 };
 pub use svg_image::SvgImage;
 pub use tab_box::TabBox;
@@ -31,13 +58,21 @@ pub use traits::{
     Flex,
     ImageTrait,
 };
+pub use virtual_list::{
+    ListSource,
+    VirtualList,
+};
 
 mod button;
 mod checkbox;
+mod combo_box;
+mod dialog;
 mod flex_box;
 mod group_box;
 mod image_control;
+mod layer;
 mod list_box;
+mod map_msg;
 mod radio;
 mod slider;
 mod svg_image;
@@ -45,6 +80,7 @@ mod tab_box;
 mod text_area;
 mod text_input;
 mod traits;
+mod virtual_list;
 
 /// All widgets must implement the Widget trait
 pub trait Widget<MSG>
@@ -81,11 +117,49 @@ where
     /// this is called in the render loop in the renderer where the widget
     /// writes into the buffer. The result will then be written into the
     /// stdout terminal.
-    fn draw(&mut self, but: &mut Buffer, layout_tree: &LayoutTree) -> Vec<Cmd>;
+    ///
+    /// The theme is inherited from the parent, or is the theme of this widget if it is set.
+    fn draw(
+        &mut self,
+        but: &mut Buffer,
+        layout_tree: &LayoutTree,
+        theme: &Theme,
+    ) -> Vec<Cmd>;
+
+    /// the style of this widget with its inline style merged into it
+    fn computed_style(&self) -> Style {
+        match self.get_inline_style() {
+            Some(inline_style) => inline_style.apply(self.style()),
+            None => self.style(),
+        }
+    }
+
+    /// set the css-like style of this widget,
+    /// which overrides the layout and the visual properties of the widget
+    fn set_inline_style(&mut self, _style: InlineStyle) {}
+
+    /// get the inline style of this widget
+    fn get_inline_style(&self) -> Option<&InlineStyle> {
+        None
+    }
 
     /// build a node with styles from this widget and its children
     /// The Layout tree is then calculated see `layout::compute_layout`
+    ///
+    /// An overlay layer takes no space in the layout of the tree it is in,
+    /// its children are laid out separately by the renderer.
     fn style_node(&self, stretch: &mut Stretch) -> Option<Node> {
+        if self.layer_placement().is_some() {
+            let style = Style {
+                position_type: PositionType::Absolute,
+                size: Size {
+                    width: Dimension::Points(0.0),
+                    height: Dimension::Points(0.0),
+                },
+                ..Default::default()
+            };
+            return stretch.new_node(style, vec![]).ok();
+        }
         let children_styles = if let Some(children) = self.children() {
             children
                 .iter()
@@ -94,12 +168,75 @@ where
         } else {
             vec![]
         };
-        stretch.new_node(self.style(), children_styles).ok()
+        stretch
+            .new_node(self.computed_style(), children_styles)
+            .ok()
     }
 
     /// set the widget as focused
     fn set_focused(&mut self, _focused: bool) {}
 
+    /// returns true if this widget is currently focused
+    fn is_focused(&self) -> bool {
+        false
+    }
+
+    /// set the hover state of this widget, the widget is hovered when the
+    /// mouse pointer is over it or over any of its children
+    fn set_hovered(&mut self, _hovered: bool) {}
+
+    /// returns true if the mouse pointer is over this widget
+    fn is_hovered(&self) -> bool {
+        false
+    }
+
+    /// called when the mouse pointer moves onto or off this widget,
+    /// after its hover state is set
+    fn hover_event(&mut self, _event: HoverEvent) -> Vec<MSG> {
+        vec![]
+    }
+
+    /// disable the widget, a disabled widget doesn't react to the events
+    fn set_disabled(&mut self, _disabled: bool) {}
+
+    /// returns true if this widget is disabled
+    fn is_disabled(&self) -> bool {
+        false
+    }
+
+    /// override the theme inherited from the parent, for this widget and its children
+    fn set_theme(&mut self, _theme: Theme) {}
+
+    /// the theme which overrides the theme inherited from the parent
+    fn get_theme(&self) -> Option<&Theme> {
+        None
+    }
+
+    /// returns where this widget is placed when it is an overlay layer,
+    /// which the renderer lays out apart from the widget tree and draws above it.
+    /// The widgets in the flow of the layout returns None.
+    fn layer_placement(&self) -> Option<Placement> {
+        None
+    }
+
+    /// returns true if this overlay layer blocks the events
+    /// to the widgets below it and keeps the focus inside of it
+    fn is_modal(&self) -> bool {
+        false
+    }
+
+    /// returns true if this widget can receive the keyboard focus
+    /// when traversing the widgets with Tab/Shift-Tab
+    fn is_focusable(&self) -> bool {
+        false
+    }
+
+    /// returns true if this widget handles the key itself when it is focused,
+    /// in which case the key is sent to it before the keymap is consulted
+    fn claims_key(&self, _key_event: &KeyEvent) -> bool {
+        false
+    }
+
     /// get an Any reference
     fn as_any(&self) -> &dyn Any;
 
@@ -122,6 +259,36 @@ where
         vec![]
     }
 
+    /// process the event the same as `process_event`, and also returns whether the event
+    /// is consumed by this widget.
+    /// A consumed event is not propagated to the rest of the widgets, such as the key events
+    /// which are not consumed by the focused widget bubbles up to its ancestors.
+    fn consume_event(&mut self, event: Event) -> (Vec<MSG>, bool) {
+        (self.process_event(event), false)
+    }
+
+    /// handle the event as it is propagated through the widget tree.
+    ///
+    /// By default, the event is passed to `consume_event` when this widget is the target
+    /// or the event bubbles up from its children, unless the default handling is prevented.
+    /// Override this to handle the event in the capture phase or to stop its propagation.
+    fn handle_event(&mut self, event: &mut WidgetEvent) -> Vec<MSG> {
+        match event.phase {
+            Phase::Capture => vec![],
+            Phase::Target | Phase::Bubble => {
+                if event.is_default_prevented() {
+                    vec![]
+                } else {
+                    let (msgs, consumed) = self.consume_event(event.event);
+                    if consumed {
+                        event.stop_propagation();
+                    }
+                    msgs
+                }
+            }
+        }
+    }
+
     ///  take the children at this index location
     fn take_child(&mut self, _index: usize) -> Option<Box<dyn Widget<MSG>>> {
         None
@@ -132,6 +299,44 @@ where
 
     /// get the id of this widget
     fn get_id(&self) -> &Option<String>;
+
+    /// set the id which identifies this widget in the tree,
+    /// this is assigned when the widget is inserted into a container
+    fn set_widget_id(&mut self, widget_id: WidgetId);
+
+    /// get the id which identifies this widget in the tree
+    fn get_widget_id(&self) -> Option<WidgetId>;
+
+    /// add a class to this widget, which can be used to query the widget
+    fn add_class(&mut self, _class: &str) {}
+
+    /// remove a class from this widget
+    fn remove_class(&mut self, _class: &str) {}
+
+    /// get the classes of this widget
+    fn get_classes(&self) -> &[String] {
+        &[]
+    }
+
+    /// the name of the kind of this widget, such as "button" or "textinput"
+    fn get_tag(&self) -> &'static str {
+        "widget"
+    }
+
+    /// set the attribute of this widget, such as the label of a button.
+    /// This is used in patching the widget from the view of a component.
+    fn set_attribute(&mut self, _name: &str, _value: &Value) {}
+
+    /// reset the attribute to its default value when it is no longer in the view
+    fn remove_attribute(&mut self, _name: &str) {}
+
+    /// replace the listeners of this event, such as "click" or "input"
+    fn set_event_listeners(
+        &mut self,
+        _event: &str,
+        _listeners: Vec<Callback<sauron_vdom::Event, MSG>>,
+    ) {
+    }
 }
 
 #[cfg(test)]
@@ -142,6 +347,10 @@ mod tests {
     use stretch::{
         geometry::*,
         number::Number,
+        style::{
+            AlignItems,
+            JustifyContent,
+        },
     };
 
     #[test]
@@ -215,4 +424,64 @@ mod tests {
             layout_tree.children_layout[1].children_layout[1].layout;
         assert_eq!(layout_btn2.location, Point { x: 20.0, y: 0.0 });
     }
+
+    #[test]
+    fn flex_properties() {
+        let mut toolbar = FlexBox::<()>::new();
+        toolbar.horizontal();
+        toolbar.set_size(Some(40.0), Some(3.0));
+        toolbar.set_gap(1.0);
+        toolbar.set_padding(0.0, 1.0, 0.0, 1.0);
+
+        let mut open = Button::<()>::new("open");
+        open.set_size(Some(6.0), Some(3.0));
+        toolbar.add_child(boxed::Box::new(open));
+
+        let mut spacer = FlexBox::<()>::new();
+        spacer.set_flex_grow(1.0);
+        toolbar.add_child(boxed::Box::new(spacer));
+
+        let mut quit = Button::<()>::new("quit");
+        quit.set_size(Some(6.0), Some(3.0));
+        toolbar.add_child(boxed::Box::new(quit));
+
+        let layout_tree = crate::layout::compute_layout(
+            &mut toolbar,
+            Size {
+                width: Number::Defined(100.0),
+                height: Number::Defined(100.0),
+            },
+        );
+        let layouts: Vec<_> = layout_tree
+            .children_layout
+            .iter()
+            .map(|child| child.layout)
+            .collect();
+        assert_eq!(layouts[0].location, Point { x: 1.0, y: 0.0 });
+        assert_eq!(layouts[1].location, Point { x: 8.0, y: 0.0 });
+        assert_eq!(layouts[1].size.width, 24.0);
+        // the spacer pushes the last button to the right edge
+        assert_eq!(layouts[2].location, Point { x: 33.0, y: 0.0 });
+
+        let mut dialog = FlexBox::<()>::new();
+        dialog.vertical();
+        dialog.set_size(Some(20.0), Some(10.0));
+        dialog.set_justify_content(JustifyContent::Center);
+        dialog.set_align_items(AlignItems::Center);
+        let mut ok = Button::<()>::new("ok");
+        ok.set_size(Some(6.0), Some(2.0));
+        dialog.add_child(boxed::Box::new(ok));
+
+        let layout_tree = crate::layout::compute_layout(
+            &mut dialog,
+            Size {
+                width: Number::Defined(100.0),
+                height: Number::Defined(100.0),
+            },
+        );
+        assert_eq!(
+            layout_tree.children_layout[0].layout.location,
+            Point { x: 7.0, y: 4.0 }
+        );
+    }
 }
@@ -1,21 +1,26 @@
 use crate::{
-    buffer::{
-        Buffer,
-        Cell,
+    buffer::Buffer,
+    widget_event::{
+        Phase,
+        WidgetEvent,
     },
+    widget_id::WidgetId,
     Cmd,
+    InlineStyle,
     LayoutTree,
+    Theme,
     Widget,
 };
 use crossterm::event::{
     Event,
+    KeyCode,
+    KeyEvent,
     MouseEvent,
 };
-use ito_canvas::unicode_canvas::{
-    Border,
-    Canvas,
+use sauron_vdom::{
+    Callback,
+    Value,
 };
-use sauron_vdom::Callback;
 use std::{
     any::Any,
     fmt,
@@ -30,7 +35,7 @@ use stretch::{
 };
 
 /// A button widget
-#[derive(PartialEq, Clone)]
+#[derive(Clone)]
 pub struct Button<MSG>
 where
     MSG: 'static,
@@ -40,8 +45,14 @@ where
     width: Option<f32>,
     height: Option<f32>,
     focused: bool,
+    hovered: bool,
+    disabled: bool,
     on_click: Vec<Callback<sauron_vdom::Event, MSG>>,
     id: Option<String>,
+    widget_id: Option<WidgetId>,
+    classes: Vec<String>,
+    theme: Option<Theme>,
+    inline_style: Option<InlineStyle>,
 }
 
 impl<MSG> Default for Button<MSG> {
@@ -52,12 +63,35 @@ impl<MSG> Default for Button<MSG> {
             width: None,
             height: None,
             focused: false,
+            hovered: false,
+            disabled: false,
             on_click: vec![],
             id: None,
+            widget_id: None,
+            classes: vec![],
+            theme: None,
+            inline_style: None,
         }
     }
 }
 
+// the widget_id is the identity of the button in the tree, not part of its value
+impl<MSG> PartialEq for Button<MSG> {
+    fn eq(&self, other: &Self) -> bool {
+        self.label == other.label
+            && self.is_rounded == other.is_rounded
+            && self.width == other.width
+            && self.height == other.height
+            && self.focused == other.focused
+            && self.hovered == other.hovered
+            && self.disabled == other.disabled
+            && self.on_click == other.on_click
+            && self.id == other.id
+            && self.classes == other.classes
+            && self.theme == other.theme
+    }
+}
+
 impl<MSG> Debug for Button<MSG> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Button")
@@ -137,7 +171,12 @@ where
     }
 
     /// draw this button to the buffer, with the given computed layout
-    fn draw(&mut self, buf: &mut Buffer, layout_tree: &LayoutTree) -> Vec<Cmd> {
+    fn draw(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        theme: &Theme,
+    ) -> Vec<Cmd> {
         let layout = layout_tree.layout;
         let loc_x = layout.location.x.round() as usize;
         let loc_y = layout.location.y.round() as usize;
@@ -149,28 +188,10 @@ where
         let bottom = top + height - 1;
         let right = left + width - 1;
 
-        let border = Border {
-            use_thick_border: false,
-            has_top: true,
-            has_bottom: true,
-            has_left: true,
-            has_right: true,
-            is_top_left_rounded: self.is_rounded,
-            is_top_right_rounded: self.is_rounded,
-            is_bottom_left_rounded: self.is_rounded,
-            is_bottom_right_rounded: self.is_rounded,
-        };
-        let mut canvas = Canvas::new();
-        canvas.draw_rect((left, top), (right, bottom), border);
-        buf.write_canvas(canvas);
-
-        for (t, ch) in self.label.chars().enumerate() {
-            let mut cell = Cell::new(ch);
-            if self.focused {
-                cell.bold();
-            }
-            buf.set_cell(loc_x + 1 + t, loc_y + 1, cell);
-        }
+        let style = theme.widget_style(self);
+        style.fill(buf, (left, top), (right, bottom));
+        style.draw_border(buf, (left, top), (right, bottom), self.is_rounded);
+        style.write_str(buf, loc_x + 1, loc_y + 1, &self.label);
 
         vec![]
     }
@@ -179,6 +200,30 @@ where
         self.focused = focused;
     }
 
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+    }
+
+    fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    fn set_hovered(&mut self, hovered: bool) {
+        self.hovered = hovered;
+    }
+
+    fn is_hovered(&self) -> bool {
+        self.hovered
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -203,10 +248,52 @@ where
                     .map(|cb| cb.emit(s_event.clone()))
                     .collect()
             }
+            // pressing enter or space on a focused button also clicks it
+            Event::Key(KeyEvent {
+                code: KeyCode::Enter,
+                ..
+            })
+            | Event::Key(KeyEvent {
+                code: KeyCode::Char(' '),
+                ..
+            }) => {
+                let s_event: sauron_vdom::Event =
+                    sauron_vdom::event::KeyEvent::new("Enter".to_string())
+                        .into();
+                self.on_click
+                    .iter()
+                    .map(|cb| cb.emit(s_event.clone()))
+                    .collect()
+            }
             _ => vec![],
         }
     }
 
+    fn consume_event(&mut self, event: Event) -> (Vec<MSG>, bool) {
+        let consumed = match event {
+            Event::Key(KeyEvent { code, .. }) => {
+                code == KeyCode::Enter || code == KeyCode::Char(' ')
+            }
+            _ => false,
+        };
+        (self.process_event(event), consumed)
+    }
+
+    fn handle_event(&mut self, event: &mut WidgetEvent) -> Vec<MSG> {
+        if event.phase != Phase::Target || event.is_default_prevented() {
+            return vec![];
+        }
+        // the click is consumed here, so the containers don't react to it
+        if let Event::Mouse(MouseEvent::Down(..)) = event.event {
+            event.stop_propagation();
+        }
+        let (msgs, consumed) = self.consume_event(event.event);
+        if consumed {
+            event.stop_propagation();
+        }
+        msgs
+    }
+
     fn set_id(&mut self, id: &str) {
         self.id = Some(id.to_string());
     }
@@ -214,4 +301,72 @@ where
     fn get_id(&self) -> &Option<String> {
         &self.id
     }
+
+    fn set_widget_id(&mut self, widget_id: WidgetId) {
+        self.widget_id = Some(widget_id);
+    }
+
+    fn get_widget_id(&self) -> Option<WidgetId> {
+        self.widget_id
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.classes.push(class.to_string());
+    }
+
+    fn remove_class(&mut self, class: &str) {
+        self.classes.retain(|c| c != class);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    fn get_theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    fn set_inline_style(&mut self, style: InlineStyle) {
+        self.inline_style = Some(style);
+    }
+
+    fn get_inline_style(&self) -> Option<&InlineStyle> {
+        self.inline_style.as_ref()
+    }
+
+    fn get_tag(&self) -> &'static str {
+        "button"
+    }
+
+    fn set_attribute(&mut self, name: &str, value: &Value) {
+        match name {
+            "label" => self.set_label(value),
+            "rounded" => self.set_rounded(value.as_bool().unwrap_or(true)),
+            "disabled" => self.disabled = value.as_bool().unwrap_or(true),
+            _ => (),
+        }
+    }
+
+    fn remove_attribute(&mut self, name: &str) {
+        match name {
+            "label" => self.set_label(""),
+            "rounded" => self.set_rounded(true),
+            "disabled" => self.disabled = false,
+            _ => (),
+        }
+    }
+
+    fn set_event_listeners(
+        &mut self,
+        event: &str,
+        listeners: Vec<Callback<sauron_vdom::Event, MSG>>,
+    ) {
+        if event == "click" {
+            self.on_click = listeners;
+        }
+    }
 }
@@ -1,15 +1,27 @@
 use crate::{
     buffer::Buffer,
     symbol,
+    widget_event::{
+        Phase,
+        WidgetEvent,
+    },
+    widget_id::WidgetId,
     Cmd,
+    InlineStyle,
     LayoutTree,
+    Theme,
     Widget,
 };
 use crossterm::event::{
     Event,
+    KeyCode,
+    KeyEvent,
     MouseEvent,
 };
-use sauron_vdom::Callback;
+use sauron_vdom::{
+    Callback,
+    Value,
+};
 use std::{
     any::Any,
     fmt,
@@ -28,7 +40,12 @@ use stretch::{
 pub struct Checkbox<MSG> {
     label: String,
     is_checked: bool,
+    focused: bool,
     id: Option<String>,
+    widget_id: Option<WidgetId>,
+    classes: Vec<String>,
+    theme: Option<Theme>,
+    inline_style: Option<InlineStyle>,
     on_input: Vec<Callback<sauron_vdom::Event, MSG>>,
 }
 
@@ -37,7 +54,12 @@ impl<MSG> Default for Checkbox<MSG> {
         Checkbox {
             label: String::new(),
             is_checked: false,
+            focused: false,
             id: None,
+            widget_id: None,
+            classes: vec![],
+            theme: None,
+            inline_style: None,
             on_input: vec![],
         }
     }
@@ -65,6 +87,11 @@ impl<MSG> Checkbox<MSG> {
         self.is_checked = checked;
     }
 
+    /// returns true if the checkbox is checked
+    pub fn is_checked(&self) -> bool {
+        self.is_checked
+    }
+
     /// attach a listener to this checkbox which will be triggered
     /// when the check status is changed
     pub fn add_input_listener(
@@ -91,7 +118,12 @@ impl<MSG: 'static> Widget<MSG> for Checkbox<MSG> {
     }
 
     /// draw this button to the buffer, with the given computed layout
-    fn draw(&mut self, buf: &mut Buffer, layout_tree: &LayoutTree) -> Vec<Cmd> {
+    fn draw(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        theme: &Theme,
+    ) -> Vec<Cmd> {
         let layout = layout_tree.layout;
         let loc_x = layout.location.x.round() as usize;
         let loc_y = layout.location.y.round() as usize;
@@ -100,14 +132,28 @@ impl<MSG: 'static> Widget<MSG> for Checkbox<MSG> {
         } else {
             symbol::BOX_UNCHECKED
         };
-        buf.set_symbol(loc_x, loc_y, box_symbol);
+        let style = theme.widget_style(self);
+        buf.set_cell(loc_x, loc_y, style.cell(box_symbol));
 
         for (t, ch) in self.label.chars().enumerate() {
-            buf.set_symbol(loc_x + 3 + t, loc_y, ch);
+            let cell = style.cell(ch);
+            buf.set_cell(loc_x + 3 + t, loc_y, cell);
         }
         vec![]
     }
 
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -120,7 +166,11 @@ impl<MSG: 'static> Widget<MSG> for Checkbox<MSG> {
 
     fn process_event(&mut self, event: Event) -> Vec<MSG> {
         match event {
-            Event::Mouse(MouseEvent::Down(_btn, _x, _y, _modifier)) => {
+            Event::Mouse(MouseEvent::Down(..))
+            | Event::Key(KeyEvent {
+                code: KeyCode::Char(' '),
+                ..
+            }) => {
                 self.is_checked = !self.is_checked;
                 let s_event: sauron_vdom::Event =
                     sauron_vdom::event::InputEvent::new(self.is_checked).into();
@@ -133,6 +183,29 @@ impl<MSG: 'static> Widget<MSG> for Checkbox<MSG> {
         }
     }
 
+    fn consume_event(&mut self, event: Event) -> (Vec<MSG>, bool) {
+        let consumed = match event {
+            Event::Key(KeyEvent { code, .. }) => code == KeyCode::Char(' '),
+            _ => false,
+        };
+        (self.process_event(event), consumed)
+    }
+
+    fn handle_event(&mut self, event: &mut WidgetEvent) -> Vec<MSG> {
+        if event.phase != Phase::Target || event.is_default_prevented() {
+            return vec![];
+        }
+        // the click is consumed here, so the containers don't react to it
+        if let Event::Mouse(MouseEvent::Down(..)) = event.event {
+            event.stop_propagation();
+        }
+        let (msgs, consumed) = self.consume_event(event.event);
+        if consumed {
+            event.stop_propagation();
+        }
+        msgs
+    }
+
     fn set_id(&mut self, id: &str) {
         self.id = Some(id.to_string());
     }
@@ -140,6 +213,72 @@ impl<MSG: 'static> Widget<MSG> for Checkbox<MSG> {
     fn get_id(&self) -> &Option<String> {
         &self.id
     }
+
+    fn set_widget_id(&mut self, widget_id: WidgetId) {
+        self.widget_id = Some(widget_id);
+    }
+
+    fn get_widget_id(&self) -> Option<WidgetId> {
+        self.widget_id
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.classes.push(class.to_string());
+    }
+
+    fn remove_class(&mut self, class: &str) {
+        self.classes.retain(|c| c != class);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    fn get_theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    fn set_inline_style(&mut self, style: InlineStyle) {
+        self.inline_style = Some(style);
+    }
+
+    fn get_inline_style(&self) -> Option<&InlineStyle> {
+        self.inline_style.as_ref()
+    }
+
+    fn get_tag(&self) -> &'static str {
+        "checkbox"
+    }
+
+    fn set_attribute(&mut self, name: &str, value: &Value) {
+        match name {
+            "label" => self.set_label(value),
+            "checked" => self.set_checked(value.as_bool().unwrap_or(false)),
+            _ => (),
+        }
+    }
+
+    fn remove_attribute(&mut self, name: &str) {
+        match name {
+            "label" => self.set_label(""),
+            "checked" => self.set_checked(false),
+            _ => (),
+        }
+    }
+
+    fn set_event_listeners(
+        &mut self,
+        event: &str,
+        listeners: Vec<Callback<sauron_vdom::Event, MSG>>,
+    ) {
+        if event == "input" {
+            self.on_input = listeners;
+        }
+    }
 }
 
 impl<MSG> Debug for Checkbox<MSG> {
@@ -0,0 +1,1095 @@
+use crate::{
+    buffer::Buffer,
+    component,
+    symbol,
+    widget_event::{
+        Phase,
+        WidgetEvent,
+    },
+    widget_id::{
+        self,
+        WidgetId,
+    },
+    Cmd,
+    InlineStyle,
+    InputBuffer,
+    Layer,
+    LayoutTree,
+    Placement,
+    StateStyle,
+    Theme,
+    Widget,
+};
+use crossterm::{
+    event::{
+        Event,
+        KeyCode,
+        KeyEvent,
+        KeyModifiers,
+        MouseEvent,
+    },
+    style::Attribute,
+};
+use ito_canvas::unicode_canvas::{
+    Border,
+    Canvas,
+};
+use sauron_vdom::{
+    Callback,
+    Value,
+};
+use std::{
+    any::Any,
+    fmt,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+use stretch::{
+    geometry::Size,
+    result::Layout,
+    style::{
+        Dimension,
+        Style,
+    },
+};
+use unicode_width::UnicodeWidthStr;
+
+/// the arrow shown at the right of the combobox
+const ARROW: char = '⌵';
+
+/// the typed letters are forgotten after this long without typing
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A drop down box which shows the selected item of a list.
+///
+/// The popup list is opened with a click, Enter or Alt-Down and is shown
+/// in an overlay layer below the combobox. The Up/Down keys move the highlighted item
+/// while the popup is open and selects the next item directly while it is closed.
+/// Typing the first letters of an item jumps to it.
+///
+/// An editable combobox also accepts a free text, which is committed with Enter.
+///
+/// `Alt-Down` and `Alt-Up` are claimed by the focused combobox, before the keymap
+/// can use them to move the focus.
+///
+/// ```ignore
+///  ┌──────────┬─┐
+///  │Banana    │⌵│
+///  └──────────┴─┘
+///  ┌────────────┐
+///  │Apple       ▮
+///  │Banana      │
+///  └────────────┘
+/// ```
+#[derive(Debug)]
+pub struct ComboBox<MSG> {
+    items: Vec<String>,
+    selected: Option<usize>,
+    /// the item under the cursor in the popup while it is open
+    highlighted: Option<usize>,
+    /// the text shown in the box, which can be edited when the combobox is editable
+    input_buffer: InputBuffer,
+    is_editable: bool,
+    is_open: bool,
+    focused: bool,
+    max_visible: usize,
+    /// the letters typed so far to jump to an item
+    type_ahead: String,
+    /// when the last letter of the type-ahead is typed
+    last_typed: Option<Instant>,
+    /// the overlay layer of the popup list, which is only in the tree while it is open
+    popup: Vec<Box<dyn Widget<MSG>>>,
+    on_input: Vec<Callback<sauron_vdom::Event, MSG>>,
+    on_change: Vec<Callback<(Option<usize>, String), MSG>>,
+    width: Option<f32>,
+    height: Option<f32>,
+    layout: Option<Layout>,
+    id: Option<String>,
+    widget_id: Option<WidgetId>,
+    classes: Vec<String>,
+    theme: Option<Theme>,
+    inline_style: Option<InlineStyle>,
+}
+
+impl<MSG> ComboBox<MSG>
+where
+    MSG: fmt::Debug + 'static,
+{
+    /// create a new combobox with no items
+    pub fn new() -> Self {
+        ComboBox {
+            items: vec![],
+            selected: None,
+            highlighted: None,
+            input_buffer: InputBuffer::new(),
+            is_editable: false,
+            is_open: false,
+            focused: false,
+            max_visible: 8,
+            type_ahead: String::new(),
+            last_typed: None,
+            popup: vec![],
+            on_input: vec![],
+            on_change: vec![],
+            width: None,
+            height: None,
+            layout: None,
+            id: None,
+            widget_id: None,
+            classes: vec![],
+            theme: None,
+            inline_style: None,
+        }
+    }
+
+    /// set the items to choose from, the selection is kept if it is still in the list
+    pub fn set_items(&mut self, items: Vec<String>) {
+        self.items = items;
+        let selected = self.selected.filter(|index| *index < self.items.len());
+        self.set_selected(selected);
+    }
+
+    /// returns the items to choose from
+    pub fn get_items(&self) -> &[String] {
+        &self.items
+    }
+
+    /// select the item at this index, the selection is cleared if it is None
+    pub fn set_selected(&mut self, selected: Option<usize>) {
+        self.selected = selected.filter(|index| *index < self.items.len());
+        let value = self
+            .selected
+            .map(|index| self.items[index].clone())
+            .unwrap_or_default();
+        self.input_buffer = InputBuffer::new_with_value(value);
+    }
+
+    /// returns the index of the selected item,
+    /// which is None if nothing is selected or the value is a free text
+    pub fn get_selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// set the value shown in the box, the item with the same value is selected.
+    /// A value that is not one of the items is kept only when the combobox is editable.
+    pub fn set_value<S: ToString>(&mut self, value: S) {
+        let value = value.to_string();
+        let index = self.items.iter().position(|item| *item == value);
+        self.set_selected(index);
+        if index.is_none() && self.is_editable {
+            self.input_buffer = InputBuffer::new_with_value(value);
+        }
+    }
+
+    /// returns the value shown in the box
+    pub fn get_value(&self) -> &str {
+        self.input_buffer.get_content()
+    }
+
+    /// set whether a free text can be typed into the box
+    pub fn set_editable(&mut self, editable: bool) {
+        self.is_editable = editable;
+    }
+
+    /// set the number of items shown in the popup before it scrolls
+    pub fn set_max_visible(&mut self, max_visible: usize) {
+        self.max_visible = max_visible.max(1);
+    }
+
+    /// returns true if the popup list is open
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// attach a listener which receives an input event with the new value,
+    /// when an item is selected or a free text is committed
+    pub fn add_input_listener(
+        &mut self,
+        cb: Callback<sauron_vdom::Event, MSG>,
+    ) {
+        self.on_input.push(cb);
+    }
+
+    /// attach a listener which receives the index of the selected item and its value,
+    /// the index is None for a free text
+    pub fn add_change_listener(
+        &mut self,
+        cb: Callback<(Option<usize>, String), MSG>,
+    ) {
+        self.on_change.push(cb);
+    }
+
+    /// open the popup list with the selected item highlighted
+    pub fn open(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
+        self.is_open = true;
+        self.highlighted = self.selected.or(Some(0));
+        self.type_ahead.clear();
+        self.sync_popup();
+    }
+
+    /// close the popup list without changing the selection
+    pub fn close(&mut self) {
+        self.is_open = false;
+        self.highlighted = None;
+        self.type_ahead.clear();
+        self.sync_popup();
+    }
+
+    /// the width which fits the longest item, the divider and the arrow
+    fn content_width(&self) -> f32 {
+        let longest = self
+            .items
+            .iter()
+            .map(|item| item.width())
+            .max()
+            .unwrap_or(0);
+        (longest + 4).max(5) as f32
+    }
+
+    /// the item the keys move from, which is the highlighted item while the popup is open
+    fn current(&self) -> Option<usize> {
+        if self.is_open {
+            self.highlighted
+        } else {
+            self.selected
+        }
+    }
+
+    /// add, update or remove the layer of the popup list to match the state of the combobox
+    fn sync_popup(&mut self) {
+        let anchor = match self.widget_id {
+            Some(widget_id) if self.is_open => widget_id,
+            _ => {
+                self.popup.clear();
+                return;
+            }
+        };
+        if self.popup.is_empty() {
+            let mut layer = Layer::new(Placement::Below(anchor));
+            layer.add_child(Box::new(Popup::default()));
+            let mut layer: Box<dyn Widget<MSG>> = Box::new(layer);
+            widget_id::ensure_widget_id(layer.as_mut());
+            self.popup.push(layer);
+        }
+        let width = self
+            .layout
+            .map(|layout| layout.size.width)
+            .unwrap_or_else(|| self.content_width());
+        let rows = self.items.len().min(self.max_visible);
+        let items = self.items.clone();
+        let highlighted = self.highlighted;
+        if let Some(popup) = self.popup_list_mut() {
+            popup.items = items;
+            popup.highlighted = highlighted;
+            popup.rows = rows;
+            popup.width = width;
+            popup.scroll_into_view();
+        }
+    }
+
+    fn popup_list_mut(&mut self) -> Option<&mut Popup> {
+        self.popup
+            .first_mut()?
+            .child_mut(0)?
+            .as_any_mut()
+            .downcast_mut::<Popup>()
+    }
+
+    /// select the item at this index and emit the change if it is a different item
+    fn select(&mut self, index: usize) -> Vec<MSG> {
+        if index >= self.items.len()
+            || (self.selected == Some(index)
+                && self.get_value() == self.items[index])
+        {
+            return vec![];
+        }
+        self.set_selected(Some(index));
+        self.emit_change()
+    }
+
+    /// commit the highlighted item, or the typed text if no item is highlighted
+    fn commit(&mut self) -> Vec<MSG> {
+        match self.highlighted {
+            Some(index) => self.select(index),
+            None if self.is_editable => {
+                let value = self.get_value().to_string();
+                self.selected =
+                    self.items.iter().position(|item| *item == value);
+                self.emit_change()
+            }
+            None => vec![],
+        }
+    }
+
+    fn emit_change(&self) -> Vec<MSG> {
+        let value = self.get_value().to_string();
+        let s_event: sauron_vdom::Event =
+            sauron_vdom::event::InputEvent::new(value.clone()).into();
+        let mut msgs: Vec<MSG> = self
+            .on_input
+            .iter()
+            .map(|cb| cb.emit(s_event.clone()))
+            .collect();
+        msgs.extend(
+            self.on_change
+                .iter()
+                .map(|cb| cb.emit((self.selected, value.clone()))),
+        );
+        msgs
+    }
+
+    /// highlight the item at this index while the popup is open,
+    /// otherwise select it right away
+    fn move_to(&mut self, index: usize) -> Vec<MSG> {
+        if self.items.is_empty() {
+            return vec![];
+        }
+        let index = index.min(self.items.len() - 1);
+        if self.is_open {
+            self.highlighted = Some(index);
+            self.sync_popup();
+            vec![]
+        } else {
+            self.select(index)
+        }
+    }
+
+    fn move_by(&mut self, delta: isize) -> Vec<MSG> {
+        let index = match self.current() {
+            Some(current) => (current as isize + delta).max(0) as usize,
+            None if delta < 0 => self.items.len().saturating_sub(1),
+            None => 0,
+        };
+        self.move_to(index)
+    }
+
+    /// jump to the next item which starts with the letters typed so far.
+    /// If there is none, the typing starts over from this letter,
+    /// so pressing the same letter again cycles through the items starting with it.
+    fn type_ahead(&mut self, ch: char) -> Vec<MSG> {
+        let now = Instant::now();
+        if let Some(last_typed) = self.last_typed {
+            if now.duration_since(last_typed) > TYPE_AHEAD_TIMEOUT {
+                self.type_ahead.clear();
+            }
+        }
+        self.last_typed = Some(now);
+        let current = self.current();
+        let is_continued = !self.type_ahead.is_empty();
+        self.type_ahead.push(ch);
+        // a longer prefix can still match the current item
+        let start = match current {
+            Some(current) if is_continued => current,
+            Some(current) => current + 1,
+            None => 0,
+        };
+        let found =
+            find_prefix(&self.items, &self.type_ahead, start).or_else(|| {
+                self.type_ahead = ch.to_string();
+                find_prefix(
+                    &self.items,
+                    &self.type_ahead,
+                    current.map(|current| current + 1).unwrap_or(0),
+                )
+            });
+        match found {
+            Some(index) => self.move_to(index),
+            None => vec![],
+        }
+    }
+
+    /// edit the free text, the popup is opened with the first item
+    /// which starts with the text highlighted
+    fn edit(&mut self, key_event: KeyEvent) -> bool {
+        let before = self.get_value().to_string();
+        if !self.input_buffer.process_key_event(key_event) {
+            return false;
+        }
+        if self.get_value() != before {
+            let value = self.get_value().to_string();
+            if !self.is_open {
+                self.open();
+            }
+            self.highlighted = if value.is_empty() {
+                None
+            } else {
+                find_prefix(&self.items, &value, 0)
+            };
+            self.sync_popup();
+        }
+        true
+    }
+
+    /// returns the msgs of the key and whether it is consumed
+    fn process_key(&mut self, key_event: KeyEvent) -> (Vec<MSG>, bool) {
+        let KeyEvent { code, modifiers } = key_event;
+        let alt = modifiers.contains(KeyModifiers::ALT);
+        if let KeyCode::Char(_) = code {
+        } else {
+            self.type_ahead.clear();
+        }
+        let page = self.max_visible as isize;
+        let msgs = match code {
+            KeyCode::Down if alt => {
+                self.open();
+                vec![]
+            }
+            KeyCode::Up if alt => {
+                self.close();
+                vec![]
+            }
+            KeyCode::Enter if self.is_open => {
+                let msgs = self.commit();
+                self.close();
+                msgs
+            }
+            KeyCode::Enter if self.is_editable => {
+                self.highlighted = None;
+                self.commit()
+            }
+            KeyCode::Enter => {
+                self.open();
+                vec![]
+            }
+            KeyCode::Esc if self.is_open => {
+                self.close();
+                vec![]
+            }
+            KeyCode::Up => self.move_by(-1),
+            KeyCode::Down => self.move_by(1),
+            KeyCode::PageUp => self.move_by(-page),
+            KeyCode::PageDown => self.move_by(page),
+            KeyCode::Home if self.is_open || !self.is_editable => {
+                self.move_to(0)
+            }
+            KeyCode::End if self.is_open || !self.is_editable => {
+                self.move_to(self.items.len().saturating_sub(1))
+            }
+            _ if self.is_editable => return (vec![], self.edit(key_event)),
+            KeyCode::Char(ch) if modifiers.is_empty() => self.type_ahead(ch),
+            _ => return (vec![], false),
+        };
+        (msgs, true)
+    }
+}
+
+impl<MSG> Default for ComboBox<MSG>
+where
+    MSG: fmt::Debug + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<MSG> Widget<MSG> for ComboBox<MSG>
+where
+    MSG: fmt::Debug + 'static,
+{
+    fn style(&self) -> Style {
+        Style {
+            size: Size {
+                width: Dimension::Points(
+                    self.width.unwrap_or_else(|| self.content_width()),
+                ),
+                height: Dimension::Points(self.height.unwrap_or(3.0)),
+            },
+            min_size: Size {
+                width: Dimension::Points(5.0),
+                height: Dimension::Points(3.0),
+            },
+            ..Default::default()
+        }
+    }
+
+    fn draw(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        theme: &Theme,
+    ) -> Vec<Cmd> {
+        let layout = layout_tree.layout;
+        self.layout = Some(layout);
+        let left = layout.location.x.round() as usize;
+        let top = layout.location.y.round() as usize;
+        let width = layout.size.width.round() as usize;
+        let height = layout.size.height.round() as usize;
+        if width < 5 || height < 3 {
+            return vec![];
+        }
+        let bottom = top + height - 1;
+        let right = left + width - 1;
+        let divider = right - 2;
+
+        let style = theme.widget_style(self);
+        style.fill(buf, (left, top), (right, bottom));
+        let rounded = style.rounded_border.unwrap_or(false);
+        let thick = style.is_thick_border();
+        let mut canvas = Canvas::new();
+        canvas.draw_rect(
+            (left, top),
+            (right, bottom),
+            Border {
+                use_thick_border: thick,
+                has_top: true,
+                has_bottom: true,
+                has_left: true,
+                has_right: true,
+                is_top_left_rounded: rounded,
+                is_top_right_rounded: rounded,
+                is_bottom_left_rounded: rounded,
+                is_bottom_right_rounded: rounded,
+            },
+        );
+        canvas.draw_vertical_line((divider, top), (divider, bottom), thick);
+        style.write_canvas(buf, canvas);
+
+        // the value is not bold like the labels of the focused widgets
+        let text_style = StateStyle {
+            attributes: vec![],
+            ..style.clone()
+        };
+        let middle = top + (height - 1) / 2;
+        text_style.write_str_clipped(
+            buf,
+            left + 1,
+            middle,
+            self.get_value(),
+            divider - left - 1,
+        );
+        style.write_str(buf, divider + 1, middle, ARROW);
+
+        if self.focused && self.is_editable {
+            let cursor = self
+                .input_buffer
+                .get_cursor_location()
+                .min(divider - left - 2);
+            vec![Cmd::ShowCursor, Cmd::MoveTo(left + 1 + cursor, middle)]
+        } else {
+            vec![]
+        }
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        // the popup is closed when the focus moves to another widget
+        if self.focused && !focused && self.is_open {
+            self.close();
+        }
+        self.focused = focused;
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn claims_key(&self, key_event: &KeyEvent) -> bool {
+        key_event.modifiers.contains(KeyModifiers::ALT)
+            && (key_event.code == KeyCode::Down
+                || key_event.code == KeyCode::Up)
+    }
+
+    /// the keys and clicks on the box are handled when it is the target,
+    /// the item picked in the popup list is selected as the click bubbles up from it
+    fn handle_event(&mut self, event: &mut WidgetEvent) -> Vec<MSG> {
+        if event.is_default_prevented() {
+            return vec![];
+        }
+        match event.phase {
+            Phase::Capture => vec![],
+            Phase::Target => {
+                match event.event {
+                    Event::Key(key_event) => {
+                        let (msgs, consumed) = self.process_key(key_event);
+                        if consumed {
+                            event.stop_propagation();
+                        }
+                        msgs
+                    }
+                    Event::Mouse(MouseEvent::Down(..)) => {
+                        if self.is_open {
+                            self.close();
+                        } else {
+                            self.open();
+                        }
+                        vec![]
+                    }
+                    _ => vec![],
+                }
+            }
+            Phase::Bubble => {
+                let picked =
+                    self.popup_list_mut().and_then(|popup| popup.picked.take());
+                match picked {
+                    Some(index) => {
+                        let msgs = self.select(index);
+                        self.close();
+                        event.stop_propagation();
+                        msgs
+                    }
+                    None => vec![],
+                }
+            }
+        }
+    }
+
+    fn children(&self) -> Option<&[Box<dyn Widget<MSG>>]> {
+        Some(&self.popup)
+    }
+
+    fn children_mut(&mut self) -> Option<&mut [Box<dyn Widget<MSG>>]> {
+        Some(&mut self.popup)
+    }
+
+    fn child_mut(&mut self, index: usize) -> Option<&mut Box<dyn Widget<MSG>>> {
+        self.popup.get_mut(index)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn set_size(&mut self, width: Option<f32>, height: Option<f32>) {
+        self.width = width;
+        self.height = height;
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.id = Some(id.to_string());
+    }
+
+    fn get_id(&self) -> &Option<String> {
+        &self.id
+    }
+
+    fn set_widget_id(&mut self, widget_id: WidgetId) {
+        self.widget_id = Some(widget_id);
+    }
+
+    fn get_widget_id(&self) -> Option<WidgetId> {
+        self.widget_id
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.classes.push(class.to_string());
+    }
+
+    fn remove_class(&mut self, class: &str) {
+        self.classes.retain(|c| c != class);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    fn get_theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    fn set_inline_style(&mut self, style: InlineStyle) {
+        self.inline_style = Some(style);
+    }
+
+    fn get_inline_style(&self) -> Option<&InlineStyle> {
+        self.inline_style.as_ref()
+    }
+
+    fn get_tag(&self) -> &'static str {
+        "combobox"
+    }
+
+    fn set_attribute(&mut self, name: &str, value: &Value) {
+        match name {
+            "labels" => self.set_items(component::value_to_strings(value)),
+            "value" => {
+                let value = value.to_string();
+                if self.get_value() != value {
+                    self.set_value(value);
+                }
+            }
+            "selected" => {
+                self.set_selected(value.as_f64().map(|index| index as usize))
+            }
+            "editable" => self.set_editable(value.as_bool() == Some(true)),
+            _ => (),
+        }
+    }
+
+    fn remove_attribute(&mut self, name: &str) {
+        match name {
+            "labels" => self.set_items(vec![]),
+            "value" | "selected" => self.set_selected(None),
+            "editable" => self.set_editable(false),
+            _ => (),
+        }
+    }
+
+    fn set_event_listeners(
+        &mut self,
+        event: &str,
+        listeners: Vec<Callback<sauron_vdom::Event, MSG>>,
+    ) {
+        if event == "input" {
+            self.on_input = listeners;
+        }
+    }
+}
+
+/// the index of the first item from start which starts with the prefix, ignoring the case.
+/// The search wraps around to the beginning of the list.
+fn find_prefix(items: &[String], prefix: &str, start: usize) -> Option<usize> {
+    let prefix = prefix.to_lowercase();
+    (0..items.len())
+        .map(|i| (start + i) % items.len())
+        .find(|index| items[*index].to_lowercase().starts_with(&prefix))
+}
+
+/// The list of items shown in the popup of a combobox.
+/// The index of the item that is clicked is kept for the combobox to pick up.
+#[derive(Debug, Default)]
+struct Popup {
+    items: Vec<String>,
+    highlighted: Option<usize>,
+    picked: Option<usize>,
+    scroll_top: usize,
+    rows: usize,
+    width: f32,
+    id: Option<String>,
+    widget_id: Option<WidgetId>,
+}
+
+impl Popup {
+    /// scroll the list so the highlighted item is visible
+    fn scroll_into_view(&mut self) {
+        if let Some(highlighted) = self.highlighted {
+            if highlighted < self.scroll_top {
+                self.scroll_top = highlighted;
+            } else if highlighted >= self.scroll_top + self.rows {
+                self.scroll_top = highlighted + 1 - self.rows;
+            }
+        }
+        self.scroll_by(0);
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        let max = self.items.len().saturating_sub(self.rows) as isize;
+        self.scroll_top =
+            (self.scroll_top as isize + delta).min(max).max(0) as usize;
+    }
+}
+
+impl<MSG> Widget<MSG> for Popup {
+    fn style(&self) -> Style {
+        Style {
+            size: Size {
+                width: Dimension::Points(self.width),
+                height: Dimension::Points(self.rows as f32 + 2.0),
+            },
+            ..Default::default()
+        }
+    }
+
+    fn draw(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        theme: &Theme,
+    ) -> Vec<Cmd> {
+        let layout = layout_tree.layout;
+        let left = layout.location.x.round() as usize;
+        let top = layout.location.y.round() as usize;
+        let width = layout.size.width.round() as usize;
+        let height = layout.size.height.round() as usize;
+        if width < 3 || height < 3 {
+            return vec![];
+        }
+        let bottom = top + height - 1;
+        let right = left + width - 1;
+        let inner_width = width - 2;
+
+        let style = theme.widget_style::<MSG, _>(self);
+        style.fill(buf, (left, top), (right, bottom));
+        style.draw_border(buf, (left, top), (right, bottom), false);
+
+        let highlight_style =
+            style.merge(&theme.accent_style()).merge(&StateStyle {
+                attributes: vec![Attribute::Reverse],
+                ..Default::default()
+            });
+        for row in 0..self.rows {
+            let index = self.scroll_top + row;
+            let item = match self.items.get(index) {
+                Some(item) => item,
+                None => break,
+            };
+            let item_style = if self.highlighted == Some(index) {
+                &highlight_style
+            } else {
+                &style
+            };
+            let y = top + 1 + row;
+            item_style.write_str(buf, left + 1, y, " ".repeat(inner_width));
+            item_style.write_str_clipped(buf, left + 1, y, item, inner_width);
+        }
+
+        // the thumb of the scrollbar is drawn on the right border
+        if self.items.len() > self.rows && self.rows > 0 {
+            let max_scroll = self.items.len() - self.rows;
+            let thumb = (self.scroll_top * (self.rows - 1) + max_scroll / 2)
+                / max_scroll;
+            style.write_str(buf, right, top + 1 + thumb, symbol::MIDDLE_BLOCK);
+        }
+        vec![]
+    }
+
+    fn handle_event(&mut self, event: &mut WidgetEvent) -> Vec<MSG> {
+        if event.phase != Phase::Target {
+            return vec![];
+        }
+        match event.event {
+            Event::Mouse(MouseEvent::Down(_, _, y, _)) => {
+                let row = y as f32 - event.origin.1.round() - 1.0;
+                if row >= 0.0 && (row as usize) < self.rows {
+                    let index = self.scroll_top + row as usize;
+                    if index < self.items.len() {
+                        self.picked = Some(index);
+                    }
+                }
+            }
+            Event::Mouse(MouseEvent::ScrollDown(..)) => self.scroll_by(1),
+            Event::Mouse(MouseEvent::ScrollUp(..)) => self.scroll_by(-1),
+            _ => (),
+        }
+        vec![]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn set_size(&mut self, width: Option<f32>, _height: Option<f32>) {
+        if let Some(width) = width {
+            self.width = width;
+        }
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.id = Some(id.to_string());
+    }
+
+    fn get_id(&self) -> &Option<String> {
+        &self.id
+    }
+
+    fn set_widget_id(&mut self, widget_id: WidgetId) {
+        self.widget_id = Some(widget_id);
+    }
+
+    fn get_widget_id(&self) -> Option<WidgetId> {
+        self.widget_id
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        Dispatch,
+        FlexBox,
+        HeadlessBackend,
+        Renderer,
+    };
+    use crossterm::event::MouseButton;
+    use std::cell::RefCell;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Msg {
+        Changed(Option<usize>, String),
+    }
+
+    #[derive(Default)]
+    struct App {
+        msgs: RefCell<Vec<Msg>>,
+    }
+
+    impl Dispatch<Msg> for App {
+        fn dispatch(
+            &self,
+            msg: Msg,
+            _root_node: &mut dyn Widget<Msg>,
+        ) -> Vec<Cmd> {
+            self.msgs.borrow_mut().push(msg);
+            vec![]
+        }
+    }
+
+    fn fruits() -> ComboBox<Msg> {
+        let mut combo = ComboBox::new();
+        combo.set_items(
+            ["apple", "banana", "cherry", "date"]
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        );
+        combo.add_change_listener(Callback::from(|(index, value)| {
+            Msg::Changed(index, value)
+        }));
+        combo
+    }
+
+    fn run(
+        combo: ComboBox<Msg>,
+        events: Vec<Event>,
+    ) -> (Vec<String>, Vec<Msg>, FlexBox<Msg>) {
+        let mut root = FlexBox::new();
+        root.vertical();
+        root.add_child(Box::new(combo));
+        let mut events = events;
+        events.insert(0, Event::Key(KeyCode::Tab.into()));
+        let backend = HeadlessBackend::new(16, 10, events);
+        let mut out = vec![];
+        let app = App::default();
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            Some(&app),
+            &mut root,
+            Box::new(backend),
+        );
+        renderer.run().expect("must run");
+        let lines = renderer
+            .buffer()
+            .to_plain_text()
+            .lines()
+            .map(ToString::to_string)
+            .collect();
+        drop(renderer);
+        let msgs = app.msgs.borrow().clone();
+        (lines, msgs, root)
+    }
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(code.into())
+    }
+
+    #[test]
+    fn select_with_keys() {
+        let (lines, msgs, _root) = run(
+            fruits(),
+            vec![
+                key(KeyCode::Enter),
+                key(KeyCode::Down),
+                key(KeyCode::Enter),
+                // the popup is closed, the items are selected right away
+                key(KeyCode::Char('c')),
+                key(KeyCode::Char('d')),
+                // reopen to see the popup
+                Event::Key(KeyEvent::new(KeyCode::Down, KeyModifiers::ALT)),
+            ],
+        );
+        assert_eq!(
+            msgs,
+            vec![
+                Msg::Changed(Some(1), "banana".to_string()),
+                Msg::Changed(Some(2), "cherry".to_string()),
+                Msg::Changed(Some(3), "date".to_string()),
+            ]
+        );
+        assert_eq!(lines[0], "┏━━━━━━┳━┓      ");
+        assert_eq!(lines[1], "┃date  ┃⌵┃      ");
+        // the popup is below the combobox, with the selected item highlighted
+        assert_eq!(lines[3], "┌────────┐      ");
+        assert_eq!(lines[7], "│date    │      ");
+        assert_eq!(lines[8], "└────────┘      ");
+    }
+
+    #[test]
+    fn type_ahead_times_out() {
+        let mut combo = fruits();
+        combo.process_key(KeyCode::Char('c').into());
+        combo.process_key(KeyCode::Char('h').into());
+        assert_eq!(combo.type_ahead, "ch");
+        assert_eq!(combo.get_value(), "cherry");
+        // after a pause, the typing starts over
+        combo.last_typed = Some(Instant::now() - 2 * TYPE_AHEAD_TIMEOUT);
+        combo.process_key(KeyCode::Char('b').into());
+        assert_eq!(combo.type_ahead, "b");
+        assert_eq!(combo.get_value(), "banana");
+        // a key which is not a letter also starts the typing over
+        combo.process_key(KeyCode::Down.into());
+        assert!(combo.type_ahead.is_empty());
+    }
+
+    #[test]
+    fn wide_items() {
+        let mut combo = ComboBox::<Msg>::new();
+        combo.set_items(vec!["日本".to_string(), "x".to_string()]);
+        assert_eq!(combo.content_width(), 8.0);
+    }
+
+    #[test]
+    fn pick_with_mouse() {
+        let click = |x, y| {
+            Event::Mouse(MouseEvent::Down(
+                MouseButton::Left,
+                x,
+                y,
+                KeyModifiers::empty(),
+            ))
+        };
+        let (lines, msgs, root) = run(fruits(), vec![click(2, 1), click(2, 6)]);
+        assert_eq!(msgs, vec![Msg::Changed(Some(2), "cherry".to_string())]);
+        // the popup is closed after picking an item
+        assert_eq!(lines[4], "                ");
+        let combo = root.children().unwrap()[0]
+            .as_any()
+            .downcast_ref::<ComboBox<Msg>>()
+            .unwrap();
+        assert!(!combo.is_open());
+        assert_eq!(combo.get_selected(), Some(2));
+    }
+
+    #[test]
+    fn free_text() {
+        let mut combo = fruits();
+        combo.set_editable(true);
+        let (_lines, msgs, _root) = run(
+            combo,
+            vec![
+                key(KeyCode::Char('b')),
+                key(KeyCode::Char('a')),
+                // the first item starting with the text is highlighted
+                key(KeyCode::Enter),
+                key(KeyCode::Char('s')),
+                key(KeyCode::Esc),
+                key(KeyCode::Enter),
+            ],
+        );
+        assert_eq!(
+            msgs,
+            vec![
+                Msg::Changed(Some(1), "banana".to_string()),
+                Msg::Changed(None, "bananas".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,621 @@
+use crate::{
+    buffer::Buffer,
+    find_node,
+    widget::Flex,
+    widget_event::{
+        Phase,
+        WidgetEvent,
+    },
+    widget_id::{
+        self,
+        WidgetId,
+    },
+    Button,
+    Cmd,
+    FlexBox,
+    InlineStyle,
+    LayoutTree,
+    Placement,
+    TextInput,
+    Theme,
+    Widget,
+};
+use crossterm::{
+    event::{
+        Event,
+        KeyCode,
+        KeyEvent,
+        MouseEvent,
+    },
+    style::{
+        Attribute,
+        Color,
+    },
+};
+use sauron_vdom::Callback;
+use std::{
+    any::Any,
+    fmt,
+};
+use stretch::{
+    geometry::{
+        Rect,
+        Size,
+    },
+    style::{
+        AlignItems,
+        AlignSelf,
+        Dimension,
+        FlexDirection,
+        JustifyContent,
+        Style,
+    },
+};
+use unicode_width::UnicodeWidthStr;
+
+/// A modal dialog with a title, a message, an optional body and a row of buttons.
+///
+/// The dialog is an overlay layer centered on the screen, which keeps the focus inside of it
+/// while it is in the widget tree. Each button emits its msg when clicked, the app then removes
+/// the dialog from the tree to close it.
+///
+/// ```ignore
+///  ┌─ Quit ────────────────┐
+///  │ Discard the changes?  │▒
+///  │       ╭───╮ ╭──────╮  │▒
+///  │       │OK │ │Cancel│  │▒
+///  │       ╰───╯ ╰──────╯  │▒
+///  └───────────────────────┘▒
+///   ▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒
+/// ```
+pub struct Dialog<MSG> {
+    title: String,
+    message: Vec<String>,
+    /// the body widgets, followed by the row of buttons
+    children: Vec<Box<dyn Widget<MSG>>>,
+    /// emitted when Esc is pressed inside of the dialog
+    on_escape: Option<MSG>,
+    /// emits the value of the input of a prompt
+    on_submit: Option<Box<dyn Fn(String) -> MSG>>,
+    submit_button: Option<WidgetId>,
+    input: Option<WidgetId>,
+    has_shadow: bool,
+    width: Option<f32>,
+    height: Option<f32>,
+    id: Option<String>,
+    widget_id: Option<WidgetId>,
+    classes: Vec<String>,
+    theme: Option<Theme>,
+    inline_style: Option<InlineStyle>,
+}
+
+impl<MSG> Dialog<MSG>
+where
+    MSG: fmt::Debug + Clone + 'static,
+{
+    /// create a new dialog with this title and no buttons
+    pub fn new(title: &str) -> Self {
+        let mut buttons = FlexBox::new();
+        buttons.horizontal();
+        buttons.set_gap(1.0);
+        // the buttons are aligned to the right of the dialog
+        buttons.set_align_self(AlignSelf::Stretch);
+        buttons.set_justify_content(JustifyContent::FlexEnd);
+        let mut buttons: Box<dyn Widget<MSG>> = Box::new(buttons);
+        widget_id::ensure_widget_id(buttons.as_mut());
+        Dialog {
+            title: title.to_string(),
+            message: vec![],
+            children: vec![buttons],
+            on_escape: None,
+            on_submit: None,
+            submit_button: None,
+            input: None,
+            has_shadow: true,
+            width: None,
+            height: None,
+            id: None,
+            widget_id: None,
+            classes: vec![],
+            theme: None,
+            inline_style: None,
+        }
+    }
+
+    /// a dialog which shows the message with an OK button,
+    /// the msg is emitted when it is dismissed with the button or Esc
+    pub fn alert(title: &str, message: &str, ok: MSG) -> Self {
+        let mut dialog = Self::new(title);
+        dialog.set_message(message);
+        dialog.add_button("OK", ok.clone());
+        dialog.set_close_on_esc(ok);
+        dialog
+    }
+
+    /// a dialog which asks to confirm the message with an OK and a Cancel button,
+    /// pressing Esc is the same as cancelling
+    pub fn confirm(title: &str, message: &str, ok: MSG, cancel: MSG) -> Self {
+        let mut dialog = Self::new(title);
+        dialog.set_message(message);
+        dialog.add_button("OK", ok);
+        dialog.add_button("Cancel", cancel.clone());
+        dialog.set_close_on_esc(cancel);
+        dialog
+    }
+
+    /// a dialog which asks for a text with a text input initialized to the value.
+    /// The text is submitted with the OK button or by pressing Enter in the text input,
+    /// pressing Esc is the same as cancelling
+    pub fn prompt<F>(
+        title: &str,
+        message: &str,
+        value: &str,
+        submit: F,
+        cancel: MSG,
+    ) -> Self
+    where
+        F: Fn(String) -> MSG + 'static,
+    {
+        let mut dialog = Self::new(title);
+        dialog.set_message(message);
+        let width = dialog.message_width().max(30);
+        let mut input = TextInput::new(value);
+        Widget::<MSG>::set_size(&mut input, Some(width as f32), None);
+        let mut input: Box<dyn Widget<MSG>> = Box::new(input);
+        widget_id::ensure_widget_id(input.as_mut());
+        dialog.input = input.get_widget_id();
+        dialog.add_child(input);
+
+        let mut ok: Box<dyn Widget<MSG>> = Box::new(Button::<MSG>::new("OK"));
+        widget_id::ensure_widget_id(ok.as_mut());
+        dialog.submit_button = ok.get_widget_id();
+        dialog.button_row().add_child(ok);
+        dialog.on_submit = Some(Box::new(submit));
+
+        dialog.add_button("Cancel", cancel.clone());
+        dialog.set_close_on_esc(cancel);
+        dialog
+    }
+
+    /// set the message shown above the body, which can have multiple lines
+    pub fn set_message(&mut self, message: &str) {
+        self.message = message.lines().map(ToString::to_string).collect();
+    }
+
+    /// replace the body of the dialog with this widget
+    pub fn set_body(&mut self, body: Box<dyn Widget<MSG>>) {
+        let buttons = self.children.pop().expect("must have the buttons");
+        self.children = vec![];
+        self.input = None;
+        self.add_child(body);
+        self.children.push(buttons);
+    }
+
+    /// add a button at the end of the row of buttons which emits the msg when clicked
+    pub fn add_button(&mut self, label: &str, msg: MSG) {
+        let mut button = Button::new(label);
+        button.add_click_listener(Callback::from(move |_| msg.clone()));
+        self.button_row().add_child(Box::new(button));
+    }
+
+    /// emit this msg when Esc is pressed while the focus is inside of the dialog
+    pub fn set_close_on_esc(&mut self, msg: MSG) {
+        self.on_escape = Some(msg);
+    }
+
+    /// set whether a shadow is drawn on the right and below the dialog
+    pub fn set_shadow(&mut self, has_shadow: bool) {
+        self.has_shadow = has_shadow;
+    }
+
+    fn button_row(&mut self) -> &mut dyn Widget<MSG> {
+        self.children
+            .last_mut()
+            .expect("must have the buttons")
+            .as_mut()
+    }
+
+    fn message_width(&self) -> usize {
+        self.message
+            .iter()
+            .map(|line| UnicodeWidthStr::width(line.as_str()))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// the current value of the input of a prompt
+    fn input_value(&self) -> String {
+        self.input
+            .and_then(|input| {
+                self.children.iter().find_map(|child| {
+                    find_node::find_widget_by_widget_id(child.as_ref(), input)
+                })
+            })
+            .and_then(|input| input.as_any().downcast_ref::<TextInput<MSG>>())
+            .map(|input| input.get_value().to_string())
+            .unwrap_or_default()
+    }
+
+    /// whether the event submits the prompt, which is clicking the OK button
+    /// or pressing Enter in the text input
+    fn is_submit(&self, event: &WidgetEvent) -> bool {
+        let target = event.target();
+        let is_enter = match event.event {
+            Event::Key(KeyEvent { code, .. }) => code == KeyCode::Enter,
+            _ => false,
+        };
+        let is_click = match event.event {
+            Event::Mouse(MouseEvent::Down(..)) => true,
+            Event::Key(KeyEvent { code, .. }) => {
+                code == KeyCode::Enter || code == KeyCode::Char(' ')
+            }
+            _ => false,
+        };
+        (target.is_some() && target == self.submit_button && is_click)
+            || (target.is_some() && target == self.input && is_enter)
+    }
+}
+
+impl<MSG> fmt::Debug for Dialog<MSG>
+where
+    MSG: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Dialog")
+            .field("title", &self.title)
+            .field("message", &self.message)
+            .field("children", &self.children)
+            .finish()
+    }
+}
+
+impl<MSG> Widget<MSG> for Dialog<MSG>
+where
+    MSG: fmt::Debug + Clone + 'static,
+{
+    fn style(&self) -> Style {
+        let mut style = self.flex_style();
+        // the message is drawn above the children
+        style.padding = Rect {
+            top: Dimension::Points(self.message.len() as f32),
+            bottom: Dimension::Points(0.0),
+            start: Dimension::Points(1.0),
+            end: Dimension::Points(1.0),
+        };
+        style.align_items = AlignItems::Stretch;
+        // wide enough for the message and the title
+        let min_width = self
+            .message_width()
+            .max(UnicodeWidthStr::width(self.title.as_str()) + 2)
+            as f32
+            + 4.0;
+        style.min_size = Size {
+            width: Dimension::Points(min_width),
+            height: Dimension::Undefined,
+        };
+        style
+    }
+
+    fn draw(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        theme: &Theme,
+    ) -> Vec<Cmd> {
+        let layout = layout_tree.layout;
+        if layout.size.width < 1.0 || layout.size.height < 1.0 {
+            return vec![];
+        }
+        let style = theme.widget_style(self);
+        let cmds = self.draw_flex(buf, layout_tree, theme);
+        let left = layout.location.x.round() as usize;
+        let top = layout.location.y.round() as usize;
+        let width = layout.size.width.round() as usize;
+        let height = layout.size.height.round() as usize;
+        // the title and message are cut off before the right border
+        let inner_width = width.saturating_sub(4);
+        if !self.title.is_empty() {
+            style.write_str_clipped(
+                buf,
+                left + 2,
+                top,
+                format!(" {} ", self.title),
+                inner_width,
+            );
+        }
+        for (i, line) in self.message.iter().enumerate() {
+            style.write_str_clipped(
+                buf,
+                left + 2,
+                top + 1 + i,
+                line,
+                inner_width,
+            );
+        }
+        if self.has_shadow {
+            draw_shadow(buf, left, top, width, height);
+        }
+        cmds
+    }
+
+    fn layer_placement(&self) -> Option<Placement> {
+        Some(Placement::Center)
+    }
+
+    fn is_modal(&self) -> bool {
+        true
+    }
+
+    /// the Esc key and the submit of a prompt are handled
+    /// before the widgets inside of the dialog receive them
+    fn handle_event(&mut self, event: &mut WidgetEvent) -> Vec<MSG> {
+        if event.phase != Phase::Capture || event.is_default_prevented() {
+            return vec![];
+        }
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Esc, ..
+        }) = event.event
+        {
+            if let Some(msg) = self.on_escape.clone() {
+                event.stop_propagation();
+                return vec![msg];
+            }
+        }
+        if self.is_submit(event) {
+            if let Some(on_submit) = &self.on_submit {
+                event.stop_propagation();
+                return vec![on_submit(self.input_value())];
+            }
+        }
+        vec![]
+    }
+
+    /// the child is added to the body, above the buttons
+    fn add_child(&mut self, mut child: Box<dyn Widget<MSG>>) -> bool {
+        widget_id::ensure_widget_id(child.as_mut());
+        let index = self.children.len() - 1;
+        self.children.insert(index, child);
+        true
+    }
+
+    fn children(&self) -> Option<&[Box<dyn Widget<MSG>>]> {
+        Some(&self.children)
+    }
+
+    fn children_mut(&mut self) -> Option<&mut [Box<dyn Widget<MSG>>]> {
+        Some(&mut self.children)
+    }
+
+    fn child_mut(&mut self, index: usize) -> Option<&mut Box<dyn Widget<MSG>>> {
+        self.children.get_mut(index)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn set_size(&mut self, width: Option<f32>, height: Option<f32>) {
+        self.width = width;
+        self.height = height;
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.id = Some(id.to_string());
+    }
+
+    fn get_id(&self) -> &Option<String> {
+        &self.id
+    }
+
+    fn set_widget_id(&mut self, widget_id: WidgetId) {
+        self.widget_id = Some(widget_id);
+    }
+
+    fn get_widget_id(&self) -> Option<WidgetId> {
+        self.widget_id
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.classes.push(class.to_string());
+    }
+
+    fn remove_class(&mut self, class: &str) {
+        self.classes.retain(|c| c != class);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    fn get_theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    fn set_inline_style(&mut self, style: InlineStyle) {
+        self.inline_style = Some(style);
+    }
+
+    fn get_inline_style(&self) -> Option<&InlineStyle> {
+        self.inline_style.as_ref()
+    }
+
+    fn get_tag(&self) -> &'static str {
+        "dialog"
+    }
+}
+
+impl<MSG> Flex<MSG> for Dialog<MSG>
+where
+    MSG: fmt::Debug + Clone + 'static,
+{
+    fn has_border(&self) -> bool {
+        true
+    }
+
+    fn is_rounded_border(&self) -> bool {
+        false
+    }
+
+    fn is_thick_border(&self) -> bool {
+        false
+    }
+
+    fn flex_direction(&self) -> FlexDirection {
+        FlexDirection::Column
+    }
+
+    fn width(&self) -> Option<f32> {
+        self.width
+    }
+
+    fn height(&self) -> Option<f32> {
+        self.height
+    }
+
+    fn scroll_top(&self) -> f32 {
+        0.0
+    }
+}
+
+/// darken the cells along the right and the bottom edge of the box
+fn draw_shadow(
+    buf: &mut Buffer,
+    left: usize,
+    top: usize,
+    width: usize,
+    height: usize,
+) {
+    let right = left + width;
+    let bottom = top + height;
+    let edge = (top + 1..=bottom)
+        .map(|y| (right, y))
+        .chain((left + 1..right).map(|x| (x, bottom)));
+    for (x, y) in edge {
+        if let Some(cell) =
+            buf.cells.get_mut(y).and_then(|line| line.get_mut(x))
+        {
+            cell.background(Color::Black);
+            cell.attributes(vec![Attribute::Dim]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        Dispatch,
+        HeadlessBackend,
+        Renderer,
+    };
+    use std::cell::RefCell;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Msg {
+        Rename(String),
+        Cancel,
+    }
+
+    #[derive(Default)]
+    struct App {
+        msgs: RefCell<Vec<Msg>>,
+    }
+
+    impl Dispatch<Msg> for App {
+        fn dispatch(
+            &self,
+            msg: Msg,
+            _root_node: &mut dyn Widget<Msg>,
+        ) -> Vec<Cmd> {
+            self.msgs.borrow_mut().push(msg);
+            vec![]
+        }
+    }
+
+    #[test]
+    fn prompt() {
+        let mut root = FlexBox::new();
+        root.add_child(Box::new(Button::new("behind")));
+        root.add_child(Box::new(Dialog::prompt(
+            "Rename",
+            "New name:",
+            "a",
+            Msg::Rename,
+            Msg::Cancel,
+        )));
+
+        let backend = HeadlessBackend::new(
+            40,
+            12,
+            vec![
+                // the text input inside of the dialog has the focus
+                Event::Key(KeyCode::Char('b').into()),
+                Event::Key(KeyCode::Enter.into()),
+                Event::Key(KeyCode::Tab.into()),
+                Event::Key(KeyCode::Enter.into()),
+                Event::Key(KeyCode::Esc.into()),
+            ],
+        );
+        let mut out = vec![];
+        let app = App::default();
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            Some(&app),
+            &mut root,
+            Box::new(backend),
+        );
+        renderer.run().expect("must run");
+        let buf = renderer.buffer();
+        // the dialog is centered over the button behind it
+        assert_eq!(
+            buf.to_plain_text().lines().nth(1),
+            Some("│be┌─ Rename ───────────────────────┐   ")
+        );
+        let shadow = buf.get_cell(37, 5).expect("must have a cell");
+        assert_eq!(shadow.background_color, Some(Color::Black));
+        drop(renderer);
+        assert_eq!(
+            *app.msgs.borrow(),
+            vec![
+                Msg::Rename("ab".to_string()),
+                // the OK button also submits the value
+                Msg::Rename("ab".to_string()),
+                Msg::Cancel,
+            ]
+        );
+    }
+
+    #[test]
+    fn wide_characters() {
+        let dialog = Dialog::alert("注意", "日本語です", Msg::Cancel);
+        assert_eq!(dialog.message_width(), 10);
+        assert_eq!(
+            Widget::<Msg>::style(&dialog).min_size.width,
+            Dimension::Points(14.0)
+        );
+
+        let mut root = FlexBox::new();
+        root.add_child(Box::new(dialog));
+        let backend = HeadlessBackend::new(20, 8, vec![]);
+        let mut out = vec![];
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            None,
+            &mut root,
+            Box::new(backend),
+        );
+        renderer.run().expect("must run");
+        let text = renderer.buffer().to_plain_text();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[1], "   ┌─ 注意 ─────┐   ");
+        assert_eq!(lines[2], "   │ 日本語です │   ");
+    }
+}
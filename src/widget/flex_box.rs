@@ -1,17 +1,31 @@
 use crate::{
     buffer::Buffer,
+    component,
     widget::Flex,
+    widget_id::{
+        self,
+        WidgetId,
+    },
     Cmd,
+    InlineStyle,
     LayoutTree,
+    Theme,
     Widget,
 };
+use sauron_vdom::Value;
 use std::{
     any::Any,
     fmt,
 };
-use stretch::style::{
-    FlexDirection,
-    Style,
+use stretch::{
+    node::{
+        Node,
+        Stretch,
+    },
+    style::{
+        FlexDirection,
+        Style,
+    },
 };
 
 /// a flex box
@@ -23,6 +37,10 @@ pub struct FlexBox<MSG> {
     flex_direction: FlexDirection,
     scroll_top: f32,
     id: Option<String>,
+    widget_id: Option<WidgetId>,
+    classes: Vec<String>,
+    theme: Option<Theme>,
+    inline_style: Option<InlineStyle>,
     has_border: bool,
     is_rounded_border: bool,
     is_thick_border: bool,
@@ -42,6 +60,10 @@ impl<MSG> FlexBox<MSG> {
             flex_direction: FlexDirection::Row,
             scroll_top: 0.0,
             id: None,
+            widget_id: None,
+            classes: vec![],
+            theme: None,
+            inline_style: None,
             has_border: false,
             is_rounded_border: false,
             is_thick_border: false,
@@ -89,11 +111,21 @@ where
         self.flex_style()
     }
 
-    fn draw(&mut self, buf: &mut Buffer, layout_tree: &LayoutTree) -> Vec<Cmd> {
-        self.draw_flex(buf, layout_tree)
+    fn style_node(&self, stretch: &mut Stretch) -> Option<Node> {
+        self.flex_style_node(stretch)
     }
 
-    fn add_child(&mut self, child: Box<dyn Widget<MSG>>) -> bool {
+    fn draw(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        theme: &Theme,
+    ) -> Vec<Cmd> {
+        self.draw_flex(buf, layout_tree, theme)
+    }
+
+    fn add_child(&mut self, mut child: Box<dyn Widget<MSG>>) -> bool {
+        widget_id::ensure_widget_id(child.as_mut());
         self.children.push(child);
         true
     }
@@ -138,6 +170,72 @@ where
     fn get_id(&self) -> &Option<String> {
         &self.id
     }
+
+    fn set_widget_id(&mut self, widget_id: WidgetId) {
+        self.widget_id = Some(widget_id);
+    }
+
+    fn get_widget_id(&self) -> Option<WidgetId> {
+        self.widget_id
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.classes.push(class.to_string());
+    }
+
+    fn remove_class(&mut self, class: &str) {
+        self.classes.retain(|c| c != class);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    fn get_theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    fn set_inline_style(&mut self, style: InlineStyle) {
+        self.inline_style = Some(style);
+    }
+
+    fn get_inline_style(&self) -> Option<&InlineStyle> {
+        self.inline_style.as_ref()
+    }
+
+    fn get_tag(&self) -> &'static str {
+        "flexbox"
+    }
+
+    fn set_attribute(&mut self, name: &str, value: &Value) {
+        match name {
+            "horizontal" => {
+                if value.as_bool() == Some(true) {
+                    self.horizontal()
+                } else {
+                    self.vertical()
+                }
+            }
+            "scroll_top" => {
+                if let Some(scroll_top) = component::value_to_f32(value) {
+                    self.set_scroll_top(scroll_top);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn remove_attribute(&mut self, name: &str) {
+        match name {
+            "horizontal" => self.horizontal(),
+            "scroll_top" => self.set_scroll_top(0.0),
+            _ => (),
+        }
+    }
 }
 
 impl<MSG> Flex<MSG> for FlexBox<MSG>
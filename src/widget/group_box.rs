@@ -1,10 +1,18 @@
 use crate::{
     buffer::Buffer,
     widget::Flex,
+    widget_id::{
+        self,
+        WidgetId,
+    },
     Cmd,
+    InlineStyle,
     LayoutTree,
+    StateStyle,
+    Theme,
     Widget,
 };
+use sauron_vdom::Value;
 use std::{
     any::Any,
     fmt,
@@ -14,6 +22,10 @@ use stretch::{
         Rect,
         Size,
     },
+    node::{
+        Node,
+        Stretch,
+    },
     style::{
         AlignContent,
         AlignItems,
@@ -38,6 +50,10 @@ pub struct GroupBox<MSG> {
     flex_direction: FlexDirection,
     scroll_top: f32,
     id: Option<String>,
+    widget_id: Option<WidgetId>,
+    classes: Vec<String>,
+    theme: Option<Theme>,
+    inline_style: Option<InlineStyle>,
     has_border: bool,
     is_rounded_border: bool,
     is_thick_border: bool,
@@ -54,6 +70,10 @@ impl<MSG> GroupBox<MSG> {
             flex_direction: FlexDirection::Column,
             scroll_top: 0.0,
             id: None,
+            widget_id: None,
+            classes: vec![],
+            theme: None,
+            inline_style: None,
             has_border: true,
             is_rounded_border: true,
             is_thick_border: false,
@@ -81,14 +101,17 @@ impl<MSG> GroupBox<MSG> {
         self.label = Some(label.to_string());
     }
 
-    fn draw_label(&self, buf: &mut Buffer, layout_tree: &LayoutTree) {
+    fn draw_label(
+        &self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        style: &StateStyle,
+    ) {
         let layout = layout_tree.layout;
         let loc_x = layout.location.x.round() as usize;
         let loc_y = layout.location.y.round() as usize;
         if let Some(label) = &self.label {
-            for (t, ch) in label.chars().enumerate() {
-                buf.set_symbol(loc_x + 3 + t, loc_y, ch);
-            }
+            style.write_str(buf, loc_x + 3, loc_y, label);
         }
     }
 }
@@ -149,13 +172,23 @@ where
         }
     }
 
-    fn draw(&mut self, buf: &mut Buffer, layout_tree: &LayoutTree) -> Vec<Cmd> {
-        let cmds = self.draw_flex(buf, layout_tree);
-        self.draw_label(buf, layout_tree);
+    fn style_node(&self, stretch: &mut Stretch) -> Option<Node> {
+        self.flex_style_node(stretch)
+    }
+
+    fn draw(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        theme: &Theme,
+    ) -> Vec<Cmd> {
+        let cmds = self.draw_flex(buf, layout_tree, theme);
+        self.draw_label(buf, layout_tree, &theme.widget_style(self));
         cmds
     }
 
-    fn add_child(&mut self, child: Box<dyn Widget<MSG>>) -> bool {
+    fn add_child(&mut self, mut child: Box<dyn Widget<MSG>>) -> bool {
+        widget_id::ensure_widget_id(child.as_mut());
         self.children.push(child);
         true
     }
@@ -200,6 +233,68 @@ where
     fn get_id(&self) -> &Option<String> {
         &self.id
     }
+
+    fn set_widget_id(&mut self, widget_id: WidgetId) {
+        self.widget_id = Some(widget_id);
+    }
+
+    fn get_widget_id(&self) -> Option<WidgetId> {
+        self.widget_id
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.classes.push(class.to_string());
+    }
+
+    fn remove_class(&mut self, class: &str) {
+        self.classes.retain(|c| c != class);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    fn get_theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    fn set_inline_style(&mut self, style: InlineStyle) {
+        self.inline_style = Some(style);
+    }
+
+    fn get_inline_style(&self) -> Option<&InlineStyle> {
+        self.inline_style.as_ref()
+    }
+
+    fn get_tag(&self) -> &'static str {
+        "groupbox"
+    }
+
+    fn set_attribute(&mut self, name: &str, value: &Value) {
+        match name {
+            "label" => self.set_label(&value.to_string()),
+            "horizontal" => {
+                if value.as_bool() == Some(true) {
+                    self.horizontal()
+                } else {
+                    self.vertical()
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn remove_attribute(&mut self, name: &str) {
+        match name {
+            "label" => self.set_label(""),
+            "horizontal" => self.vertical(),
+            _ => (),
+        }
+    }
 }
 
 impl<MSG> Flex<MSG> for GroupBox<MSG>
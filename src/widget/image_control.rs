@@ -1,8 +1,11 @@
 use crate::{
     buffer::Buffer,
     widget::traits::ImageTrait,
+    widget_id::WidgetId,
     Cmd,
+    InlineStyle,
     LayoutTree,
+    Theme,
     Widget,
 };
 use image::{
@@ -25,6 +28,10 @@ pub struct Image<MSG> {
     /// style layout
     height: Option<f32>,
     id: Option<String>,
+    widget_id: Option<WidgetId>,
+    classes: Vec<String>,
+    theme: Option<Theme>,
+    inline_style: Option<InlineStyle>,
     _phantom_msg: PhantomData<MSG>,
 }
 
@@ -37,6 +44,10 @@ impl<MSG> Image<MSG> {
             width: None,
             height: None,
             id: None,
+            widget_id: None,
+            classes: vec![],
+            theme: None,
+            inline_style: None,
             _phantom_msg: PhantomData,
         };
         image
@@ -66,7 +77,12 @@ where
     }
 
     /// draw this button to the buffer, with the given computed layout
-    fn draw(&mut self, buf: &mut Buffer, layout_tree: &LayoutTree) -> Vec<Cmd> {
+    fn draw(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        _theme: &Theme,
+    ) -> Vec<Cmd> {
         self.draw_image(buf, layout_tree)
     }
 
@@ -90,6 +106,46 @@ where
     fn get_id(&self) -> &Option<String> {
         &self.id
     }
+
+    fn set_widget_id(&mut self, widget_id: WidgetId) {
+        self.widget_id = Some(widget_id);
+    }
+
+    fn get_widget_id(&self) -> Option<WidgetId> {
+        self.widget_id
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.classes.push(class.to_string());
+    }
+
+    fn remove_class(&mut self, class: &str) {
+        self.classes.retain(|c| c != class);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    fn get_theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    fn set_inline_style(&mut self, style: InlineStyle) {
+        self.inline_style = Some(style);
+    }
+
+    fn get_inline_style(&self) -> Option<&InlineStyle> {
+        self.inline_style.as_ref()
+    }
+
+    fn get_tag(&self) -> &'static str {
+        "image"
+    }
 }
 
 impl<MSG> fmt::Debug for Image<MSG> {
@@ -0,0 +1,398 @@
+use crate::{
+    buffer::Buffer,
+    widget::Flex,
+    widget_id::{
+        self,
+        WidgetId,
+    },
+    Cmd,
+    InlineStyle,
+    LayoutTree,
+    Theme,
+    Widget,
+};
+use sauron_vdom::Value;
+use std::{
+    any::Any,
+    fmt,
+};
+use stretch::style::{
+    FlexDirection,
+    Style,
+};
+
+/// Where an overlay layer is placed on the screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Placement {
+    /// centered on the screen
+    Center,
+    /// below the widget with this widget id, aligned to its left edge.
+    /// It is placed above the widget instead when there is not enough room below it.
+    Below(WidgetId),
+    /// above the widget with this widget id, aligned to its left edge.
+    /// It is placed below the widget instead when there is not enough room above it.
+    Above(WidgetId),
+    /// at this location on the screen
+    At(f32, f32),
+}
+
+/// An overlay layer which floats above the rest of the widgets, such as popups, menus and dialogs.
+///
+/// The layer takes no space where it is added in the widget tree,
+/// its children are laid out apart from the tree and drawn after it by the renderer.
+/// The mouse events are hit tested to the layers first, the last added layer is on top.
+/// A modal layer blocks the events to the widgets below it and keeps the focus inside of it.
+///
+/// The events to the children of the layer still bubble up through the ancestors of the layer.
+/// Remove the layer from the tree to close it.
+#[derive(Debug)]
+pub struct Layer<MSG> {
+    children: Vec<Box<dyn Widget<MSG>>>,
+    placement: Placement,
+    is_modal: bool,
+    width: Option<f32>,
+    height: Option<f32>,
+    flex_direction: FlexDirection,
+    id: Option<String>,
+    widget_id: Option<WidgetId>,
+    classes: Vec<String>,
+    theme: Option<Theme>,
+    inline_style: Option<InlineStyle>,
+    has_border: bool,
+    is_rounded_border: bool,
+    is_thick_border: bool,
+}
+
+impl<MSG> Layer<MSG> {
+    /// create a new layer placed at this placement
+    pub fn new(placement: Placement) -> Self {
+        Layer {
+            children: vec![],
+            placement,
+            is_modal: false,
+            width: None,
+            height: None,
+            flex_direction: FlexDirection::Column,
+            id: None,
+            widget_id: None,
+            classes: vec![],
+            theme: None,
+            inline_style: None,
+            has_border: false,
+            is_rounded_border: false,
+            is_thick_border: false,
+        }
+    }
+
+    /// set where this layer is placed on the screen
+    pub fn set_placement(&mut self, placement: Placement) {
+        self.placement = placement;
+    }
+
+    /// set whether this layer blocks the events to the widgets below it
+    pub fn set_modal(&mut self, modal: bool) {
+        self.is_modal = modal;
+    }
+
+    /// set whether a border is drawn around the children
+    pub fn set_border(&mut self, has_border: bool) {
+        self.has_border = has_border;
+    }
+
+    /// set whether the corners of the border are rounded
+    pub fn set_rounded(&mut self, rounded: bool) {
+        self.is_rounded_border = rounded;
+    }
+
+    /// set whether the border is drawn with thick lines
+    pub fn set_thick_border(&mut self, thick: bool) {
+        self.is_thick_border = thick;
+    }
+
+    /// set to vertical column direction
+    pub fn vertical(&mut self) {
+        self.flex_direction = FlexDirection::Column;
+    }
+
+    /// set to horizontal row direction
+    pub fn horizontal(&mut self) {
+        self.flex_direction = FlexDirection::Row;
+    }
+}
+
+impl<MSG> Widget<MSG> for Layer<MSG>
+where
+    MSG: fmt::Debug + 'static,
+{
+    /// the style of the layer when it is laid out apart from the widget tree
+    fn style(&self) -> Style {
+        self.flex_style()
+    }
+
+    /// nothing is drawn in place of the layer in the widget tree since it has no size there,
+    /// when drawn by the renderer the children covers whatever is below the layer
+    fn draw(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        theme: &Theme,
+    ) -> Vec<Cmd> {
+        let size = layout_tree.layout.size;
+        if size.width < 1.0 || size.height < 1.0 {
+            return vec![];
+        }
+        self.draw_flex(buf, layout_tree, theme)
+    }
+
+    fn layer_placement(&self) -> Option<Placement> {
+        Some(self.placement)
+    }
+
+    fn is_modal(&self) -> bool {
+        self.is_modal
+    }
+
+    fn add_child(&mut self, mut child: Box<dyn Widget<MSG>>) -> bool {
+        widget_id::ensure_widget_id(child.as_mut());
+        self.children.push(child);
+        true
+    }
+
+    fn children(&self) -> Option<&[Box<dyn Widget<MSG>>]> {
+        Some(&self.children)
+    }
+
+    fn children_mut(&mut self) -> Option<&mut [Box<dyn Widget<MSG>>]> {
+        Some(&mut self.children)
+    }
+
+    fn take_child(&mut self, index: usize) -> Option<Box<dyn Widget<MSG>>> {
+        Some(self.children.remove(index))
+    }
+
+    fn child_mut(&mut self, index: usize) -> Option<&mut Box<dyn Widget<MSG>>> {
+        self.children.get_mut(index)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn set_size(&mut self, width: Option<f32>, height: Option<f32>) {
+        self.width = width;
+        self.height = height;
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.id = Some(id.to_string());
+    }
+
+    fn get_id(&self) -> &Option<String> {
+        &self.id
+    }
+
+    fn set_widget_id(&mut self, widget_id: WidgetId) {
+        self.widget_id = Some(widget_id);
+    }
+
+    fn get_widget_id(&self) -> Option<WidgetId> {
+        self.widget_id
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.classes.push(class.to_string());
+    }
+
+    fn remove_class(&mut self, class: &str) {
+        self.classes.retain(|c| c != class);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    fn get_theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    fn set_inline_style(&mut self, style: InlineStyle) {
+        self.inline_style = Some(style);
+    }
+
+    fn get_inline_style(&self) -> Option<&InlineStyle> {
+        self.inline_style.as_ref()
+    }
+
+    fn get_tag(&self) -> &'static str {
+        "layer"
+    }
+
+    fn set_attribute(&mut self, name: &str, value: &Value) {
+        match name {
+            "modal" => self.set_modal(value.as_bool() == Some(true)),
+            "border" => self.set_border(value.as_bool() == Some(true)),
+            _ => (),
+        }
+    }
+
+    fn remove_attribute(&mut self, name: &str) {
+        match name {
+            "modal" => self.set_modal(false),
+            "border" => self.set_border(false),
+            _ => (),
+        }
+    }
+}
+
+impl<MSG> Flex<MSG> for Layer<MSG>
+where
+    MSG: fmt::Debug + 'static,
+{
+    fn has_border(&self) -> bool {
+        self.has_border
+    }
+
+    fn is_rounded_border(&self) -> bool {
+        self.is_rounded_border
+    }
+
+    fn is_thick_border(&self) -> bool {
+        self.is_thick_border
+    }
+
+    fn flex_direction(&self) -> FlexDirection {
+        self.flex_direction
+    }
+
+    fn width(&self) -> Option<f32> {
+        self.width
+    }
+
+    fn height(&self) -> Option<f32> {
+        self.height
+    }
+
+    fn scroll_top(&self) -> f32 {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        Button,
+        Dispatch,
+        FlexBox,
+        HeadlessBackend,
+        Renderer,
+    };
+    use crossterm::event::{
+        Event,
+        KeyCode,
+        KeyModifiers,
+        MouseButton,
+        MouseEvent,
+    };
+    use sauron_vdom::Callback;
+    use std::cell::RefCell;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Msg {
+        Behind,
+        Ok,
+    }
+
+    #[derive(Default)]
+    struct App {
+        msgs: RefCell<Vec<Msg>>,
+    }
+
+    impl Dispatch<Msg> for App {
+        fn dispatch(
+            &self,
+            msg: Msg,
+            _root_node: &mut dyn Widget<Msg>,
+        ) -> Vec<Cmd> {
+            self.msgs.borrow_mut().push(msg);
+            vec![]
+        }
+    }
+
+    fn button(label: &str, msg: Msg) -> Button<Msg> {
+        let mut button = Button::new(label);
+        button.set_size(Some(6.0), Some(3.0));
+        button.add_click_listener(Callback::from(move |_| msg.clone()));
+        button
+    }
+
+    #[test]
+    fn modal_layer_on_top() {
+        let mut root = FlexBox::new();
+        root.vertical();
+        root.add_child(Box::new(button("file", Msg::Behind)));
+        let file = root.children().unwrap()[0].get_widget_id().unwrap();
+
+        let mut menu = Layer::new(Placement::Below(file));
+        menu.add_child(Box::new(button("open", Msg::Behind)));
+        root.add_child(Box::new(menu));
+
+        let mut dialog = Layer::new(Placement::Center);
+        dialog.set_modal(true);
+        dialog.add_child(Box::new(button("ok", Msg::Ok)));
+        root.add_child(Box::new(dialog));
+
+        let click = |x, y| {
+            Event::Mouse(MouseEvent::Down(
+                MouseButton::Left,
+                x,
+                y,
+                KeyModifiers::empty(),
+            ))
+        };
+        let backend = HeadlessBackend::new(
+            20,
+            10,
+            vec![
+                // the widgets below the modal layer can not be clicked
+                click(1, 1),
+                click(1, 4),
+                Event::Key(KeyCode::Tab.into()),
+                Event::Key(KeyCode::Enter.into()),
+            ],
+        );
+        let mut out = vec![];
+        let app = App::default();
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            Some(&app),
+            &mut root,
+            Box::new(backend),
+        );
+        renderer.run().expect("must run");
+        let lines: Vec<String> = renderer
+            .buffer()
+            .to_plain_text()
+            .lines()
+            .map(ToString::to_string)
+            .collect();
+        // the menu is below the file button, the dialog is centered over it
+        assert_eq!(lines[3], "╭────╮ ┏━━━━┓       ");
+        assert_eq!(lines[4], "│open│ ┃ok  ┃       ");
+        drop(renderer);
+        let ok = root.children().unwrap()[2].children().unwrap()[0]
+            .get_widget_id()
+            .unwrap();
+        assert_eq!(*app.msgs.borrow(), vec![Msg::Ok]);
+        // the focus is kept inside of the modal layer
+        assert_eq!(crate::focus::focused_widget(&root), Some(ok));
+    }
+}
@@ -1,56 +1,124 @@
 use crate::{
     buffer::Buffer,
+    component,
+    symbol::bar,
+    widget_event::{
+        Phase,
+        WidgetEvent,
+    },
+    widget_id::WidgetId,
     Cmd,
+    InlineStyle,
     LayoutTree,
+    StateStyle,
+    Theme,
     Widget,
 };
-use ito_canvas::unicode_canvas::{
-    Border,
-    Canvas,
+use crossterm::{
+    event::{
+        Event,
+        KeyCode,
+        KeyEvent,
+        KeyModifiers,
+        MouseButton,
+        MouseEvent,
+    },
+    style::Attribute,
+};
+use ito_canvas::unicode_canvas::Canvas;
+use sauron_vdom::{
+    Callback,
+    Value,
 };
-use sauron_vdom::Callback;
 use std::{
     any::Any,
     fmt,
 };
 use stretch::{
     geometry::Size,
+    result::Layout,
     style::{
         Dimension,
-        FlexDirection,
         Style,
     },
 };
 
-/// a flex box
+/// A scrollable list of items where one or more items can be selected.
+///
+/// The Up/Down, PageUp/PageDown and Home/End keys move the cursor and select the item under it.
+/// In a multi select list, Shift extends the selection from the last selected item,
+/// Ctrl moves the cursor without changing the selection and Space toggles the item
+/// under the cursor. The same modifiers apply when clicking on the items.
+/// Pressing Enter activates the selected items.
+///
+/// ```ignore
+///  ┌───────────────────┐
+///  │ Item11            │
+///  │────────────────── │
+///  │█Item12 ██████████ │
+///  │────────────────── │
+///  │ Item13            ▇
+///  └───────────────────┘
+/// ```
 #[derive(Default, Debug)]
 pub struct ListBox<MSG> {
     list: Vec<String>,
+    /// the indices of the selected items, in ascending order
+    selected: Vec<usize>,
+    /// the item which the keys move from
+    cursor: Option<usize>,
+    /// the item where the range selected with Shift starts
+    anchor: Option<usize>,
+    is_multi_select: bool,
+    focused: bool,
     width: Option<f32>,
     height: Option<f32>,
-    flex_direction: FlexDirection,
-    scroll_top: f32,
+    /// the index of the first visible item
+    scroll_top: usize,
+    layout: Option<Layout>,
     on_input: Vec<Callback<sauron_vdom::Event, MSG>>,
+    on_change: Vec<Callback<Vec<usize>, MSG>>,
+    on_activate: Vec<Callback<Vec<usize>, MSG>>,
     id: Option<String>,
+    widget_id: Option<WidgetId>,
+    classes: Vec<String>,
+    theme: Option<Theme>,
+    inline_style: Option<InlineStyle>,
     use_divider: bool,
 }
 
-impl<MSG> ListBox<MSG> {
-    ///create a new flexbox
+impl<MSG: 'static> ListBox<MSG> {
+    /// create a new list box with no items
     pub fn new() -> Self {
         ListBox {
             width: None,
             height: None,
-            flex_direction: FlexDirection::Row,
-            scroll_top: 0.0,
+            scroll_top: 0,
             on_input: vec![],
+            on_change: vec![],
+            on_activate: vec![],
             list: vec![],
+            selected: vec![],
+            cursor: None,
+            anchor: None,
+            is_multi_select: false,
+            focused: false,
+            layout: None,
             id: None,
+            widget_id: None,
+            classes: vec![],
+            theme: None,
+            inline_style: None,
             use_divider: true,
         }
     }
 
-    fn draw_border(&mut self, buf: &mut Buffer, layout_tree: &LayoutTree) {
+    fn draw_border(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        style: &StateStyle,
+    ) {
         let layout = layout_tree.layout;
         let loc_x = layout.location.x.round() as usize;
         let loc_y = layout.location.y.round() as usize;
@@ -62,45 +130,329 @@ impl<MSG> ListBox<MSG> {
         let bottom = top + height - 1;
         let right = left + width - 1;
 
-        let border = Border {
-            use_thick_border: false,
-            has_top: true,
-            has_bottom: true,
-            has_left: true,
-            has_right: true,
-            is_top_left_rounded: false,
-            is_top_right_rounded: false,
-            is_bottom_left_rounded: false,
-            is_bottom_right_rounded: false,
-        };
-        let mut canvas = Canvas::new();
-        canvas.draw_rect((left, top), (right, bottom), border);
-        buf.write_canvas(canvas);
+        style.fill(buf, (left, top), (right, bottom));
+        style.draw_border(buf, (left, top), (right, bottom), false);
     }
 
-    /// set the list of this listbox;
+    /// set the list of this listbox, the selected items which are no longer in the list
+    /// are unselected
     pub fn set_list(&mut self, list: Vec<String>) {
         self.list = list;
+        let len = self.list.len();
+        self.selected.retain(|index| *index < len);
+        self.cursor = self.cursor.filter(|index| *index < len);
+        self.anchor = self.anchor.filter(|index| *index < len);
+        self.scroll_by(0);
+    }
+
+    /// returns the items of this listbox
+    pub fn get_list(&self) -> &[String] {
+        &self.list
+    }
+
+    /// set whether more than one item can be selected
+    pub fn set_multi_select(&mut self, multi_select: bool) {
+        self.is_multi_select = multi_select;
+        if !multi_select {
+            self.selected.truncate(1);
+        }
+    }
+
+    /// set whether a line is drawn below each of the items
+    pub fn set_use_divider(&mut self, use_divider: bool) {
+        self.use_divider = use_divider;
+    }
+
+    /// select the items at these indices, only the first one is selected
+    /// if this is not a multi select list
+    pub fn set_selected(&mut self, selected: Vec<usize>) {
+        let len = self.list.len();
+        let mut selected: Vec<usize> =
+            selected.into_iter().filter(|index| *index < len).collect();
+        selected.sort();
+        selected.dedup();
+        if !self.is_multi_select {
+            selected.truncate(1);
+        }
+        self.cursor = selected.first().copied();
+        self.anchor = self.cursor;
+        self.selected = selected;
+        self.scroll_into_view();
+    }
+
+    /// returns the indices of the selected items in ascending order
+    pub fn get_selected(&self) -> &[usize] {
+        &self.selected
+    }
+
+    /// attach a listener which receives an input event with the indices of the
+    /// selected items, when the selection is changed by the user
+    pub fn add_input_listener(
+        &mut self,
+        cb: Callback<sauron_vdom::Event, MSG>,
+    ) {
+        self.on_input.push(cb);
+    }
+
+    /// attach a listener which receives the indices of the selected items,
+    /// when the selection is changed by the user
+    pub fn add_change_listener(&mut self, cb: Callback<Vec<usize>, MSG>) {
+        self.on_change.push(cb);
     }
 
-    fn draw_items(&self, buf: &mut Buffer, layout_tree: &LayoutTree) {
+    /// attach a listener which receives the indices of the selected items,
+    /// when they are activated with Enter
+    pub fn add_activate_listener(&mut self, cb: Callback<Vec<usize>, MSG>) {
+        self.on_activate.push(cb);
+    }
+
+    /// the number of lines each item takes
+    fn item_height(&self) -> usize {
+        if self.use_divider {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// the number of lines inside of the border
+    fn inner_height(&self) -> usize {
+        match self.layout {
+            Some(layout) => {
+                (layout.size.height.round() as usize).saturating_sub(2)
+            }
+            None => self.list.len() * self.item_height(),
+        }
+    }
+
+    /// the number of items which fits inside of the border,
+    /// the divider of the last visible item may be cut off
+    fn visible_items(&self) -> usize {
+        (self.inner_height() as f32 / self.item_height() as f32).ceil() as usize
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        let max = self.list.len().saturating_sub(self.visible_items()) as isize;
+        self.scroll_top =
+            (self.scroll_top as isize + delta).min(max).max(0) as usize;
+    }
+
+    /// scroll the list so the item under the cursor is visible
+    fn scroll_into_view(&mut self) {
+        if let Some(cursor) = self.cursor {
+            let visible = self.visible_items().max(1);
+            if cursor < self.scroll_top {
+                self.scroll_top = cursor;
+            } else if cursor >= self.scroll_top + visible {
+                self.scroll_top = cursor + 1 - visible;
+            }
+        }
+        self.scroll_by(0);
+    }
+
+    /// move the cursor to the item at this index and update the selection
+    /// according to the modifiers, returns the msgs if the selection is changed
+    fn move_to(&mut self, index: usize, modifiers: KeyModifiers) -> Vec<MSG> {
+        if self.list.is_empty() {
+            return vec![];
+        }
+        let index = index.min(self.list.len() - 1);
+        self.cursor = Some(index);
+        self.scroll_into_view();
+        if !self.is_multi_select {
+            return self.select(vec![index]);
+        }
+        if modifiers.contains(KeyModifiers::CONTROL) {
+            vec![]
+        } else if modifiers.contains(KeyModifiers::SHIFT) {
+            let anchor = self.anchor.unwrap_or(index);
+            let range = if anchor <= index {
+                anchor..=index
+            } else {
+                index..=anchor
+            };
+            self.select(range.collect())
+        } else {
+            self.anchor = Some(index);
+            self.select(vec![index])
+        }
+    }
+
+    fn move_by(&mut self, delta: isize, modifiers: KeyModifiers) -> Vec<MSG> {
+        let index = match self.cursor {
+            Some(cursor) => (cursor as isize + delta).max(0) as usize,
+            None => 0,
+        };
+        self.move_to(index, modifiers)
+    }
+
+    /// add or remove the item under the cursor from the selection
+    fn toggle(&mut self) -> Vec<MSG> {
+        let cursor = match self.cursor {
+            Some(cursor) => cursor,
+            None => return vec![],
+        };
+        self.anchor = Some(cursor);
+        let mut selected = self.selected.clone();
+        match selected.iter().position(|index| *index == cursor) {
+            Some(pos) => {
+                selected.remove(pos);
+            }
+            None => {
+                selected.push(cursor);
+                selected.sort();
+            }
+        }
+        self.select(selected)
+    }
+
+    /// set the selection and emit the change if it is different
+    fn select(&mut self, selected: Vec<usize>) -> Vec<MSG> {
+        if self.selected == selected {
+            return vec![];
+        }
+        self.selected = selected;
+        let value = Value::Vec(
+            self.selected
+                .iter()
+                .map(|index| Value::Usize(*index))
+                .collect(),
+        );
+        let s_event: sauron_vdom::Event =
+            sauron_vdom::event::InputEvent::new(value).into();
+        let mut msgs: Vec<MSG> = self
+            .on_input
+            .iter()
+            .map(|cb| cb.emit(s_event.clone()))
+            .collect();
+        msgs.extend(
+            self.on_change
+                .iter()
+                .map(|cb| cb.emit(self.selected.clone())),
+        );
+        msgs
+    }
+
+    fn activate(&self) -> Vec<MSG> {
+        if self.selected.is_empty() {
+            return vec![];
+        }
+        self.on_activate
+            .iter()
+            .map(|cb| cb.emit(self.selected.clone()))
+            .collect()
+    }
+
+    fn process_key(&mut self, key_event: KeyEvent) -> (Vec<MSG>, bool) {
+        let KeyEvent { code, modifiers } = key_event;
+        let page = self.visible_items().max(1) as isize;
+        let msgs = match code {
+            KeyCode::Up => self.move_by(-1, modifiers),
+            KeyCode::Down => self.move_by(1, modifiers),
+            KeyCode::PageUp => self.move_by(-page, modifiers),
+            KeyCode::PageDown => self.move_by(page, modifiers),
+            KeyCode::Home => self.move_to(0, modifiers),
+            KeyCode::End => {
+                self.move_to(self.list.len().saturating_sub(1), modifiers)
+            }
+            KeyCode::Char(' ') if self.is_multi_select => self.toggle(),
+            KeyCode::Char(' ') => {
+                self.move_to(self.cursor.unwrap_or(0), modifiers)
+            }
+            KeyCode::Enter => self.activate(),
+            _ => return (vec![], false),
+        };
+        (msgs, true)
+    }
+
+    /// select the item at this row, counted from the top of this widget
+    fn click(&mut self, y: f32, modifiers: KeyModifiers) -> (Vec<MSG>, bool) {
+        match self.item_at(y) {
+            Some(index) => {
+                let msgs = if self.is_multi_select
+                    && modifiers.contains(KeyModifiers::CONTROL)
+                {
+                    self.cursor = Some(index);
+                    self.toggle()
+                } else {
+                    self.move_to(index, modifiers)
+                };
+                (msgs, true)
+            }
+            None => (vec![], false),
+        }
+    }
+
+    /// the index of the item at this row, counted from the top of this widget
+    fn item_at(&self, y: f32) -> Option<usize> {
+        let row = y - 1.0;
+        if row < 0.0 || row as usize >= self.inner_height() {
+            return None;
+        }
+        let index = self.scroll_top + row as usize / self.item_height();
+        if index < self.list.len() {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    fn draw_items(
+        &self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        style: &StateStyle,
+        theme: &Theme,
+    ) {
         let layout = layout_tree.layout;
         let loc_x = layout.location.x.round() as usize;
         let loc_y = layout.location.y.round() as usize;
         let width = layout.size.width.round() as usize;
-        for (j, li) in self.list.iter().enumerate() {
-            if self.use_divider {
+        let inner_height = self.inner_height();
+        let item_height = self.item_height();
+        let left = loc_x + 1;
+        let right = loc_x + width - 2;
+
+        let selected_style =
+            style.merge(&theme.accent_style()).merge(&StateStyle {
+                attributes: vec![Attribute::Reverse],
+                ..Default::default()
+            });
+        let cursor_style = style.merge(&StateStyle {
+            attributes: vec![Attribute::Underlined],
+            ..Default::default()
+        });
+        for j in 0..self.visible_items() {
+            let index = self.scroll_top + j;
+            let li = match self.list.get(index) {
+                Some(li) => li,
+                None => break,
+            };
+            let y = loc_y + 1 + j * item_height;
+            let item_style = if self.selected.contains(&index) {
+                &selected_style
+            } else if self.focused && self.cursor == Some(index) {
+                &cursor_style
+            } else {
+                style
+            };
+            // the highlight covers the whole row, up to the divider
+            item_style.write_str(buf, left, y, " ".repeat(right - left));
+            item_style.write_str_clipped(
+                buf,
+                left + 1,
+                y,
+                li,
+                (right - left).saturating_sub(1),
+            );
+            if self.use_divider && (j * item_height + 1) < inner_height {
                 let mut canvas = Canvas::new();
-                buf.write_str(loc_x + 2, loc_y + 1 + (j * 2), li);
-                let left = loc_x + 1;
-                let right = loc_x + width - 2;
-                let bottom = loc_y + 1 + (j * 2) + 1;
                 canvas.draw_horizontal_line(
-                    (left, bottom),
-                    (right, bottom),
+                    (left, y + 1),
+                    (right - 1, y + 1),
                     false,
                 );
-                buf.write_canvas(canvas);
+                style.write_canvas(buf, canvas);
             }
         }
     }
@@ -140,12 +492,45 @@ where
         }
     }
 
-    fn draw(&mut self, buf: &mut Buffer, layout_tree: &LayoutTree) -> Vec<Cmd> {
-        self.draw_border(buf, layout_tree);
-        self.draw_items(buf, layout_tree);
+    fn draw(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        theme: &Theme,
+    ) -> Vec<Cmd> {
+        let layout = layout_tree.layout;
+        if layout.size.width < 4.0 || layout.size.height < 2.0 {
+            return vec![];
+        }
+        self.layout = Some(layout);
+        // the list may have been resized
+        self.scroll_by(0);
+        let style = theme.widget_style(self);
+        self.draw_border(buf, layout_tree, &style);
+        self.draw_items(buf, layout_tree, &style, theme);
+        draw_scrollbar(
+            buf,
+            &style,
+            &layout,
+            self.scroll_top,
+            self.visible_items(),
+            self.list.len(),
+        );
         vec![]
     }
 
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -159,6 +544,62 @@ where
         self.height = height;
     }
 
+    fn process_event(&mut self, event: Event) -> Vec<MSG> {
+        self.consume_event(event).0
+    }
+
+    fn consume_event(&mut self, event: Event) -> (Vec<MSG>, bool) {
+        match event {
+            Event::Key(key_event) => self.process_key(key_event),
+            // without the origin of a propagated event,
+            // the mouse location is taken as relative to the parent
+            Event::Mouse(MouseEvent::Down(
+                MouseButton::Left,
+                _x,
+                y,
+                modifiers,
+            )) => {
+                match self.layout {
+                    Some(layout) => {
+                        self.click(
+                            y as f32 - layout.location.y.round(),
+                            modifiers,
+                        )
+                    }
+                    None => (vec![], false),
+                }
+            }
+            Event::Mouse(MouseEvent::ScrollDown(..)) => {
+                self.scroll_by(1);
+                (vec![], true)
+            }
+            Event::Mouse(MouseEvent::ScrollUp(..)) => {
+                self.scroll_by(-1);
+                (vec![], true)
+            }
+            _ => (vec![], false),
+        }
+    }
+
+    fn handle_event(&mut self, event: &mut WidgetEvent) -> Vec<MSG> {
+        if event.phase == Phase::Capture || event.is_default_prevented() {
+            return vec![];
+        }
+        let (msgs, consumed) = match event.event {
+            Event::Mouse(MouseEvent::Down(
+                MouseButton::Left,
+                _x,
+                y,
+                modifiers,
+            )) => self.click(y as f32 - event.origin.1.round(), modifiers),
+            _ => self.consume_event(event.event),
+        };
+        if consumed {
+            event.stop_propagation();
+        }
+        msgs
+    }
+
     fn set_id(&mut self, id: &str) {
         self.id = Some(id.to_string());
     }
@@ -166,4 +607,295 @@ where
     fn get_id(&self) -> &Option<String> {
         &self.id
     }
+
+    fn set_widget_id(&mut self, widget_id: WidgetId) {
+        self.widget_id = Some(widget_id);
+    }
+
+    fn get_widget_id(&self) -> Option<WidgetId> {
+        self.widget_id
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.classes.push(class.to_string());
+    }
+
+    fn remove_class(&mut self, class: &str) {
+        self.classes.retain(|c| c != class);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    fn get_theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    fn set_inline_style(&mut self, style: InlineStyle) {
+        self.inline_style = Some(style);
+    }
+
+    fn get_inline_style(&self) -> Option<&InlineStyle> {
+        self.inline_style.as_ref()
+    }
+
+    fn get_tag(&self) -> &'static str {
+        "listbox"
+    }
+
+    fn set_attribute(&mut self, name: &str, value: &Value) {
+        match name {
+            "labels" => self.set_list(component::value_to_strings(value)),
+            "selected" => {
+                let selected = match value {
+                    Value::Vec(values) => values.iter().collect(),
+                    value => vec![value],
+                };
+                self.set_selected(
+                    selected
+                        .into_iter()
+                        .filter_map(|value| value.as_f64())
+                        .map(|index| index as usize)
+                        .collect(),
+                );
+            }
+            "multiple" => self.set_multi_select(value.as_bool() == Some(true)),
+            "divider" => self.set_use_divider(value.as_bool() != Some(false)),
+            _ => (),
+        }
+    }
+
+    fn remove_attribute(&mut self, name: &str) {
+        match name {
+            "labels" => self.set_list(vec![]),
+            "selected" => self.set_selected(vec![]),
+            "multiple" => self.set_multi_select(false),
+            "divider" => self.set_use_divider(true),
+            _ => (),
+        }
+    }
+
+    fn set_event_listeners(
+        &mut self,
+        event: &str,
+        listeners: Vec<Callback<sauron_vdom::Event, MSG>>,
+    ) {
+        if event == "input" {
+            self.on_input = listeners;
+        }
+    }
+}
+
+/// draw the thumb of the scrollbar on the right border of the layout,
+/// its size is relative to the part of the content that is visible
+pub(super) fn draw_scrollbar(
+    buf: &mut Buffer,
+    style: &StateStyle,
+    layout: &Layout,
+    scroll_top: usize,
+    visible: usize,
+    total: usize,
+) {
+    let track = (layout.size.height.round() as usize).saturating_sub(2);
+    if total <= visible || track == 0 {
+        return;
+    }
+    let right = (layout.location.x + layout.size.width).round() as usize - 1;
+    let top = layout.location.y.round() as usize + 1;
+    let thumb = (track * visible / total).max(1);
+    let max_scroll = total - visible;
+    let offset = (scroll_top * (track - thumb) + max_scroll / 2) / max_scroll;
+    for y in 0..thumb {
+        style.write_str(buf, right, top + offset + y, bar::SEVEN_EIGHTHS);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        Dispatch,
+        FlexBox,
+        HeadlessBackend,
+        Renderer,
+    };
+    use std::cell::RefCell;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Msg {
+        Changed(Vec<usize>),
+        Activated(Vec<usize>),
+    }
+
+    #[derive(Default)]
+    struct App {
+        msgs: RefCell<Vec<Msg>>,
+    }
+
+    impl Dispatch<Msg> for App {
+        fn dispatch(
+            &self,
+            msg: Msg,
+            _root_node: &mut dyn Widget<Msg>,
+        ) -> Vec<Cmd> {
+            self.msgs.borrow_mut().push(msg);
+            vec![]
+        }
+    }
+
+    fn items(multi_select: bool) -> ListBox<Msg> {
+        let mut list_box = ListBox::new();
+        list_box.set_list((1..=6).map(|i| format!("Item{}", i)).collect());
+        list_box.set_multi_select(multi_select);
+        list_box.set_size(Some(12.0), Some(7.0));
+        list_box.add_change_listener(Callback::from(Msg::Changed));
+        list_box.add_activate_listener(Callback::from(Msg::Activated));
+        list_box
+    }
+
+    fn run(
+        list_box: ListBox<Msg>,
+        events: Vec<Event>,
+    ) -> (Vec<String>, Vec<Msg>) {
+        let mut root = FlexBox::new();
+        root.add_child(Box::new(list_box));
+        let mut events = events;
+        events.insert(0, Event::Key(KeyCode::Tab.into()));
+        let backend = HeadlessBackend::new(14, 7, events);
+        let mut out = vec![];
+        let app = App::default();
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            Some(&app),
+            &mut root,
+            Box::new(backend),
+        );
+        renderer.run().expect("must run");
+        let lines = renderer
+            .buffer()
+            .to_plain_text()
+            .lines()
+            .map(ToString::to_string)
+            .collect();
+        drop(renderer);
+        let msgs = app.msgs.borrow().clone();
+        (lines, msgs)
+    }
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(code.into())
+    }
+
+    #[test]
+    fn keyboard_selection_scrolls() {
+        let (lines, msgs) = run(
+            items(false),
+            vec![
+                key(KeyCode::Down),
+                key(KeyCode::End),
+                key(KeyCode::Up),
+                key(KeyCode::Enter),
+            ],
+        );
+        assert_eq!(
+            msgs,
+            vec![
+                Msg::Changed(vec![0]),
+                Msg::Changed(vec![5]),
+                Msg::Changed(vec![4]),
+                Msg::Activated(vec![4]),
+            ]
+        );
+        // 3 items fit, the list is scrolled to the end
+        assert_eq!(lines[1], "┃ Item4    ┃  ");
+        assert_eq!(lines[2], "┃╶───────╴ ┃  ");
+        assert_eq!(lines[3], "┃ Item5    ┃  ");
+        // the thumb of the scrollbar is at the bottom
+        assert_eq!(lines[4], "┃╶───────╴ ▇  ");
+        assert_eq!(lines[5], "┃ Item6    ▇  ");
+    }
+
+    #[test]
+    fn multi_select_with_mouse() {
+        let click = |y, modifiers| {
+            Event::Mouse(MouseEvent::Down(MouseButton::Left, 3, y, modifiers))
+        };
+        let (_lines, msgs) = run(
+            items(true),
+            vec![
+                click(1, KeyModifiers::empty()),
+                click(5, KeyModifiers::SHIFT),
+                // the divider belongs to the item above it
+                click(4, KeyModifiers::CONTROL),
+                Event::Mouse(MouseEvent::ScrollDown(
+                    3,
+                    3,
+                    KeyModifiers::empty(),
+                )),
+                // move the cursor to the last selected item and unselect it
+                Event::Key(KeyEvent::new(KeyCode::Down, KeyModifiers::CONTROL)),
+                key(KeyCode::Char(' ')),
+            ],
+        );
+        assert_eq!(
+            msgs,
+            vec![
+                Msg::Changed(vec![0]),
+                Msg::Changed(vec![0, 1, 2]),
+                Msg::Changed(vec![0, 2]),
+                Msg::Changed(vec![0]),
+            ]
+        );
+    }
+
+    #[test]
+    fn click_in_a_nested_list() {
+        let mut inner = FlexBox::new();
+        inner.add_child(Box::new(items(false)));
+        let mut root = FlexBox::new();
+        root.vertical();
+        root.add_child(Box::new(crate::Button::new("above")));
+        root.add_child(Box::new(inner));
+        let click = Event::Mouse(MouseEvent::Down(
+            MouseButton::Left,
+            3,
+            6,
+            KeyModifiers::empty(),
+        ));
+        let backend = HeadlessBackend::new(14, 12, vec![click]);
+        let mut out = vec![];
+        let app = App::default();
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            Some(&app),
+            &mut root,
+            Box::new(backend),
+        );
+        renderer.run().expect("must run");
+        let lines: Vec<String> = renderer
+            .buffer()
+            .to_plain_text()
+            .lines()
+            .map(ToString::to_string)
+            .collect();
+        drop(renderer);
+        // the list starts below the button, the click is on its second item
+        assert_eq!(lines[3], "┏━━━━━━━━━━┓  ");
+        assert_eq!(lines[6], "┃ Item2    ┃  ");
+        assert_eq!(*app.msgs.borrow(), vec![Msg::Changed(vec![1])]);
+    }
+
+    #[test]
+    fn wide_items_are_cut_off() {
+        let mut list_box = items(false);
+        list_box.set_list(vec!["日本語のアイテム".to_string()]);
+        let (lines, _msgs) = run(list_box, vec![]);
+        // only the characters that fit before the border are drawn
+        assert_eq!(lines[1], "┃ 日本語の ┃  ");
+    }
 }
@@ -0,0 +1,486 @@
+use crate::{
+    buffer::Buffer,
+    find_node::find_widget_path,
+    focus,
+    layout,
+    theme,
+    widget_event::{
+        self,
+        HoverEvent,
+        Phase,
+        WidgetEvent,
+    },
+    widget_id::WidgetId,
+    Cmd,
+    InlineStyle,
+    LayoutTree,
+    Theme,
+    Widget,
+};
+use crossterm::event::{
+    Event,
+    MouseEvent,
+};
+use sauron_vdom::Value;
+use std::{
+    any::Any,
+    cell::Cell,
+    fmt,
+};
+use stretch::{
+    geometry::{
+        Point,
+        Size,
+    },
+    node::{
+        Node,
+        Stretch,
+    },
+    number::Number,
+    style::Style,
+};
+
+/// Embeds a widget tree which emits CMSG into a tree which emits PMSG.
+/// The msgs of the child are converted with the mapping function,
+/// so a reusable widget can be written once with its own msg type and used anywhere.
+///
+/// The child tree is a single stop when traversing the focus with Tab/Shift-Tab,
+/// the focus inside of it is moved with the mouse or by the child itself.
+pub struct MapMsg<CMSG, PMSG> {
+    child: Box<dyn Widget<CMSG>>,
+    mapper: Box<dyn Fn(CMSG) -> PMSG>,
+    widget_id: Option<WidgetId>,
+    /// the layout of the child tree, relative to this widget
+    child_layout: Option<LayoutTree>,
+    /// set when the parent tree is laid out again,
+    /// the child tree is then laid out again on the next draw
+    relayout: Cell<bool>,
+    /// the path in the child tree which captures the mouse while a button is held down
+    pointer_capture: Option<Vec<WidgetId>>,
+}
+
+impl<CMSG, PMSG> MapMsg<CMSG, PMSG> {
+    /// embed the child, converting its msgs with f
+    pub fn new<F>(child: Box<dyn Widget<CMSG>>, f: F) -> Self
+    where
+        F: Fn(CMSG) -> PMSG + 'static,
+    {
+        MapMsg {
+            child,
+            mapper: Box::new(f),
+            widget_id: None,
+            child_layout: None,
+            relayout: Cell::new(true),
+            pointer_capture: None,
+        }
+    }
+
+    /// get a reference to the embedded child
+    pub fn child(&self) -> &dyn Widget<CMSG> {
+        self.child.as_ref()
+    }
+
+    /// get a mutable reference to the embedded child
+    pub fn child_mut(&mut self) -> &mut dyn Widget<CMSG> {
+        self.child.as_mut()
+    }
+
+    fn map(&self, msgs: Vec<CMSG>) -> Vec<PMSG> {
+        msgs.into_iter().map(|msg| (self.mapper)(msg)).collect()
+    }
+
+    /// the path in the child tree which receives this event
+    fn child_path(&mut self, event: &WidgetEvent) -> Vec<WidgetId> {
+        let child_layout = match self.child_layout {
+            Some(ref child_layout) => child_layout,
+            None => return vec![],
+        };
+        match event.event {
+            Event::Key(_) => {
+                match focus::focused_widget(self.child.as_ref()) {
+                    Some(focused) => {
+                        find_widget_path(self.child.as_ref(), focused)
+                    }
+                    None => vec![child_layout.widget_id()],
+                }
+            }
+            Event::Mouse(mouse_event) => {
+                let capture = match mouse_event {
+                    MouseEvent::Drag(..) => self.pointer_capture.clone(),
+                    MouseEvent::Up(..) => self.pointer_capture.take(),
+                    _ => None,
+                };
+                if let Some(capture) = capture {
+                    return capture;
+                }
+                let (x, y) = match mouse_event {
+                    MouseEvent::Down(_, x, y, _)
+                    | MouseEvent::Up(_, x, y, _)
+                    | MouseEvent::Drag(_, x, y, _)
+                    | MouseEvent::ScrollDown(x, y, _)
+                    | MouseEvent::ScrollUp(x, y, _) => (x, y),
+                };
+                let hits = child_layout.hit_widgets(
+                    x as f32 - event.origin.0,
+                    y as f32 - event.origin.1,
+                );
+                if let MouseEvent::Down(..) = mouse_event {
+                    let focusable =
+                        focus::focusable_widgets(self.child.as_ref());
+                    let hit = hits
+                        .iter()
+                        .rev()
+                        .copied()
+                        .find(|widget_id| focusable.contains(widget_id));
+                    if hit.is_some() {
+                        layout::set_focused_widget(self.child.as_mut(), hit);
+                    }
+                    self.pointer_capture = Some(hits.clone());
+                }
+                hits
+            }
+            Event::Resize(..) => vec![],
+        }
+    }
+}
+
+impl<CMSG, PMSG> fmt::Debug for MapMsg<CMSG, PMSG> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MapMsg")
+            .field("child", &self.child)
+            .finish()
+    }
+}
+
+impl<CMSG, PMSG> Widget<PMSG> for MapMsg<CMSG, PMSG>
+where
+    CMSG: 'static,
+    PMSG: 'static,
+{
+    fn style(&self) -> Style {
+        self.child.style()
+    }
+
+    /// the children of the child are laid out in the parent tree,
+    /// so the size of this widget fits its content
+    fn style_node(&self, stretch: &mut Stretch) -> Option<Node> {
+        self.relayout.set(true);
+        self.child.style_node(stretch)
+    }
+
+    fn draw(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        theme: &Theme,
+    ) -> Vec<Cmd> {
+        let layout = layout_tree.layout;
+        let resized = self
+            .child_layout
+            .as_ref()
+            .map(|child_layout| child_layout.layout.size != layout.size)
+            .unwrap_or(true);
+        // the child tree is only laid out when the parent tree is, or when this widget is resized
+        if self.relayout.replace(false) || resized {
+            self.child_layout = Some(layout::compute_layout(
+                self.child.as_mut(),
+                Size {
+                    width: Number::Defined(layout.size.width),
+                    height: Number::Defined(layout.size.height),
+                },
+            ));
+        }
+        let child_layout = self
+            .child_layout
+            .as_mut()
+            .expect("must have a child layout");
+        child_layout.layout = layout;
+        let cmds =
+            theme::draw_widget(self.child.as_mut(), buf, child_layout, theme);
+        // mouse events are hit tested relative to this widget
+        child_layout.layout.location = Point { x: 0.0, y: 0.0 };
+        cmds
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        let focus = if focused {
+            focus::focused_widget(self.child.as_ref()).or_else(|| {
+                focus::focusable_widgets(self.child.as_ref())
+                    .first()
+                    .copied()
+            })
+        } else {
+            None
+        };
+        layout::set_focused_widget(self.child.as_mut(), focus);
+    }
+
+    fn is_focused(&self) -> bool {
+        focus::focused_widget(self.child.as_ref()).is_some()
+    }
+
+    fn is_focusable(&self) -> bool {
+        !focus::focusable_widgets(self.child.as_ref()).is_empty()
+    }
+
+    fn set_hovered(&mut self, hovered: bool) {
+        self.child.set_hovered(hovered);
+    }
+
+    fn is_hovered(&self) -> bool {
+        self.child.is_hovered()
+    }
+
+    fn hover_event(&mut self, event: HoverEvent) -> Vec<PMSG> {
+        let msgs = self.child.hover_event(event);
+        self.map(msgs)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn set_size(&mut self, width: Option<f32>, height: Option<f32>) {
+        self.child.set_size(width, height);
+    }
+
+    fn set_disabled(&mut self, disabled: bool) {
+        self.child.set_disabled(disabled);
+    }
+
+    fn is_disabled(&self) -> bool {
+        self.child.is_disabled()
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.child.set_theme(theme);
+    }
+
+    fn set_inline_style(&mut self, style: InlineStyle) {
+        self.child.set_inline_style(style);
+    }
+
+    fn get_inline_style(&self) -> Option<&InlineStyle> {
+        self.child.get_inline_style()
+    }
+
+    /// the event is propagated through the child tree as if it was the root,
+    /// starting from the location of this widget
+    fn handle_event(&mut self, event: &mut WidgetEvent) -> Vec<PMSG> {
+        if event.phase != Phase::Target {
+            return vec![];
+        }
+        let path = self.child_path(event);
+        let child_layouts = match self.child_layout {
+            Some(ref child_layout) => child_layout.widget_layouts(),
+            None => return vec![],
+        };
+        let mut child_event = WidgetEvent::new(event.event, path);
+        if event.is_default_prevented() {
+            child_event.prevent_default();
+        }
+        let msgs = widget_event::propagate(
+            self.child.as_mut(),
+            &mut child_event,
+            &child_layouts,
+            event.origin,
+        );
+        if child_event.is_propagation_stopped() {
+            event.stop_propagation();
+        }
+        if child_event.is_default_prevented() {
+            event.prevent_default();
+        }
+        self.map(msgs)
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.child.set_id(id);
+    }
+
+    fn get_id(&self) -> &Option<String> {
+        self.child.get_id()
+    }
+
+    fn set_widget_id(&mut self, widget_id: WidgetId) {
+        self.widget_id = Some(widget_id);
+    }
+
+    fn get_widget_id(&self) -> Option<WidgetId> {
+        self.widget_id
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.child.add_class(class);
+    }
+
+    fn remove_class(&mut self, class: &str) {
+        self.child.remove_class(class);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        self.child.get_classes()
+    }
+
+    fn get_tag(&self) -> &'static str {
+        self.child.get_tag()
+    }
+
+    fn set_attribute(&mut self, name: &str, value: &Value) {
+        self.child.set_attribute(name, value);
+    }
+
+    fn remove_attribute(&mut self, name: &str) {
+        self.child.remove_attribute(name);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        Button,
+        Dispatch,
+        FlexBox,
+        HeadlessBackend,
+        Renderer,
+    };
+    use crossterm::event::{
+        KeyCode,
+        KeyModifiers,
+        MouseButton,
+    };
+    use sauron_vdom::Callback;
+    use std::cell::RefCell;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum LoginMsg {
+        Submit,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Msg {
+        Login(LoginMsg),
+    }
+
+    #[derive(Default)]
+    struct App {
+        msgs: RefCell<Vec<Msg>>,
+    }
+
+    impl Dispatch<Msg> for App {
+        fn dispatch(
+            &self,
+            msg: Msg,
+            _root_node: &mut dyn Widget<Msg>,
+        ) -> Vec<Cmd> {
+            self.msgs.borrow_mut().push(msg);
+            vec![]
+        }
+    }
+
+    fn login_form() -> FlexBox<LoginMsg> {
+        let mut form = FlexBox::new();
+        form.vertical();
+        let mut login = Button::new("login");
+        login.set_size(Some(10.0), Some(3.0));
+        login.add_click_listener(Callback::from(|_| LoginMsg::Submit));
+        form.add_child(Box::new(login));
+        form
+    }
+
+    #[test]
+    fn child_msgs_are_mapped() {
+        let mut root = FlexBox::<Msg>::new();
+        root.vertical();
+        let mut top = Button::new("top");
+        top.set_size(Some(10.0), Some(3.0));
+        root.add_child(Box::new(top));
+        root.add_child(Box::new(MapMsg::new(
+            Box::new(login_form()),
+            Msg::Login,
+        )));
+
+        let backend = HeadlessBackend::new(
+            20,
+            8,
+            vec![
+                // the login button is below the top button
+                Event::Mouse(MouseEvent::Down(
+                    MouseButton::Left,
+                    2,
+                    4,
+                    KeyModifiers::empty(),
+                )),
+                Event::Key(KeyCode::Enter.into()),
+            ],
+        );
+        let mut out = vec![];
+        let app = App::default();
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            Some(&app),
+            &mut root,
+            Box::new(backend),
+        );
+        renderer.run().expect("must run");
+        drop(renderer);
+        assert_eq!(
+            *app.msgs.borrow(),
+            vec![Msg::Login(LoginMsg::Submit), Msg::Login(LoginMsg::Submit)]
+        );
+        // the click focused the login button inside of the embedded form
+        let form = root.children().unwrap()[1]
+            .as_any()
+            .downcast_ref::<MapMsg<LoginMsg, Msg>>()
+            .unwrap();
+        assert!(form.is_focused());
+        assert!(form.child().children().unwrap()[0].is_focused());
+    }
+
+    #[test]
+    fn child_layout_is_reused() {
+        let mut root = FlexBox::<Msg>::new();
+        root.add_child(Box::new(MapMsg::new(
+            Box::new(login_form()),
+            Msg::Login,
+        )));
+        let size = Size {
+            width: Number::Defined(20.0),
+            height: Number::Defined(8.0),
+        };
+        let layout_tree = layout::compute_layout(&mut root, size);
+        let mut buf = Buffer::new(20, 8);
+        let theme = Theme::default();
+        let login_height = |root: &FlexBox<Msg>| {
+            let form = root.children().unwrap()[0]
+                .as_any()
+                .downcast_ref::<MapMsg<LoginMsg, Msg>>()
+                .unwrap();
+            form.child_layout.as_ref().unwrap().children_layout[0]
+                .layout
+                .size
+                .height
+        };
+        root.draw(&mut buf, &layout_tree, &theme);
+        assert_eq!(login_height(&root), 3.0);
+
+        let form = root.children_mut().unwrap()[0]
+            .as_any_mut()
+            .downcast_mut::<MapMsg<LoginMsg, Msg>>()
+            .unwrap();
+        form.child_mut().children_mut().unwrap()[0]
+            .set_size(Some(10.0), Some(5.0));
+        // the child tree is not laid out again until the parent tree is
+        root.draw(&mut buf, &layout_tree, &theme);
+        assert_eq!(login_height(&root), 3.0);
+
+        let layout_tree = layout::compute_layout(&mut root, size);
+        root.draw(&mut buf, &layout_tree, &theme);
+        assert_eq!(login_height(&root), 5.0);
+    }
+}
@@ -1,18 +1,27 @@
 use crate::{
     buffer::Buffer,
     cmd::Cmd,
+    component,
     layout::LayoutTree,
     symbol,
+    widget_id::WidgetId,
+    InlineStyle,
+    Theme,
     Widget,
 };
 use crossterm::event::{
     Event,
+    KeyCode,
+    KeyEvent,
     MouseEvent,
 };
 use stretch::result::Layout;
 
 use ito_canvas::unicode_canvas::Canvas;
-use sauron_vdom::Callback;
+use sauron_vdom::{
+    Callback,
+    Value,
+};
 use std::{
     any::Any,
     fmt,
@@ -31,7 +40,12 @@ pub struct Slider<MSG> {
     value: f32,
     width: Option<f32>,
     id: Option<String>,
+    widget_id: Option<WidgetId>,
+    classes: Vec<String>,
+    theme: Option<Theme>,
+    inline_style: Option<InlineStyle>,
     use_thick_track: bool,
+    focused: bool,
     layout: Option<Layout>,
     on_input: Vec<Callback<sauron_vdom::Event, MSG>>,
 }
@@ -42,7 +56,12 @@ impl<MSG> Default for Slider<MSG> {
             value: 0.0,
             width: None,
             id: None,
+            widget_id: None,
+            classes: vec![],
+            theme: None,
+            inline_style: None,
             use_thick_track: false,
+            focused: false,
             layout: None,
             on_input: vec![],
         }
@@ -63,6 +82,11 @@ impl<MSG> Slider<MSG> {
         self.value = value;
     }
 
+    /// returns the value of this slider, from 0.0 to 1.0
+    pub fn get_value(&self) -> f32 {
+        self.value
+    }
+
     /// set the use thick track, default is false
     pub fn use_thick_track(&mut self, use_thick: bool) {
         self.use_thick_track = use_thick;
@@ -87,13 +111,19 @@ where
         }
     }
 
-    fn draw(&mut self, buf: &mut Buffer, layout_tree: &LayoutTree) -> Vec<Cmd> {
+    fn draw(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        theme: &Theme,
+    ) -> Vec<Cmd> {
         let layout = layout_tree.layout;
         self.layout = Some(layout.clone());
         let loc_x = layout.location.x.round() as usize;
         let loc_y = layout.location.y.round() as usize;
         let width = layout.size.width.round() as usize;
         let _height = layout.size.height.round() as usize;
+        let style = theme.widget_style(self);
         let mut canvas = Canvas::new();
         let right = loc_x + width - 2;
         canvas.draw_horizontal_line(
@@ -101,12 +131,28 @@ where
             (right, loc_y),
             self.use_thick_track,
         );
-        buf.write_canvas(canvas);
+        style.write_canvas(buf, canvas);
         let slider_loc = (self.value * width as f32) as usize;
-        buf.set_symbol(loc_x + slider_loc, loc_y, symbol::MIDDLE_BLOCK);
+        let mut thumb = style.cell(symbol::MIDDLE_BLOCK);
+        if let Some(accent) = theme.palette.accent {
+            thumb.color(accent);
+        }
+        buf.set_cell(loc_x + slider_loc, loc_y, thumb);
         vec![]
     }
 
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -122,18 +168,42 @@ where
     fn process_event(&mut self, event: Event) -> Vec<MSG> {
         let layout = self.layout.expect("must have a layout set");
         match event {
-            Event::Mouse(MouseEvent::Down(_btn, x, _y, _modifier)) => {
+            Event::Mouse(MouseEvent::Down(_btn, x, _y, _modifier))
+            | Event::Mouse(MouseEvent::Drag(_btn, x, _y, _modifier)) => {
                 let cursor_loc = x as i32 - layout.location.x.round() as i32;
                 let width = layout.size.width;
-                let value = cursor_loc as f32 / width;
-                eprintln!("value: {}", value);
-                self.value = value;
+                // the thumb stays at the ends when dragged past the track
+                self.value = (cursor_loc as f32 / width).clamp(0.0, 1.0);
+                vec![]
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::Left,
+                ..
+            }) => {
+                self.value = (self.value - 0.1).max(0.0);
+                vec![]
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::Right,
+                ..
+            }) => {
+                self.value = (self.value + 0.1).min(1.0);
                 vec![]
             }
             _ => vec![],
         }
     }
 
+    fn consume_event(&mut self, event: Event) -> (Vec<MSG>, bool) {
+        let consumed = match event {
+            Event::Key(KeyEvent { code, .. }) => {
+                code == KeyCode::Left || code == KeyCode::Right
+            }
+            _ => false,
+        };
+        (self.process_event(event), consumed)
+    }
+
     fn set_id(&mut self, id: &str) {
         self.id = Some(id.to_string());
     }
@@ -141,4 +211,68 @@ where
     fn get_id(&self) -> &Option<String> {
         &self.id
     }
+
+    fn set_widget_id(&mut self, widget_id: WidgetId) {
+        self.widget_id = Some(widget_id);
+    }
+
+    fn get_widget_id(&self) -> Option<WidgetId> {
+        self.widget_id
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.classes.push(class.to_string());
+    }
+
+    fn remove_class(&mut self, class: &str) {
+        self.classes.retain(|c| c != class);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    fn get_theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    fn set_inline_style(&mut self, style: InlineStyle) {
+        self.inline_style = Some(style);
+    }
+
+    fn get_inline_style(&self) -> Option<&InlineStyle> {
+        self.inline_style.as_ref()
+    }
+
+    fn get_tag(&self) -> &'static str {
+        "slider"
+    }
+
+    fn set_attribute(&mut self, name: &str, value: &Value) {
+        if name == "value" {
+            if let Some(value) = component::value_to_f32(value) {
+                self.set_value(value);
+            }
+        }
+    }
+
+    fn remove_attribute(&mut self, name: &str) {
+        if name == "value" {
+            self.set_value(0.0);
+        }
+    }
+
+    fn set_event_listeners(
+        &mut self,
+        event: &str,
+        listeners: Vec<Callback<sauron_vdom::Event, MSG>>,
+    ) {
+        if event == "input" {
+            self.on_input = listeners;
+        }
+    }
 }
@@ -1,8 +1,11 @@
 use crate::{
     buffer::Buffer,
     widget::ImageTrait,
+    widget_id::WidgetId,
     Cmd,
+    InlineStyle,
     LayoutTree,
+    Theme,
     Widget,
 };
 use image::{
@@ -27,6 +30,10 @@ pub struct SvgImage<MSG> {
     /// style layout
     height: Option<f32>,
     id: Option<String>,
+    widget_id: Option<WidgetId>,
+    classes: Vec<String>,
+    theme: Option<Theme>,
+    inline_style: Option<InlineStyle>,
     _phantom_msg: PhantomData<MSG>,
 }
 
@@ -54,6 +61,10 @@ impl<MSG> SvgImage<MSG> {
             width: Some(width as f32 / 10.0),
             height: Some(height as f32 / 10.0 / 2.0),
             id: None,
+            widget_id: None,
+            classes: vec![],
+            theme: None,
+            inline_style: None,
             _phantom_msg: PhantomData,
         };
         image
@@ -83,7 +94,12 @@ where
     }
 
     /// draw this button to the buffer, with the given computed layout
-    fn draw(&mut self, buf: &mut Buffer, layout_tree: &LayoutTree) -> Vec<Cmd> {
+    fn draw(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        _theme: &Theme,
+    ) -> Vec<Cmd> {
         self.draw_image(buf, layout_tree)
     }
 
@@ -107,6 +123,46 @@ where
     fn get_id(&self) -> &Option<String> {
         &self.id
     }
+
+    fn set_widget_id(&mut self, widget_id: WidgetId) {
+        self.widget_id = Some(widget_id);
+    }
+
+    fn get_widget_id(&self) -> Option<WidgetId> {
+        self.widget_id
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.classes.push(class.to_string());
+    }
+
+    fn remove_class(&mut self, class: &str) {
+        self.classes.retain(|c| c != class);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    fn get_theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    fn set_inline_style(&mut self, style: InlineStyle) {
+        self.inline_style = Some(style);
+    }
+
+    fn get_inline_style(&self) -> Option<&InlineStyle> {
+        self.inline_style.as_ref()
+    }
+
+    fn get_tag(&self) -> &'static str {
+        "svg"
+    }
 }
 
 impl<MSG> fmt::Debug for SvgImage<MSG> {
@@ -1,18 +1,31 @@
 use crate::{
     buffer::Buffer,
+    component,
+    theme,
     widget::Flex,
+    widget_id::{
+        self,
+        WidgetId,
+    },
     Cmd,
+    InlineStyle,
     LayoutTree,
+    StateStyle,
+    Theme,
     Widget,
 };
 use crossterm::event::{
     Event,
+    KeyCode,
+    KeyEvent,
+    KeyModifiers,
     MouseEvent,
 };
 use ito_canvas::unicode_canvas::{
     Border,
     Canvas,
 };
+use sauron_vdom::Value;
 use std::{
     any::Any,
     fmt,
@@ -22,6 +35,10 @@ use stretch::{
         Rect,
         Size,
     },
+    node::{
+        Node,
+        Stretch,
+    },
     result::Layout,
     style::{
         AlignContent,
@@ -37,6 +54,9 @@ use stretch::{
     },
 };
 
+/// the rows taken by the tab labels, including the line below them
+const LABELS_HEIGHT: f32 = 3.0;
+
 /// A Tab box contains multiple box which
 /// can only be shown one at a time
 ///```ignore
@@ -62,6 +82,10 @@ pub struct TabBox<MSG> {
     flex_direction: FlexDirection,
     scroll_top: f32,
     id: Option<String>,
+    widget_id: Option<WidgetId>,
+    classes: Vec<String>,
+    theme: Option<Theme>,
+    inline_style: Option<InlineStyle>,
     has_border: bool,
     is_rounded_border: bool,
     is_thick_border: bool,
@@ -80,6 +104,10 @@ impl<MSG> TabBox<MSG> {
             flex_direction: FlexDirection::Column,
             scroll_top: 0.0,
             id: None,
+            widget_id: None,
+            classes: vec![],
+            theme: None,
+            inline_style: None,
             has_border: true,
             is_rounded_border: true,
             is_thick_border: false,
@@ -142,6 +170,8 @@ impl<MSG> TabBox<MSG> {
         buf: &mut Buffer,
         canvas: &mut Canvas,
         layout_tree: &LayoutTree,
+        theme: &Theme,
+        style: &StateStyle,
     ) {
         let layout = layout_tree.layout;
         let loc_x = layout.location.x.round() as usize;
@@ -158,15 +188,17 @@ impl<MSG> TabBox<MSG> {
         for (tab_index, ((left, top), (right, bottom))) in
             tab_rects.iter().enumerate()
         {
-            if self.active_tab == tab_index {
-                buf.write_bold_str(
-                    left + 2,
-                    top + 1,
-                    &self.tab_labels[tab_index],
-                );
+            let label_style = if self.active_tab == tab_index {
+                style.merge(&theme.accent_style())
             } else {
-                buf.write_str(left + 2, top + 1, &self.tab_labels[tab_index]);
-            }
+                style.clone()
+            };
+            label_style.write_str(
+                buf,
+                left + 2,
+                top + 1,
+                &self.tab_labels[tab_index],
+            );
             canvas.draw_rect(
                 (*left, *top),
                 (*right, *bottom),
@@ -218,30 +250,56 @@ impl<MSG> TabBox<MSG> {
         &mut self,
         buf: &mut Buffer,
         layout_tree: &LayoutTree,
-    ) -> Vec<Cmd> {
+        theme: &Theme,
+        style: &StateStyle,
+    ) -> Vec<Cmd>
+    where
+        MSG: fmt::Debug + 'static,
+    {
         let layout = layout_tree.layout;
         let loc_x = layout.location.x.round();
         let loc_y = layout.location.y.round();
         let width = layout.size.width.round();
         let height = layout.size.height.round();
 
-        let mut inner_buf =
-            Buffer::new(width as usize - 2, height as usize - 2);
+        // the children are laid out relative to this widget, below the tab labels
+        let mut inner_buf = Buffer::new(width as usize, height as usize);
+        if let Some(bg) = style.background {
+            inner_buf.set_background(bg);
+        }
 
         let cmds = self
             .children
             .iter_mut()
             .zip(layout_tree.children_layout.iter())
             .flat_map(|(child, child_layout)| {
-                child.draw(&mut inner_buf, child_layout)
+                theme::draw_widget(
+                    child.as_mut(),
+                    &mut inner_buf,
+                    child_layout,
+                    theme,
+                )
             })
             .collect();
 
+        // only the area inside the border and below the labels is visible
+        let left = self.border_left() as usize;
+        let top = LABELS_HEIGHT as usize;
+        let right = (width - self.border_right()).max(0.0) as usize;
+        let bottom = (height - self.border_bottom()).max(0.0) as usize;
+        let scroll_top = self.scroll_top as usize;
         for (j, line) in inner_buf.cells.iter().enumerate() {
-            for (i, cell) in line.iter().enumerate() {
+            if j < scroll_top {
+                continue;
+            }
+            let y = j - scroll_top;
+            if y < top || y >= bottom {
+                continue;
+            }
+            for (i, cell) in line.iter().enumerate().take(right).skip(left) {
                 buf.set_cell(
                     loc_x as usize + i,
-                    loc_y as usize + j + 2,
+                    loc_y as usize + y,
                     cell.clone(),
                 )
             }
@@ -260,6 +318,26 @@ impl<MSG> TabBox<MSG> {
             self.active_tab = index;
         }
     }
+
+    /// returns the index of the active tab
+    pub fn get_active_tab(&self) -> usize {
+        self.active_tab
+    }
+
+    /// switch to the next tab, wrapping around to the first tab
+    pub fn next_tab(&mut self) {
+        if !self.tab_labels.is_empty() {
+            self.active_tab = (self.active_tab + 1) % self.tab_labels.len();
+        }
+    }
+
+    /// switch to the previous tab, wrapping around to the last tab
+    pub fn prev_tab(&mut self) {
+        if !self.tab_labels.is_empty() {
+            let len = self.tab_labels.len();
+            self.active_tab = (self.active_tab + len - 1) % len;
+        }
+    }
 }
 
 impl<MSG> Widget<MSG> for TabBox<MSG>
@@ -307,7 +385,7 @@ where
                 end: Dimension::Points(0.0),
             },
             padding: Rect {
-                top: Dimension::Points(1.0),
+                top: Dimension::Points(LABELS_HEIGHT - self.border_top()),
                 start: Dimension::Points(1.0),
                 bottom: Dimension::Points(0.0),
                 end: Dimension::Points(0.0),
@@ -318,7 +396,16 @@ where
         }
     }
 
-    fn draw(&mut self, buf: &mut Buffer, layout_tree: &LayoutTree) -> Vec<Cmd> {
+    fn style_node(&self, stretch: &mut Stretch) -> Option<Node> {
+        self.flex_style_node(stretch)
+    }
+
+    fn draw(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        theme: &Theme,
+    ) -> Vec<Cmd> {
         // offset the position of the top_border
         let layout = layout_tree.layout;
         self.layout = Some(layout.clone());
@@ -343,14 +430,16 @@ where
             is_bottom_right_rounded: true,
         };
 
-        self.draw_children(buf, layout_tree);
-        self.draw_labels(buf, &mut canvas, layout_tree);
+        let style = theme.widget_style(self);
+        let cmds = self.draw_children(buf, layout_tree, theme, &style);
+        self.draw_labels(buf, &mut canvas, layout_tree, theme, &style);
         canvas.draw_rect((left, top), (right, bottom), border);
-        buf.write_canvas(canvas);
-        vec![]
+        style.write_canvas(buf, canvas);
+        cmds
     }
 
-    fn add_child(&mut self, child: Box<dyn Widget<MSG>>) -> bool {
+    fn add_child(&mut self, mut child: Box<dyn Widget<MSG>>) -> bool {
+        widget_id::ensure_widget_id(child.as_mut());
         self.children.push(child);
         true
     }
@@ -396,17 +485,105 @@ where
         &self.id
     }
 
+    fn set_widget_id(&mut self, widget_id: WidgetId) {
+        self.widget_id = Some(widget_id);
+    }
+
+    fn get_widget_id(&self) -> Option<WidgetId> {
+        self.widget_id
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.classes.push(class.to_string());
+    }
+
+    fn remove_class(&mut self, class: &str) {
+        self.classes.retain(|c| c != class);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    fn get_theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    fn set_inline_style(&mut self, style: InlineStyle) {
+        self.inline_style = Some(style);
+    }
+
+    fn get_inline_style(&self) -> Option<&InlineStyle> {
+        self.inline_style.as_ref()
+    }
+
+    fn get_tag(&self) -> &'static str {
+        "tabbox"
+    }
+
+    fn set_attribute(&mut self, name: &str, value: &Value) {
+        match name {
+            "labels" => self.set_tab_labels(component::value_to_strings(value)),
+            "active_tab" => {
+                if let Some(index) = component::value_to_f32(value) {
+                    self.set_active_tab(index as usize);
+                }
+            }
+            "horizontal" => {
+                if value.as_bool() == Some(true) {
+                    self.horizontal()
+                } else {
+                    self.vertical()
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn remove_attribute(&mut self, name: &str) {
+        match name {
+            "labels" => self.set_tab_labels(vec![]),
+            "active_tab" => self.set_active_tab(0),
+            "horizontal" => self.vertical(),
+            _ => (),
+        }
+    }
+
     fn process_event(&mut self, event: Event) -> Vec<MSG> {
+        self.consume_event(event).0
+    }
+
+    /// Ctrl-PageUp and Ctrl-PageDown switches the tabs,
+    /// even when one of the widgets inside the tab is focused
+    fn consume_event(&mut self, event: Event) -> (Vec<MSG>, bool) {
         match event {
+            Event::Key(KeyEvent {
+                code: KeyCode::PageUp,
+                modifiers,
+            }) if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.prev_tab();
+                (vec![], true)
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::PageDown,
+                modifiers,
+            }) if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.next_tab();
+                (vec![], true)
+            }
             Event::Mouse(MouseEvent::Down(_btn, x, y, _modifier)) => {
                 if let Some(active_tab) =
                     self.hit_tab_label(x as usize, y as usize)
                 {
                     self.active_tab = active_tab;
                 }
-                vec![]
+                (vec![], false)
             }
-            _ => vec![],
+            _ => (vec![], false),
         }
     }
 }
@@ -443,3 +620,40 @@ where
         self.scroll_top
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        Button,
+        FlexBox,
+        HeadlessBackend,
+        Renderer,
+    };
+
+    #[test]
+    fn children_below_the_labels() {
+        let mut tab_box = TabBox::<()>::new();
+        tab_box.set_tab_labels(vec!["one".to_string()]);
+        tab_box.add_child(Box::new(Button::new("inside")));
+        let mut root = FlexBox::new();
+        root.add_child(Box::new(tab_box));
+
+        let backend = HeadlessBackend::new(20, 9, vec![]);
+        let mut out = vec![];
+        let mut renderer = Renderer::with_backend(
+            &mut out,
+            None,
+            &mut root,
+            Box::new(backend),
+        );
+        renderer.run().expect("must run");
+        let text = renderer.buffer().to_plain_text();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[1], "   │ one │          ");
+        // the button is inside of the border, right below the labels
+        assert_eq!(lines[3], "│ ╭──────╮         │");
+        assert_eq!(lines[4], "│ │inside│         │");
+        assert_eq!(lines[6], "╰──────────────────╯");
+    }
+}
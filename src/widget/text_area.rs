@@ -3,8 +3,12 @@ use crate::{
     buffer::Buffer,
     symbol,
     symbol::bar,
+    widget_id::WidgetId,
     Cmd,
+    InlineStyle,
     LayoutTree,
+    StateStyle,
+    Theme,
     Widget,
 };
 use crossterm::event::{
@@ -13,11 +17,10 @@ use crossterm::event::{
     KeyModifiers,
     MouseEvent,
 };
-use ito_canvas::unicode_canvas::{
-    Border,
-    Canvas,
+use sauron_vdom::{
+    Callback,
+    Value,
 };
-use sauron_vdom::Callback;
 use std::{
     any::Any,
     fmt,
@@ -42,6 +45,10 @@ pub struct TextArea<MSG> {
     scroll_top: f32,
     scroll_left: f32,
     id: Option<String>,
+    widget_id: Option<WidgetId>,
+    classes: Vec<String>,
+    theme: Option<Theme>,
+    inline_style: Option<InlineStyle>,
     on_input: Vec<Callback<sauron_vdom::Event, MSG>>,
     has_border: bool,
     is_rounded_border: bool,
@@ -63,6 +70,10 @@ impl<MSG> TextArea<MSG> {
             scroll_top: 0.0,
             scroll_left: 0.0,
             id: None,
+            widget_id: None,
+            classes: vec![],
+            theme: None,
+            inline_style: None,
             on_input: vec![],
             has_border: true,
             is_rounded_border: false,
@@ -85,8 +96,8 @@ impl<MSG> TextArea<MSG> {
     }
 
     /// process the keypress event
-    pub fn process_key(&mut self, key_event: KeyEvent) {
-        self.area_buffer.process_key_event(key_event);
+    pub fn process_key(&mut self, key_event: KeyEvent) -> bool {
+        self.area_buffer.process_key_event(key_event)
     }
 
     /// set the value of this text area
@@ -186,7 +197,12 @@ impl<MSG> TextArea<MSG> {
         (abs_cursor_x, abs_cursor_y)
     }
 
-    fn draw_scrollers(&self, buf: &mut Buffer, layout_tree: &LayoutTree) {
+    fn draw_scrollers(
+        &self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        style: &StateStyle,
+    ) {
         let layout = layout_tree.layout;
         let loc_x = layout.location.x.round();
         let loc_y = layout.location.y.round();
@@ -204,26 +220,31 @@ impl<MSG> TextArea<MSG> {
 
         if inner_height > 0.0 {
             for j in 0..scroller_height {
-                buf.set_symbol(
+                buf.set_cell(
                     right as usize,
                     bottom as usize - j - 1,
-                    bar::SEVEN_EIGHTHS,
+                    style.cell(bar::SEVEN_EIGHTHS),
                 );
             }
         }
 
         if inner_width > 0.0 {
             for i in 0..scroller_width {
-                buf.set_symbol(
+                buf.set_cell(
                     right as usize - i - 1,
                     bottom as usize,
-                    symbol::MIDDLE_BLOCK,
+                    style.cell(symbol::MIDDLE_BLOCK),
                 );
             }
         }
     }
 
-    fn draw_border(&mut self, buf: &mut Buffer, layout_tree: &LayoutTree) {
+    fn draw_border(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        style: &StateStyle,
+    ) {
         let layout = layout_tree.layout;
         let loc_x = layout.location.x.round() as usize;
         let loc_y = layout.location.y.round() as usize;
@@ -235,20 +256,7 @@ impl<MSG> TextArea<MSG> {
         let bottom = top + height - 1;
         let right = left + width - 1;
 
-        let border = Border {
-            use_thick_border: self.focused,
-            has_top: true,
-            has_bottom: true,
-            has_left: true,
-            has_right: true,
-            is_top_left_rounded: false,
-            is_top_right_rounded: false,
-            is_bottom_left_rounded: false,
-            is_bottom_right_rounded: false,
-        };
-        let mut canvas = Canvas::new();
-        canvas.draw_rect((left, top), (right, bottom), border);
-        buf.write_canvas(canvas);
+        style.draw_border(buf, (left, top), (right, bottom), false);
     }
 }
 
@@ -293,7 +301,12 @@ where
     }
 
     /// draw this button to the buffer, with the given computed layout
-    fn draw(&mut self, buf: &mut Buffer, layout_tree: &LayoutTree) -> Vec<Cmd> {
+    fn draw(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        theme: &Theme,
+    ) -> Vec<Cmd> {
         let layout = layout_tree.layout;
         let loc_x = layout.location.x.round();
         let loc_y = layout.location.y.round();
@@ -301,6 +314,20 @@ where
         self.layout = Some(layout.clone());
 
         let bottom = loc_y + height - 1.0;
+        let style = theme.widget_style(self);
+        style.fill(
+            buf,
+            (loc_x as usize, loc_y as usize),
+            (
+                (loc_x + layout.size.width.round()) as usize - 1,
+                bottom as usize,
+            ),
+        );
+        // the text is not bold like the labels of the focused widgets
+        let text_style = StateStyle {
+            attributes: vec![],
+            ..style.clone()
+        };
 
         // draw the text content
         let text_loc_y = loc_y - self.scroll_top;
@@ -316,10 +343,10 @@ where
                     if (i as f32) >= self.scroll_left
                         && (i as f32) < right_scroll
                     {
-                        buf.set_symbol(
+                        buf.set_cell(
                             (text_loc_x + i as f32 + 1.0) as usize,
                             (text_loc_y + j as f32 + 1.0) as usize,
-                            ch,
+                            text_style.cell(ch),
                         );
                     }
                 }
@@ -330,8 +357,8 @@ where
 
         let is_cursor_visible = abs_cursor_y > loc_y && abs_cursor_y < bottom;
 
-        self.draw_border(buf, layout_tree);
-        self.draw_scrollers(buf, layout_tree);
+        self.draw_border(buf, layout_tree, &style);
+        self.draw_scrollers(buf, layout_tree, &style);
 
         if self.focused && is_cursor_visible {
             vec![
@@ -347,6 +374,14 @@ where
         self.focused = focused;
     }
 
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -363,16 +398,7 @@ where
     fn process_event(&mut self, event: Event) -> Vec<MSG> {
         let layout = self.layout.expect("must have a layout");
         match event {
-            Event::Key(ke) => {
-                self.process_key(ke);
-                let s_event: sauron_vdom::Event =
-                    sauron_vdom::event::InputEvent::new(self.get_content())
-                        .into();
-                self.on_input
-                    .iter()
-                    .map(|cb| cb.emit(s_event.clone()))
-                    .collect()
-            }
+            Event::Key(_) => self.consume_event(event).0,
             Event::Mouse(MouseEvent::Down(_btn, x, y, _modifier)) => {
                 let mut x = x as f32 - layout.location.x.round();
                 let mut y = y as f32 - layout.location.y.round() - 1.0;
@@ -429,6 +455,26 @@ where
         }
     }
 
+    fn consume_event(&mut self, event: Event) -> (Vec<MSG>, bool) {
+        match event {
+            Event::Key(ke) => {
+                if !self.process_key(ke) {
+                    return (vec![], false);
+                }
+                let s_event: sauron_vdom::Event =
+                    sauron_vdom::event::InputEvent::new(self.get_content())
+                        .into();
+                let msgs = self
+                    .on_input
+                    .iter()
+                    .map(|cb| cb.emit(s_event.clone()))
+                    .collect();
+                (msgs, true)
+            }
+            _ => (self.process_event(event), false),
+        }
+    }
+
     fn set_id(&mut self, id: &str) {
         self.id = Some(id.to_string());
     }
@@ -436,4 +482,70 @@ where
     fn get_id(&self) -> &Option<String> {
         &self.id
     }
+
+    fn set_widget_id(&mut self, widget_id: WidgetId) {
+        self.widget_id = Some(widget_id);
+    }
+
+    fn get_widget_id(&self) -> Option<WidgetId> {
+        self.widget_id
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.classes.push(class.to_string());
+    }
+
+    fn remove_class(&mut self, class: &str) {
+        self.classes.retain(|c| c != class);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    fn get_theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    fn set_inline_style(&mut self, style: InlineStyle) {
+        self.inline_style = Some(style);
+    }
+
+    fn get_inline_style(&self) -> Option<&InlineStyle> {
+        self.inline_style.as_ref()
+    }
+
+    fn get_tag(&self) -> &'static str {
+        "textarea"
+    }
+
+    fn set_attribute(&mut self, name: &str, value: &Value) {
+        // the cursor is kept if the value is not changed
+        if name == "value" {
+            let value = value.to_string();
+            if self.get_value() != value {
+                self.set_value(value);
+            }
+        }
+    }
+
+    fn remove_attribute(&mut self, name: &str) {
+        if name == "value" {
+            self.set_value("");
+        }
+    }
+
+    fn set_event_listeners(
+        &mut self,
+        event: &str,
+        listeners: Vec<Callback<sauron_vdom::Event, MSG>>,
+    ) {
+        if event == "input" {
+            self.on_input = listeners;
+        }
+    }
 }
@@ -1,8 +1,12 @@
 use crate::{
     buffer::Buffer,
+    widget_id::WidgetId,
     Cmd,
+    InlineStyle,
     InputBuffer,
     LayoutTree,
+    StateStyle,
+    Theme,
     Widget,
 };
 use crossterm::event::{
@@ -10,11 +14,14 @@ use crossterm::event::{
     KeyEvent,
     MouseEvent,
 };
-use ito_canvas::unicode_canvas::{
-    Border,
-    Canvas,
+use sauron_vdom::{
+    Callback,
+    Value,
+};
+use std::{
+    any::Any,
+    fmt,
 };
-use std::any::Any;
 use stretch::{
     geometry::Size,
     result::Layout,
@@ -24,19 +31,46 @@ use stretch::{
     },
 };
 
-/// A one line text input
-#[derive(Default, Debug)]
-pub struct TextInput {
+/// A one line text input,
+/// which emits the msgs of its input listeners when the user changes its value.
+///
+/// Note: `TextInput` is generic over the msg of its listeners,
+/// code which named the type without it now has to use `TextInput<MSG>`.
+pub struct TextInput<MSG> {
     input_buffer: InputBuffer,
     is_rounded: bool,
     focused: bool,
     width: Option<f32>,
     height: Option<f32>,
     id: Option<String>,
+    widget_id: Option<WidgetId>,
+    classes: Vec<String>,
+    theme: Option<Theme>,
+    inline_style: Option<InlineStyle>,
     layout: Option<Layout>,
+    on_input: Vec<Callback<sauron_vdom::Event, MSG>>,
 }
 
-impl TextInput {
+impl<MSG> Default for TextInput<MSG> {
+    fn default() -> Self {
+        TextInput {
+            input_buffer: InputBuffer::new(),
+            is_rounded: false,
+            focused: false,
+            width: None,
+            height: None,
+            id: None,
+            widget_id: None,
+            classes: vec![],
+            theme: None,
+            inline_style: None,
+            layout: None,
+            on_input: vec![],
+        }
+    }
+}
+
+impl<MSG> TextInput<MSG> {
     /// creates a new text input with initial value
     pub fn new<S>(value: S) -> Self
     where
@@ -46,14 +80,18 @@ impl TextInput {
             input_buffer: InputBuffer::new_with_value(value),
             is_rounded: false,
             id: None,
+            widget_id: None,
+            classes: vec![],
+            theme: None,
+            inline_style: None,
             layout: None,
             ..Default::default()
         }
     }
 
     /// process the key event for this text input
-    pub fn process_key(&mut self, key_event: KeyEvent) {
-        self.input_buffer.process_key_event(key_event);
+    pub fn process_key(&mut self, key_event: KeyEvent) -> bool {
+        self.input_buffer.process_key_event(key_event)
     }
 
     /// set the value of the buffer
@@ -66,6 +104,15 @@ impl TextInput {
         self.input_buffer.get_content()
     }
 
+    /// attach a listener which receives an input event with the new value,
+    /// when the value is changed by the user
+    pub fn add_input_listener(
+        &mut self,
+        cb: Callback<sauron_vdom::Event, MSG>,
+    ) {
+        self.on_input.push(cb);
+    }
+
     /// set whether to use rounded corner when drawing the border of the text input
     pub fn set_rounded(&mut self, rounded: bool) {
         self.is_rounded = rounded;
@@ -111,7 +158,16 @@ impl TextInput {
     }
 }
 
-impl<MSG> Widget<MSG> for TextInput {
+impl<MSG> fmt::Debug for TextInput<MSG> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TextInput")
+            .field("value", &self.get_value())
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+impl<MSG: 'static> Widget<MSG> for TextInput<MSG> {
     fn style(&self) -> Style {
         Style {
             size: Size {
@@ -143,7 +199,12 @@ impl<MSG> Widget<MSG> for TextInput {
     }
 
     /// draw this button to the buffer, with the given computed layout
-    fn draw(&mut self, buf: &mut Buffer, layout_tree: &LayoutTree) -> Vec<Cmd> {
+    fn draw(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        theme: &Theme,
+    ) -> Vec<Cmd> {
         let layout = layout_tree.layout;
         self.layout = Some(layout.clone());
         let loc_x = layout.location.x.round() as usize;
@@ -156,25 +217,19 @@ impl<MSG> Widget<MSG> for TextInput {
         let bottom = top + height - 1;
         let right = left + width - 1;
 
-        let border = Border {
-            use_thick_border: self.focused,
-            has_top: true,
-            has_bottom: true,
-            has_left: true,
-            has_right: true,
-            is_top_left_rounded: self.is_rounded,
-            is_top_right_rounded: self.is_rounded,
-            is_bottom_left_rounded: self.is_rounded,
-            is_bottom_right_rounded: self.is_rounded,
-        };
-        let mut canvas = Canvas::new();
-        canvas.draw_rect((left, top), (right, bottom), border);
-        buf.write_canvas(canvas);
+        let style = theme.widget_style::<MSG, _>(self);
+        style.fill(buf, (left, top), (right, bottom));
+        style.draw_border(buf, (left, top), (right, bottom), self.is_rounded);
 
+        // the value is not bold like the labels of the focused widgets
+        let text_style = StateStyle {
+            attributes: vec![],
+            ..style.clone()
+        };
         let inner_width = self.inner_width(&layout_tree.layout);
         for (t, ch) in self.get_value().chars().enumerate() {
             if loc_x + t < inner_width {
-                buf.set_symbol(loc_x + 1 + t, loc_y + 1, ch);
+                buf.set_cell(loc_x + 1 + t, loc_y + 1, text_style.cell(ch));
             }
         }
 
@@ -193,6 +248,14 @@ impl<MSG> Widget<MSG> for TextInput {
         self.focused = focused;
     }
 
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -207,18 +270,36 @@ impl<MSG> Widget<MSG> for TextInput {
     }
 
     fn process_event(&mut self, event: Event) -> Vec<MSG> {
-        let layout = self.layout.expect("must have a layout set");
+        self.consume_event(event).0
+    }
+
+    fn consume_event(&mut self, event: Event) -> (Vec<MSG>, bool) {
         match event {
             Event::Key(ke) => {
-                self.process_key(ke);
-                vec![]
+                let before = self.get_value().to_string();
+                let consumed = self.process_key(ke);
+                if self.get_value() == before {
+                    return (vec![], consumed);
+                }
+                let s_event: sauron_vdom::Event =
+                    sauron_vdom::event::InputEvent::new(
+                        self.get_value().to_string(),
+                    )
+                    .into();
+                let msgs = self
+                    .on_input
+                    .iter()
+                    .map(|cb| cb.emit(s_event.clone()))
+                    .collect();
+                (msgs, consumed)
             }
             Event::Mouse(MouseEvent::Down(_btn, x, _y, _modifier)) => {
+                let layout = self.layout.expect("must have a layout set");
                 let cursor_loc = x as i32 - layout.location.x.round() as i32;
                 self.input_buffer.set_cursor_loc(cursor_loc as usize);
-                vec![]
+                (vec![], false)
             }
-            _ => vec![],
+            _ => (vec![], false),
         }
     }
 
@@ -229,4 +310,76 @@ impl<MSG> Widget<MSG> for TextInput {
     fn get_id(&self) -> &Option<String> {
         &self.id
     }
+
+    fn set_widget_id(&mut self, widget_id: WidgetId) {
+        self.widget_id = Some(widget_id);
+    }
+
+    fn get_widget_id(&self) -> Option<WidgetId> {
+        self.widget_id
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.classes.push(class.to_string());
+    }
+
+    fn remove_class(&mut self, class: &str) {
+        self.classes.retain(|c| c != class);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    fn get_theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    fn set_inline_style(&mut self, style: InlineStyle) {
+        self.inline_style = Some(style);
+    }
+
+    fn get_inline_style(&self) -> Option<&InlineStyle> {
+        self.inline_style.as_ref()
+    }
+
+    fn get_tag(&self) -> &'static str {
+        "textinput"
+    }
+
+    fn set_attribute(&mut self, name: &str, value: &Value) {
+        match name {
+            // the cursor is kept if the value is not changed
+            "value" => {
+                let value = value.to_string();
+                if self.get_value() != value {
+                    self.set_value(value);
+                }
+            }
+            "rounded" => self.set_rounded(value.as_bool().unwrap_or(false)),
+            _ => (),
+        }
+    }
+
+    fn remove_attribute(&mut self, name: &str) {
+        match name {
+            "value" => self.set_value(""),
+            "rounded" => self.set_rounded(false),
+            _ => (),
+        }
+    }
+
+    fn set_event_listeners(
+        &mut self,
+        event: &str,
+        listeners: Vec<Callback<sauron_vdom::Event, MSG>>,
+    ) {
+        if event == "input" {
+            self.on_input = listeners;
+        }
+    }
 }
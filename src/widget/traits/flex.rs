@@ -1,18 +1,21 @@
 use crate::{
     buffer::Buffer,
+    theme,
     Cmd,
+    InlineStyle,
     LayoutTree,
+    Theme,
     Widget,
 };
-use ito_canvas::unicode_canvas::{
-    Border,
-    Canvas,
-};
 use stretch::{
     geometry::{
         Rect,
         Size,
     },
+    node::{
+        Node,
+        Stretch,
+    },
     style::{
         AlignContent,
         AlignItems,
@@ -27,21 +30,35 @@ use stretch::{
     },
 };
 
+/// The common behavior of the widgets which lay out their children with flexbox.
+///
+/// The flex properties set with the builder methods are stored in the inline style
+/// of the widget, so they are the same as declaring them with `set_inline_style`.
 pub trait Flex<MSG>: Widget<MSG> {
+    /// whether a border is drawn around the children
     fn has_border(&self) -> bool;
+    /// whether the corners of the border are rounded
     fn is_rounded_border(&self) -> bool;
+    /// whether the border is drawn with thick lines
     fn is_thick_border(&self) -> bool;
+    /// the direction of the main axis
     fn flex_direction(&self) -> FlexDirection;
+    /// the fixed width of this widget
     fn width(&self) -> Option<f32>;
+    /// the fixed height of this widget
     fn height(&self) -> Option<f32>;
+    /// how many rows the content is scrolled up
     fn scroll_top(&self) -> f32;
+    /// take as much width as possible
     fn is_expand_width(&self) -> bool {
         false
     }
+    /// take as much height as possible
     fn is_expand_height(&self) -> bool {
         false
     }
 
+    /// the width of the top border
     fn border_top(&self) -> f32 {
         if self.has_border() {
             1.0
@@ -50,6 +67,7 @@ pub trait Flex<MSG>: Widget<MSG> {
         }
     }
 
+    /// the width of the bottom border
     fn border_bottom(&self) -> f32 {
         if self.has_border() {
             1.0
@@ -58,6 +76,7 @@ pub trait Flex<MSG>: Widget<MSG> {
         }
     }
 
+    /// the width of the left border
     fn border_left(&self) -> f32 {
         if self.has_border() {
             1.0
@@ -66,6 +85,7 @@ pub trait Flex<MSG>: Widget<MSG> {
         }
     }
 
+    /// the width of the right border
     fn border_right(&self) -> f32 {
         if self.has_border() {
             1.0
@@ -74,6 +94,7 @@ pub trait Flex<MSG>: Widget<MSG> {
         }
     }
 
+    /// draw the border around the given area
     fn draw_border(
         &self,
         buf: &mut Buffer,
@@ -81,19 +102,13 @@ pub trait Flex<MSG>: Widget<MSG> {
         y: f32,
         width: f32,
         height: f32,
+        theme: &Theme,
     ) {
         if self.has_border() {
-            let border = Border {
-                use_thick_border: self.is_thick_border(),
-                has_top: true,
-                has_bottom: true,
-                has_left: true,
-                has_right: true,
-                is_top_left_rounded: self.is_rounded_border(),
-                is_top_right_rounded: self.is_rounded_border(),
-                is_bottom_left_rounded: self.is_rounded_border(),
-                is_bottom_right_rounded: self.is_rounded_border(),
-            };
+            let mut style = theme.widget_style::<MSG, _>(self);
+            if self.is_thick_border() {
+                style.thick_border = Some(true);
+            }
             let loc_x = x.round();
             let loc_y = y.round();
             let width = width.round();
@@ -103,14 +118,180 @@ pub trait Flex<MSG>: Widget<MSG> {
             let top = loc_y as usize;
             let bottom = (loc_y + height - 1.0) as usize;
             let right = (loc_x + width - 1.0) as usize;
-            let mut canvas = Canvas::new();
-            canvas.draw_rect((left, top), (right, bottom), border);
-            for (i, j, ch) in canvas.get_cells() {
-                buf.set_symbol(i, j, ch);
+            style.draw_border(
+                buf,
+                (left, top),
+                (right, bottom),
+                self.is_rounded_border(),
+            );
+        }
+    }
+
+    /// modify the inline style of this widget
+    fn update_inline_style<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut InlineStyle),
+        Self: Sized,
+    {
+        let mut inline_style =
+            self.get_inline_style().cloned().unwrap_or_default();
+        f(&mut inline_style);
+        self.set_inline_style(inline_style);
+    }
+
+    /// set how much this widget grows relative to its siblings to fill the space of its parent
+    fn set_flex_grow(&mut self, flex_grow: f32)
+    where
+        Self: Sized,
+    {
+        self.update_inline_style(|s| s.flex_grow = Some(flex_grow));
+    }
+
+    /// set how much this widget shrinks relative to its siblings when there is not enough space
+    fn set_flex_shrink(&mut self, flex_shrink: f32)
+    where
+        Self: Sized,
+    {
+        self.update_inline_style(|s| s.flex_shrink = Some(flex_shrink));
+    }
+
+    /// set the initial size of this widget along the main axis of its parent
+    fn set_flex_basis(&mut self, flex_basis: Dimension)
+    where
+        Self: Sized,
+    {
+        self.update_inline_style(|s| s.flex_basis = Some(flex_basis));
+    }
+
+    /// set whether the children wraps into multiple lines
+    fn set_flex_wrap(&mut self, flex_wrap: FlexWrap)
+    where
+        Self: Sized,
+    {
+        self.update_inline_style(|s| s.flex_wrap = Some(flex_wrap));
+    }
+
+    /// set how the children are distributed along the main axis
+    fn set_justify_content(&mut self, justify_content: JustifyContent)
+    where
+        Self: Sized,
+    {
+        self.update_inline_style(|s| s.justify_content = Some(justify_content));
+    }
+
+    /// set how the children are aligned along the cross axis
+    fn set_align_items(&mut self, align_items: AlignItems)
+    where
+        Self: Sized,
+    {
+        self.update_inline_style(|s| s.align_items = Some(align_items));
+    }
+
+    /// set how this widget is aligned along the cross axis of its parent,
+    /// overriding the `align_items` of the parent
+    fn set_align_self(&mut self, align_self: AlignSelf)
+    where
+        Self: Sized,
+    {
+        self.update_inline_style(|s| s.align_self = Some(align_self));
+    }
+
+    /// set how the lines are distributed along the cross axis when the children wraps
+    fn set_align_content(&mut self, align_content: AlignContent)
+    where
+        Self: Sized,
+    {
+        self.update_inline_style(|s| s.align_content = Some(align_content));
+    }
+
+    /// set the space in cells between the children
+    fn set_gap(&mut self, gap: f32)
+    where
+        Self: Sized,
+    {
+        self.update_inline_style(|s| s.gap = Some(gap));
+    }
+
+    /// set the space between the border and the children,
+    /// in the same order as css: top, right, bottom and left
+    fn set_padding(&mut self, top: f32, right: f32, bottom: f32, left: f32)
+    where
+        Self: Sized,
+    {
+        self.update_inline_style(|s| {
+            s.padding = Some(points_rect(top, right, bottom, left))
+        });
+    }
+
+    /// set the space around this widget,
+    /// in the same order as css: top, right, bottom and left
+    fn set_margin(&mut self, top: f32, right: f32, bottom: f32, left: f32)
+    where
+        Self: Sized,
+    {
+        self.update_inline_style(|s| {
+            s.margin = Some(points_rect(top, right, bottom, left))
+        });
+    }
+
+    /// set the minimum size of this widget, None leaves it unconstrained
+    fn set_min_size(&mut self, width: Option<f32>, height: Option<f32>)
+    where
+        Self: Sized,
+    {
+        self.update_inline_style(|s| {
+            s.min_width = width.map(Dimension::Points);
+            s.min_height = height.map(Dimension::Points);
+        });
+    }
+
+    /// set the maximum size of this widget, None leaves it unconstrained
+    fn set_max_size(&mut self, width: Option<f32>, height: Option<f32>)
+    where
+        Self: Sized,
+    {
+        self.update_inline_style(|s| {
+            s.max_width = width.map(Dimension::Points);
+            s.max_height = height.map(Dimension::Points);
+        });
+    }
+
+    /// build the style node of this widget with the gap added
+    /// to the margin of each child after the first one
+    fn flex_style_node(&self, stretch: &mut Stretch) -> Option<Node> {
+        let style = self.computed_style();
+        let children = self
+            .children()
+            .unwrap_or(&[])
+            .iter()
+            .filter_map(|c| c.style_node(stretch))
+            .collect::<Vec<_>>();
+        let gap = self.get_inline_style().and_then(|s| s.gap).unwrap_or(0.0);
+        if gap > 0.0 {
+            for child in children.iter().skip(1) {
+                let mut child_style = *stretch.style(*child).ok()?;
+                let margin = &mut child_style.margin;
+                let edge = match style.flex_direction {
+                    FlexDirection::Row => &mut margin.start,
+                    FlexDirection::RowReverse => &mut margin.end,
+                    FlexDirection::Column => &mut margin.top,
+                    FlexDirection::ColumnReverse => &mut margin.bottom,
+                };
+                *edge = match *edge {
+                    Dimension::Points(points) => {
+                        Dimension::Points(points + gap)
+                    }
+                    Dimension::Undefined => Dimension::Points(gap),
+                    // auto and percent margins are left as they are
+                    dimension => dimension,
+                };
+                stretch.set_style(*child, child_style).ok()?;
             }
         }
+        stretch.new_node(style, children).ok()
     }
 
+    /// the layout style of a flex container without any flex properties set
     fn flex_style(&self) -> Style {
         Style {
             flex_direction: self.flex_direction(),
@@ -171,10 +352,12 @@ pub trait Flex<MSG>: Widget<MSG> {
         }
     }
 
+    /// draw the children inside of the border
     fn draw_flex(
         &mut self,
         buf: &mut Buffer,
         layout_tree: &LayoutTree,
+        theme: &Theme,
     ) -> Vec<Cmd> {
         let layout = layout_tree.layout;
         let loc_x = layout.location.x.round();
@@ -182,12 +365,11 @@ pub trait Flex<MSG>: Widget<MSG> {
         let width = layout.size.width.round();
         let height = layout.size.height.round();
 
-        let mut inner_buf = Buffer::new(
-            width as usize
-                - (self.border_left() + self.border_right()) as usize,
-            height as usize
-                - (self.border_top() + self.border_bottom()) as usize,
-        );
+        // the children are laid out relative to this widget, including the border and padding
+        let mut inner_buf = Buffer::new(width as usize, height as usize);
+        if let Some(bg) = theme.widget_style::<MSG, _>(self).background {
+            inner_buf.set_background(bg);
+        }
 
         let cmds = self
             .children_mut()
@@ -195,23 +377,53 @@ pub trait Flex<MSG>: Widget<MSG> {
             .iter_mut()
             .zip(layout_tree.children_layout.iter())
             .flat_map(|(child, child_layout)| {
-                child.draw(&mut inner_buf, child_layout)
+                theme::draw_widget(
+                    child.as_mut(),
+                    &mut inner_buf,
+                    child_layout,
+                    theme,
+                )
             })
             .collect();
 
+        // only the area inside the border is visible, the content is scrolled in it
+        let left = self.border_left() as usize;
+        let top = self.border_top() as usize;
+        let right = (width - self.border_right()).max(0.0) as usize;
+        let bottom = (height - self.border_bottom()).max(0.0) as usize;
+        let scroll_top = self.scroll_top() as usize;
         for (j, line) in inner_buf.cells.iter().enumerate() {
-            for (i, cell) in line.iter().enumerate() {
-                if j >= self.scroll_top() as usize {
-                    let y = j - self.scroll_top() as usize;
-                    buf.set_cell(
-                        loc_x as usize + i,
-                        loc_y as usize + y,
-                        cell.clone(),
-                    )
-                }
+            if j < scroll_top {
+                continue;
+            }
+            let y = j - scroll_top;
+            if y < top || y >= bottom {
+                continue;
+            }
+            for (i, cell) in line.iter().enumerate().take(right).skip(left) {
+                buf.set_cell(
+                    loc_x as usize + i,
+                    loc_y as usize + y,
+                    cell.clone(),
+                )
             }
         }
-        self.draw_border(buf, loc_x, loc_y, width, height);
+        self.draw_border(buf, loc_x, loc_y, width, height, theme);
         cmds
     }
 }
+
+/// the edges in points, in the same order as css
+fn points_rect(
+    top: f32,
+    right: f32,
+    bottom: f32,
+    left: f32,
+) -> Rect<Dimension> {
+    Rect {
+        start: Dimension::Points(left),
+        end: Dimension::Points(right),
+        top: Dimension::Points(top),
+        bottom: Dimension::Points(bottom),
+    }
+}
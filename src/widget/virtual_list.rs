@@ -0,0 +1,653 @@
+use super::list_box::draw_scrollbar;
+use crate::{
+    buffer::{
+        Buffer,
+        Cell,
+    },
+    widget_event::{
+        Phase,
+        WidgetEvent,
+    },
+    widget_id::WidgetId,
+    Cmd,
+    InlineStyle,
+    LayoutTree,
+    StateStyle,
+    Theme,
+    Widget,
+};
+use crossterm::{
+    event::{
+        Event,
+        KeyCode,
+        KeyEvent,
+        MouseButton,
+        MouseEvent,
+    },
+    style::Attribute,
+};
+use sauron_vdom::Callback;
+use std::{
+    any::Any,
+    cell::RefCell,
+    fmt,
+    rc::Rc,
+};
+use stretch::{
+    geometry::Size,
+    result::Layout,
+    style::{
+        Dimension,
+        Style,
+    },
+};
+
+/// The rows of a `VirtualList`, which are only asked for when they are visible
+pub trait ListSource {
+    /// the number of rows
+    fn len(&self) -> usize;
+
+    /// returns true if there are no rows
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// the cells of the row at this index, the cells beyond the width are not drawn
+    fn row(&self, index: usize, width: usize) -> Vec<Cell>;
+}
+
+impl ListSource for Vec<String> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn row(&self, index: usize, width: usize) -> Vec<Cell> {
+        self[index].chars().take(width).map(Cell::new).collect()
+    }
+}
+
+/// the source can be shared with the app, which adds the rows to it
+impl<T> ListSource for Rc<RefCell<T>>
+where
+    T: ListSource,
+{
+    fn len(&self) -> usize {
+        self.borrow().len()
+    }
+
+    fn row(&self, index: usize, width: usize) -> Vec<Cell> {
+        self.borrow().row(index, width)
+    }
+}
+
+/// A list which draws only the visible rows of a large collection, such as logs.
+///
+/// The rows are pulled from the source each time the list is drawn,
+/// so the rows which are scrolled out of view are never created.
+/// The first visible row is kept in place when rows are appended to the source.
+/// In tail-follow mode, the list stays scrolled to the end as the rows are appended
+/// while it is at the end, scrolling up stops following until it is scrolled back down.
+pub struct VirtualList<MSG> {
+    source: Box<dyn ListSource>,
+    selected: Option<usize>,
+    /// the index of the first visible row
+    scroll_top: usize,
+    follow_tail: bool,
+    /// the number of rows when the list was last drawn
+    last_len: usize,
+    focused: bool,
+    width: Option<f32>,
+    height: Option<f32>,
+    layout: Option<Layout>,
+    on_select: Vec<Callback<usize, MSG>>,
+    on_activate: Vec<Callback<usize, MSG>>,
+    id: Option<String>,
+    widget_id: Option<WidgetId>,
+    classes: Vec<String>,
+    theme: Option<Theme>,
+    inline_style: Option<InlineStyle>,
+}
+
+impl<MSG: 'static> VirtualList<MSG> {
+    /// create a virtual list which shows the rows of this source
+    pub fn new<S>(source: S) -> Self
+    where
+        S: ListSource + 'static,
+    {
+        VirtualList {
+            source: Box::new(source),
+            selected: None,
+            scroll_top: 0,
+            follow_tail: false,
+            last_len: 0,
+            focused: false,
+            width: None,
+            height: None,
+            layout: None,
+            on_select: vec![],
+            on_activate: vec![],
+            id: None,
+            widget_id: None,
+            classes: vec![],
+            theme: None,
+            inline_style: None,
+        }
+    }
+
+    /// replace the source of the rows, the list is scrolled back to the top
+    pub fn set_source<S>(&mut self, source: S)
+    where
+        S: ListSource + 'static,
+    {
+        self.source = Box::new(source);
+        self.selected = None;
+        self.scroll_top = 0;
+        self.last_len = 0;
+    }
+
+    /// set whether the list stays scrolled to the end as rows are appended
+    pub fn set_follow_tail(&mut self, follow_tail: bool) {
+        self.follow_tail = follow_tail;
+    }
+
+    /// select the row at this index and scroll it into view
+    pub fn set_selected(&mut self, selected: Option<usize>) {
+        self.selected = selected.filter(|index| *index < self.source.len());
+        self.scroll_into_view();
+    }
+
+    /// returns the index of the selected row
+    pub fn get_selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// returns the index of the first visible row
+    pub fn scroll_top(&self) -> usize {
+        self.scroll_top
+    }
+
+    /// scroll the list so the row at this index is the first visible row
+    pub fn scroll_to(&mut self, index: usize) {
+        self.scroll_top = index;
+        self.scroll_by(0);
+    }
+
+    /// attach a listener which receives the index of the row selected by the user
+    pub fn add_select_listener(&mut self, cb: Callback<usize, MSG>) {
+        self.on_select.push(cb);
+    }
+
+    /// attach a listener which receives the index of the selected row,
+    /// when it is activated with Enter
+    pub fn add_activate_listener(&mut self, cb: Callback<usize, MSG>) {
+        self.on_activate.push(cb);
+    }
+
+    /// the number of rows inside of the border
+    fn visible_rows(&self) -> usize {
+        match self.layout {
+            Some(layout) => {
+                (layout.size.height.round() as usize).saturating_sub(2)
+            }
+            None => 0,
+        }
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        let max =
+            self.source.len().saturating_sub(self.visible_rows()) as isize;
+        self.scroll_top =
+            (self.scroll_top as isize + delta).min(max).max(0) as usize;
+    }
+
+    /// scroll the list so the selected row is visible
+    fn scroll_into_view(&mut self) {
+        if let Some(selected) = self.selected {
+            let visible = self.visible_rows().max(1);
+            if selected < self.scroll_top {
+                self.scroll_top = selected;
+            } else if selected >= self.scroll_top + visible {
+                self.scroll_top = selected + 1 - visible;
+            }
+        }
+        self.scroll_by(0);
+    }
+
+    /// follow the appended rows if the list was scrolled to the end
+    fn follow_appended_rows(&mut self) {
+        let len = self.source.len();
+        let visible = self.visible_rows();
+        if self.follow_tail && self.scroll_top + visible >= self.last_len {
+            self.scroll_top = len.saturating_sub(visible);
+        }
+        self.last_len = len;
+        self.scroll_by(0);
+    }
+
+    fn select(&mut self, index: usize) -> Vec<MSG> {
+        let len = self.source.len();
+        if len == 0 {
+            return vec![];
+        }
+        let index = index.min(len - 1);
+        self.set_selected(Some(index));
+        self.on_select.iter().map(|cb| cb.emit(index)).collect()
+    }
+
+    fn select_by(&mut self, delta: isize) -> Vec<MSG> {
+        let index = match self.selected {
+            Some(selected) => (selected as isize + delta).max(0) as usize,
+            None => self.scroll_top,
+        };
+        self.select(index)
+    }
+
+    fn process_key(&mut self, key_event: KeyEvent) -> (Vec<MSG>, bool) {
+        let page = self.visible_rows().max(1) as isize;
+        let msgs = match key_event.code {
+            KeyCode::Up => self.select_by(-1),
+            KeyCode::Down => self.select_by(1),
+            KeyCode::PageUp => self.select_by(-page),
+            KeyCode::PageDown => self.select_by(page),
+            KeyCode::Home => self.select(0),
+            KeyCode::End => self.select(self.source.len().saturating_sub(1)),
+            KeyCode::Enter => {
+                match self.selected {
+                    Some(selected) => {
+                        self.on_activate
+                            .iter()
+                            .map(|cb| cb.emit(selected))
+                            .collect()
+                    }
+                    None => vec![],
+                }
+            }
+            _ => return (vec![], false),
+        };
+        (msgs, true)
+    }
+
+    /// select the row at this line, counted from the top of this widget
+    fn click(&mut self, y: f32) -> (Vec<MSG>, bool) {
+        match self.row_at(y) {
+            Some(index) => (self.select(index), true),
+            None => (vec![], false),
+        }
+    }
+
+    /// the index of the row at this line, counted from the top of this widget
+    fn row_at(&self, y: f32) -> Option<usize> {
+        let line = y - 1.0;
+        if line < 0.0 || line as usize >= self.visible_rows() {
+            return None;
+        }
+        let index = self.scroll_top + line as usize;
+        if index < self.source.len() {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    fn draw_rows(
+        &self,
+        buf: &mut Buffer,
+        layout: &Layout,
+        style: &StateStyle,
+        theme: &Theme,
+    ) {
+        let left = layout.location.x.round() as usize + 1;
+        let top = layout.location.y.round() as usize + 1;
+        let inner_width =
+            (layout.size.width.round() as usize).saturating_sub(2);
+        let selected_style =
+            style.merge(&theme.accent_style()).merge(&StateStyle {
+                attributes: vec![Attribute::Reverse],
+                ..Default::default()
+            });
+        let end =
+            (self.scroll_top + self.visible_rows()).min(self.source.len());
+        for (line, index) in (self.scroll_top..end).enumerate() {
+            let y = top + line;
+            let row_style = if self.selected == Some(index) {
+                for x in left..left + inner_width {
+                    buf.set_cell(x, y, selected_style.cell(' '));
+                }
+                &selected_style
+            } else {
+                style
+            };
+            let mut x = left;
+            for mut cell in self.source.row(index, inner_width) {
+                let cell_width = cell.unicode_width().max(1);
+                if x + cell_width > left + inner_width {
+                    break;
+                }
+                if cell.foreground_color.is_none() {
+                    if let Some(fg) = row_style.foreground {
+                        cell.color(fg);
+                    }
+                }
+                if cell.background_color.is_none() {
+                    if let Some(bg) = row_style.background {
+                        cell.background(bg);
+                    }
+                }
+                cell.attributes(row_style.attributes.clone());
+                buf.set_cell(x, y, cell);
+                x += cell_width;
+            }
+        }
+    }
+}
+
+impl<MSG> fmt::Debug for VirtualList<MSG> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VirtualList")
+            .field("len", &self.source.len())
+            .field("selected", &self.selected)
+            .field("scroll_top", &self.scroll_top)
+            .field("follow_tail", &self.follow_tail)
+            .finish()
+    }
+}
+
+impl<MSG> Widget<MSG> for VirtualList<MSG>
+where
+    MSG: fmt::Debug + 'static,
+{
+    fn style(&self) -> Style {
+        Style {
+            size: Size {
+                width: if let Some(width) = self.width {
+                    Dimension::Points(width)
+                } else {
+                    Dimension::Percent(1.0)
+                },
+                height: if let Some(height) = self.height {
+                    Dimension::Points(height)
+                } else {
+                    Dimension::Percent(1.0)
+                },
+            },
+            min_size: Size {
+                width: Dimension::Points(3.0),
+                height: Dimension::Points(3.0),
+            },
+            ..Default::default()
+        }
+    }
+
+    fn draw(
+        &mut self,
+        buf: &mut Buffer,
+        layout_tree: &LayoutTree,
+        theme: &Theme,
+    ) -> Vec<Cmd> {
+        let layout = layout_tree.layout;
+        if layout.size.width < 3.0 || layout.size.height < 3.0 {
+            return vec![];
+        }
+        self.layout = Some(layout);
+        self.follow_appended_rows();
+
+        let left = layout.location.x.round() as usize;
+        let top = layout.location.y.round() as usize;
+        let right = left + layout.size.width.round() as usize - 1;
+        let bottom = top + layout.size.height.round() as usize - 1;
+        let style = theme.widget_style(self);
+        style.fill(buf, (left, top), (right, bottom));
+        style.draw_border(buf, (left, top), (right, bottom), false);
+        self.draw_rows(buf, &layout, &style, theme);
+        draw_scrollbar(
+            buf,
+            &style,
+            &layout,
+            self.scroll_top,
+            self.visible_rows(),
+            self.source.len(),
+        );
+        vec![]
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn set_size(&mut self, width: Option<f32>, height: Option<f32>) {
+        self.width = width;
+        self.height = height;
+    }
+
+    fn process_event(&mut self, event: Event) -> Vec<MSG> {
+        self.consume_event(event).0
+    }
+
+    fn consume_event(&mut self, event: Event) -> (Vec<MSG>, bool) {
+        match event {
+            Event::Key(key_event) => self.process_key(key_event),
+            // without the origin of a propagated event,
+            // the mouse location is taken as relative to the parent
+            Event::Mouse(MouseEvent::Down(MouseButton::Left, _x, y, _)) => {
+                match self.layout {
+                    Some(layout) => {
+                        self.click(y as f32 - layout.location.y.round())
+                    }
+                    None => (vec![], false),
+                }
+            }
+            Event::Mouse(MouseEvent::ScrollDown(..)) => {
+                self.scroll_by(1);
+                (vec![], true)
+            }
+            Event::Mouse(MouseEvent::ScrollUp(..)) => {
+                self.scroll_by(-1);
+                (vec![], true)
+            }
+            _ => (vec![], false),
+        }
+    }
+
+    fn handle_event(&mut self, event: &mut WidgetEvent) -> Vec<MSG> {
+        if event.phase == Phase::Capture || event.is_default_prevented() {
+            return vec![];
+        }
+        let (msgs, consumed) = match event.event {
+            Event::Mouse(MouseEvent::Down(MouseButton::Left, _x, y, _)) => {
+                self.click(y as f32 - event.origin.1.round())
+            }
+            _ => self.consume_event(event.event),
+        };
+        if consumed {
+            event.stop_propagation();
+        }
+        msgs
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.id = Some(id.to_string());
+    }
+
+    fn get_id(&self) -> &Option<String> {
+        &self.id
+    }
+
+    fn set_widget_id(&mut self, widget_id: WidgetId) {
+        self.widget_id = Some(widget_id);
+    }
+
+    fn get_widget_id(&self) -> Option<WidgetId> {
+        self.widget_id
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.classes.push(class.to_string());
+    }
+
+    fn remove_class(&mut self, class: &str) {
+        self.classes.retain(|c| c != class);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    fn get_theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
+
+    fn set_inline_style(&mut self, style: InlineStyle) {
+        self.inline_style = Some(style);
+    }
+
+    fn get_inline_style(&self) -> Option<&InlineStyle> {
+        self.inline_style.as_ref()
+    }
+
+    fn get_tag(&self) -> &'static str {
+        "virtuallist"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        layout,
+        theme,
+        widget_event::WidgetEvent,
+    };
+    use crossterm::event::KeyModifiers;
+    use stretch::{
+        geometry::Size,
+        number::Number,
+    };
+
+    /// a source with a row for each number, which keeps the rows that are asked for
+    #[derive(Default)]
+    struct Numbers {
+        len: usize,
+        requested: RefCell<Vec<usize>>,
+    }
+
+    impl ListSource for Numbers {
+        fn len(&self) -> usize {
+            self.len
+        }
+
+        fn row(&self, index: usize, width: usize) -> Vec<Cell> {
+            self.requested.borrow_mut().push(index);
+            format!("row {}", index)
+                .chars()
+                .take(width)
+                .map(Cell::new)
+                .collect()
+        }
+    }
+
+    fn draw(list: &mut VirtualList<()>) -> Vec<String> {
+        let layout_tree = layout::compute_layout(
+            list,
+            Size {
+                width: Number::Defined(12.0),
+                height: Number::Defined(5.0),
+            },
+        );
+        let mut buf = Buffer::new(12, 5);
+        theme::draw_widget(list, &mut buf, &layout_tree, &Theme::plain());
+        buf.to_plain_text()
+            .lines()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn only_visible_rows_are_drawn() {
+        let numbers = Rc::new(RefCell::new(Numbers {
+            len: 100_000,
+            ..Default::default()
+        }));
+        let mut list = VirtualList::new(numbers.clone());
+        list.scroll_to(50_000);
+        let lines = draw(&mut list);
+        assert_eq!(lines[1], "│row 50000 │");
+        // the thumb of the scrollbar is in the middle
+        assert_eq!(lines[2], "│row 50001 ▇");
+        assert_eq!(lines[3], "│row 50002 │");
+        assert_eq!(
+            *numbers.borrow().requested.borrow(),
+            vec![50_000, 50_001, 50_002]
+        );
+    }
+
+    #[test]
+    fn follow_tail() {
+        let logs = Rc::new(RefCell::new(
+            (0..10)
+                .map(|i| format!("log {}", i))
+                .collect::<Vec<String>>(),
+        ));
+        let mut list = VirtualList::new(logs.clone());
+        list.set_follow_tail(true);
+        let lines = draw(&mut list);
+        assert_eq!(lines[3], "│log 9     ▇");
+
+        logs.borrow_mut().push("log 10".to_string());
+        let lines = draw(&mut list);
+        assert_eq!(lines[3], "│log 10    ▇");
+
+        // scrolling up stops following the appended rows
+        list.process_event(Event::Mouse(MouseEvent::ScrollUp(
+            1,
+            1,
+            KeyModifiers::empty(),
+        )));
+        logs.borrow_mut().push("log 11".to_string());
+        let lines = draw(&mut list);
+        assert_eq!(list.scroll_top(), 7);
+        assert_eq!(lines[1], "│log 7     │");
+    }
+
+    #[test]
+    fn click_at_the_origin_of_the_event() {
+        let numbers = Rc::new(RefCell::new(Numbers {
+            len: 10,
+            ..Default::default()
+        }));
+        let mut list = VirtualList::<()>::new(numbers);
+        draw(&mut list);
+        // the list is nested, its absolute location differs from its layout
+        let click = Event::Mouse(MouseEvent::Down(
+            MouseButton::Left,
+            3,
+            12,
+            KeyModifiers::empty(),
+        ));
+        let mut event = WidgetEvent::new(click, vec![]);
+        event.phase = Phase::Target;
+        event.origin = (0.0, 10.0);
+        list.handle_event(&mut event);
+        assert_eq!(list.get_selected(), Some(1));
+        assert!(event.is_propagation_stopped());
+    }
+}
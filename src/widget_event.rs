@@ -0,0 +1,204 @@
+//! Events are propagated through the widget tree in the same way as the DOM:
+//! first the capture phase from the root down to the parent of the target,
+//! then the target itself, and then the bubble phase back up to the root.
+//!
+//! Any widget along the path can stop the propagation to the rest of the widgets,
+//! or prevent the default handling of the event by the widgets after it.
+use crate::{
+    find_node::find_widget_by_widget_id_mut,
+    widget_id::WidgetId,
+    Widget,
+};
+use crossterm::event::Event;
+use std::collections::HashMap;
+use stretch::result::Layout;
+
+/// The phase of the propagation the event is in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    /// the event is going down from the root to the parent of the target
+    Capture,
+    /// the event is at the target widget
+    Target,
+    /// the event is going up from the parent of the target to the root
+    Bubble,
+}
+
+/// Synthesized by the renderer when the mouse pointer moves onto or off a widget.
+/// Unlike the terminal events, these are sent only to the widget itself and are not propagated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HoverEvent {
+    /// the pointer moved onto the widget or one of its children
+    MouseEnter,
+    /// the pointer moved off the widget and all of its children
+    MouseLeave,
+}
+
+/// An event that is propagated through the widgets in the path to the target
+#[derive(Debug, Clone, PartialEq)]
+pub struct WidgetEvent {
+    /// the terminal event
+    pub event: Event,
+    /// the current phase of the propagation
+    pub phase: Phase,
+    /// the id of the widgets from the root to the target
+    pub path: Vec<WidgetId>,
+    /// the id of the widget that is currently handling the event
+    pub current: Option<WidgetId>,
+    /// the absolute location of the widget that is currently handling the event,
+    /// subtract it from the location of a mouse event to get the location inside the widget
+    pub origin: (f32, f32),
+    propagation_stopped: bool,
+    default_prevented: bool,
+}
+
+impl WidgetEvent {
+    /// create an event which is to be propagated along this path,
+    /// the last node in the path is the target.
+    pub fn new(event: Event, path: Vec<WidgetId>) -> Self {
+        let current = path.first().copied();
+        WidgetEvent {
+            event,
+            phase: Phase::Capture,
+            path,
+            current,
+            origin: (0.0, 0.0),
+            propagation_stopped: false,
+            default_prevented: false,
+        }
+    }
+
+    /// the id of the widget the event is meant for
+    pub fn target(&self) -> Option<WidgetId> {
+        self.path.last().copied()
+    }
+
+    /// stop the event from reaching the rest of the widgets in the path
+    pub fn stop_propagation(&mut self) {
+        self.propagation_stopped = true;
+    }
+
+    /// returns true if a widget has stopped the propagation of this event
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.propagation_stopped
+    }
+
+    /// prevent the widgets after the current one from doing their default handling of the
+    /// event, such as a checkbox toggling its state, while still receiving it.
+    pub fn prevent_default(&mut self) {
+        self.default_prevented = true;
+    }
+
+    /// returns true if a widget has prevented the default handling of this event
+    pub fn is_default_prevented(&self) -> bool {
+        self.default_prevented
+    }
+
+    /// the order in which the widgets receive the event with their phase
+    pub(crate) fn route(&self) -> Vec<(WidgetId, Phase)> {
+        let mut route = vec![];
+        if let Some((target, ancestors)) = self.path.split_last() {
+            route.extend(ancestors.iter().map(|idx| (*idx, Phase::Capture)));
+            route.push((*target, Phase::Target));
+            route.extend(
+                ancestors.iter().rev().map(|idx| (*idx, Phase::Bubble)),
+            );
+        }
+        route
+    }
+}
+
+/// propagate the event through the widgets in its path and returns the msgs they emit.
+/// The layouts are the absolute layouts of the widgets in the tree of the root,
+/// which are offset by the location of the root.
+pub(crate) fn propagate<MSG>(
+    root: &mut dyn Widget<MSG>,
+    widget_event: &mut WidgetEvent,
+    layouts: &HashMap<WidgetId, Layout>,
+    offset: (f32, f32),
+) -> Vec<MSG> {
+    let mut msgs = vec![];
+    for (widget_id, phase) in widget_event.route() {
+        if widget_event.is_propagation_stopped() {
+            break;
+        }
+        widget_event.phase = phase;
+        widget_event.current = Some(widget_id);
+        widget_event.origin = layouts
+            .get(&widget_id)
+            .map(|layout| {
+                (offset.0 + layout.location.x, offset.1 + layout.location.y)
+            })
+            .unwrap_or(offset);
+        // disabled widgets are passed over, the event still reaches the rest of the path
+        if let Some(widget) = find_widget_by_widget_id_mut(root, widget_id)
+            .filter(|widget| !widget.is_disabled())
+        {
+            msgs.extend(widget.handle_event(widget_event));
+        }
+    }
+    msgs
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        Button,
+        FlexBox,
+    };
+    use crossterm::event::{
+        KeyCode,
+        KeyModifiers,
+        MouseButton,
+        MouseEvent,
+    };
+    use sauron_vdom::Callback;
+
+    #[test]
+    fn route() {
+        let (root, parent, target) =
+            (WidgetId::next(), WidgetId::next(), WidgetId::next());
+        let event = WidgetEvent::new(
+            Event::Key(KeyCode::Enter.into()),
+            vec![root, parent, target],
+        );
+        assert_eq!(event.target(), Some(target));
+        assert_eq!(
+            event.route(),
+            vec![
+                (root, Phase::Capture),
+                (parent, Phase::Capture),
+                (target, Phase::Target),
+                (parent, Phase::Bubble),
+                (root, Phase::Bubble),
+            ]
+        );
+    }
+
+    #[test]
+    fn disabled_widgets_are_skipped() {
+        let mut root = FlexBox::new();
+        let mut button = Button::new("ok");
+        button.add_click_listener(Callback::from(|_| "clicked"));
+        root.add_child(Box::new(button));
+        crate::widget_id::assign_widget_ids(&mut root);
+        let path = vec![
+            root.get_widget_id().unwrap(),
+            root.children().unwrap()[0].get_widget_id().unwrap(),
+        ];
+        let click = Event::Mouse(MouseEvent::Down(
+            MouseButton::Left,
+            1,
+            1,
+            KeyModifiers::empty(),
+        ));
+        let propagate_click = |root: &mut FlexBox<&'static str>| {
+            let mut widget_event = WidgetEvent::new(click, path.clone());
+            propagate(root, &mut widget_event, &HashMap::new(), (0.0, 0.0))
+        };
+        assert_eq!(propagate_click(&mut root), vec!["clicked"]);
+        root.child_mut(0).unwrap().set_disabled(true);
+        assert!(propagate_click(&mut root).is_empty());
+    }
+}
@@ -0,0 +1,97 @@
+//! Widgets are identified by a `WidgetId` which is assigned when the widget is inserted
+//! into a container, or when the tree is laid out for the widgets which are not inserted,
+//! such as the root widget.
+//!
+//! Unlike the depth first node index, the id of a widget stays the same when the widgets
+//! before it are added or removed, so it can be held across the dispatch of the msgs.
+use crate::Widget;
+use std::{
+    collections::HashSet,
+    fmt,
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+};
+
+static NEXT_WIDGET_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A unique id of a widget in the tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WidgetId(u64);
+
+impl WidgetId {
+    /// generate a new id which is not used by any other widget
+    pub(crate) fn next() -> Self {
+        WidgetId(NEXT_WIDGET_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl fmt::Display for WidgetId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// assign a new id to this widget if it doesn't have one yet
+pub(crate) fn ensure_widget_id<MSG>(widget: &mut dyn Widget<MSG>) {
+    if widget.get_widget_id().is_none() {
+        widget.set_widget_id(WidgetId::next());
+    }
+}
+
+/// assign an id to all the widgets in the tree that doesn't have one yet.
+/// Widgets which are cloned from an inserted widget carry the same id,
+/// so the duplicates are also given a new id.
+pub(crate) fn assign_widget_ids<MSG>(root: &mut dyn Widget<MSG>) {
+    assign_unique(root, &mut HashSet::new())
+}
+
+fn assign_unique<MSG>(
+    widget: &mut dyn Widget<MSG>,
+    seen: &mut HashSet<WidgetId>,
+) {
+    match widget.get_widget_id() {
+        Some(widget_id) if seen.insert(widget_id) => (),
+        _ => {
+            let widget_id = WidgetId::next();
+            widget.set_widget_id(widget_id);
+            seen.insert(widget_id);
+        }
+    }
+    if let Some(children) = widget.children_mut() {
+        for child in children.iter_mut() {
+            assign_unique(child.as_mut(), seen);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn unique_ids() {
+        let mut root = FlexBox::<()>::new();
+        root.add_child(Box::new(Button::new("hello")));
+        let inserted = root.children().unwrap()[0].get_widget_id();
+        assert!(inserted.is_some());
+        // the clone carries the same id as the inserted button
+        let clone = root.children().unwrap()[0]
+            .as_any()
+            .downcast_ref::<Button<()>>()
+            .unwrap()
+            .clone();
+        root.add_child(Box::new(clone));
+        assert_eq!(root.children().unwrap()[1].get_widget_id(), inserted);
+        assert!(root.get_widget_id().is_none());
+
+        assign_widget_ids(&mut root);
+        let children = root.children().unwrap();
+        assert!(root.get_widget_id().is_some());
+        assert_eq!(children[0].get_widget_id(), inserted);
+        assert!(children[1].get_widget_id().is_some());
+        assert_ne!(children[1].get_widget_id(), inserted);
+    }
+}